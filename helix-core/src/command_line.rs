@@ -257,11 +257,20 @@ pub enum ExpansionKind {
     ///
     /// For example `%reg{a}`.
     Register,
+    /// Execute a language server command and insert its result.
+    ///
+    /// For example `%lsp{rust-analyzer.analyzerStatus}`.
+    Lsp,
 }
 
 impl ExpansionKind {
-    pub const VARIANTS: &'static [Self] =
-        &[Self::Variable, Self::Unicode, Self::Shell, Self::Register];
+    pub const VARIANTS: &'static [Self] = &[
+        Self::Variable,
+        Self::Unicode,
+        Self::Shell,
+        Self::Register,
+        Self::Lsp,
+    ];
 
     pub const fn as_str(&self) -> &'static str {
         match self {
@@ -269,6 +278,7 @@ pub const fn as_str(&self) -> &'static str {
             Self::Unicode => "u",
             Self::Shell => "sh",
             Self::Register => "reg",
+            Self::Lsp => "lsp",
         }
     }
 
@@ -278,6 +288,7 @@ pub fn from_kind(name: &str) -> Option<Self> {
             "u" => Some(Self::Unicode),
             "sh" => Some(Self::Shell),
             "reg" => Some(Self::Register),
+            "lsp" => Some(Self::Lsp),
             _ => None,
         }
     }
@@ -0,0 +1,264 @@
+//! Parsing and resolving the conflict markers git leaves in files that failed to merge.
+
+use std::ops::Range;
+
+use ropey::RopeSlice;
+
+use crate::transaction::Change;
+
+/// A conflict between two versions of a block of lines. All fields are (0-based) line numbers.
+///
+/// ```text
+/// <<<<<<< ours
+/// our version
+/// ||||||| base
+/// version of the common ancestor, only written by the `diff3` and `zdiff3` conflict styles
+/// =======
+/// their version
+/// >>>>>>> theirs
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Conflict {
+    /// The line of the `<<<<<<<` marker.
+    pub start: usize,
+    /// The line of the `|||||||` marker, if the common ancestor is included.
+    pub base: Option<usize>,
+    /// The line of the `=======` marker.
+    pub separator: usize,
+    /// The line of the `>>>>>>>` marker.
+    pub end: usize,
+}
+
+/// Which versions of the lines a conflict is resolved to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resolution {
+    Ours,
+    Theirs,
+    /// Our version followed by their version.
+    Both,
+}
+
+impl Conflict {
+    /// The lines of our version.
+    pub fn ours(&self) -> Range<usize> {
+        self.start + 1..self.base.unwrap_or(self.separator)
+    }
+
+    /// The lines of the version of the common ancestor.
+    pub fn base_lines(&self) -> Option<Range<usize>> {
+        self.base.map(|base| base + 1..self.separator)
+    }
+
+    /// The lines of their version.
+    pub fn theirs(&self) -> Range<usize> {
+        self.separator + 1..self.end
+    }
+
+    /// All lines of the conflict including the markers.
+    pub fn lines(&self) -> Range<usize> {
+        self.start..self.end + 1
+    }
+
+    pub fn contains_line(&self, line: usize) -> bool {
+        self.lines().contains(&line)
+    }
+
+    /// The lines of the version that `line` is part of. The marker lines belong to the version
+    /// after them, apart from the `>>>>>>>` marker which belongs to their version.
+    pub fn version_at(&self, line: usize) -> Range<usize> {
+        if line < self.ours().end {
+            self.ours()
+        } else if line < self.separator {
+            self.base_lines().unwrap_or_else(|| self.ours())
+        } else {
+            self.theirs()
+        }
+    }
+
+    /// Returns the change that replaces the whole conflict with the lines of `resolution`.
+    pub fn resolve(&self, text: RopeSlice, resolution: Resolution) -> Change {
+        let slice = |lines: Range<usize>| {
+            text.slice(text.line_to_char(lines.start)..text.line_to_char(lines.end))
+        };
+        let mut resolved = String::new();
+        if resolution != Resolution::Theirs {
+            resolved.extend(slice(self.ours()).chunks());
+        }
+        if resolution != Resolution::Ours {
+            resolved.extend(slice(self.theirs()).chunks());
+        }
+        let lines = self.lines();
+        (
+            text.line_to_char(lines.start),
+            text.line_to_char(lines.end),
+            (!resolved.is_empty()).then(|| resolved.into()),
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Marker {
+    Start,
+    Base,
+    Separator,
+    End,
+}
+
+/// Returns the conflict marker the line starts with, if any. Markers are exactly seven
+/// characters long and followed by a space, for the label of the version, or the end of the line.
+fn marker(line: RopeSlice) -> Option<Marker> {
+    let mut chars = line.chars();
+    let first = chars.next()?;
+    let marker = match first {
+        '<' => Marker::Start,
+        '|' => Marker::Base,
+        '=' => Marker::Separator,
+        '>' => Marker::End,
+        _ => return None,
+    };
+    if !chars.by_ref().take(6).all(|ch| ch == first) {
+        return None;
+    }
+    match chars.next() {
+        None | Some('\n' | '\r') => Some(marker),
+        Some(' ') if marker != Marker::Separator => Some(marker),
+        _ => None,
+    }
+}
+
+/// Finds all conflicts in `text`, in ascending order. Markers that aren't part of a complete
+/// conflict are ignored.
+pub fn find_conflicts(text: RopeSlice) -> Vec<Conflict> {
+    let mut conflicts = Vec::new();
+    let mut start = None;
+    let mut base = None;
+    let mut separator = None;
+    for (line, content) in text.lines().enumerate() {
+        match (marker(content), start, separator) {
+            (Some(Marker::Start), _, _) => {
+                start = Some(line);
+                base = None;
+                separator = None;
+            }
+            (Some(Marker::Base), Some(_), None) if base.is_none() => base = Some(line),
+            (Some(Marker::Separator), Some(_), None) => separator = Some(line),
+            (Some(Marker::End), Some(start_line), Some(separator_line)) => {
+                conflicts.push(Conflict {
+                    start: start_line,
+                    base,
+                    separator: separator_line,
+                    end: line,
+                });
+                start = None;
+                base = None;
+                separator = None;
+            }
+            _ => (),
+        }
+    }
+    conflicts
+}
+
+/// Returns the conflict containing `line`.
+pub fn conflict_at(conflicts: &[Conflict], line: usize) -> Option<&Conflict> {
+    let idx = conflicts.partition_point(|conflict| conflict.end < line);
+    conflicts
+        .get(idx)
+        .filter(|conflict| conflict.contains_line(line))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Rope, Transaction};
+
+    const TEXT: &str = "\
+a
+<<<<<<< HEAD
+ours
+=======
+theirs 1
+theirs 2
+>>>>>>> branch
+b
+<<<<<<< HEAD
+ours
+||||||| base
+base
+=======
+>>>>>>> branch
+";
+
+    #[test]
+    fn find() {
+        let conflicts = find_conflicts(Rope::from(TEXT).slice(..));
+        assert_eq!(
+            conflicts,
+            [
+                Conflict {
+                    start: 1,
+                    base: None,
+                    separator: 3,
+                    end: 6,
+                },
+                Conflict {
+                    start: 8,
+                    base: Some(10),
+                    separator: 12,
+                    end: 13,
+                },
+            ]
+        );
+        assert_eq!(conflicts[0].ours(), 2..3);
+        assert_eq!(conflicts[0].theirs(), 4..6);
+        assert_eq!(conflicts[1].ours(), 9..10);
+        assert_eq!(conflicts[1].base_lines(), Some(11..12));
+        assert_eq!(conflicts[1].theirs(), 13..13);
+        assert_eq!(conflicts[1].version_at(10), 11..12);
+        assert_eq!(conflict_at(&conflicts, 7), None);
+        assert_eq!(conflict_at(&conflicts, 13), Some(&conflicts[1]));
+    }
+
+    #[test]
+    fn ignore_incomplete_markers() {
+        let text = Rope::from("<<<<<<< HEAD\n========\n>>>>>>>> branch\n=======\n>>>>>>>\n");
+        let conflicts = find_conflicts(text.slice(..));
+        assert_eq!(
+            conflicts,
+            [Conflict {
+                start: 0,
+                base: None,
+                separator: 3,
+                end: 4,
+            }]
+        );
+        assert!(find_conflicts(Rope::from("=======\n>>>>>>> a\n").slice(..)).is_empty());
+    }
+
+    #[test]
+    fn resolve() {
+        let text = Rope::from(TEXT);
+        let conflicts = find_conflicts(text.slice(..));
+        let resolve = |resolutions: [Resolution; 2]| {
+            let changes = conflicts
+                .iter()
+                .zip(resolutions)
+                .map(|(conflict, resolution)| conflict.resolve(text.slice(..), resolution));
+            let mut doc = text.clone();
+            Transaction::change(&text, changes).apply(&mut doc);
+            doc.to_string()
+        };
+        assert_eq!(
+            resolve([Resolution::Ours, Resolution::Ours]),
+            "a\nours\nb\nours\n"
+        );
+        assert_eq!(
+            resolve([Resolution::Theirs, Resolution::Theirs]),
+            "a\ntheirs 1\ntheirs 2\nb\n"
+        );
+        assert_eq!(
+            resolve([Resolution::Both, Resolution::Both]),
+            "a\nours\ntheirs 1\ntheirs 2\nb\nours\n"
+        );
+    }
+}
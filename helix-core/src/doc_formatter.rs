@@ -173,6 +173,7 @@ fn default() -> Self {
 pub struct DocumentFormatter<'t> {
     text_fmt: &'t TextFormat,
     annotations: &'t TextAnnotations<'t>,
+    text: RopeSlice<'t>,
 
     /// The visual position at the end of the last yielded word boundary
     visual_pos: Position,
@@ -212,13 +213,19 @@ pub fn new_at_prev_checkpoint(
         char_idx: usize,
     ) -> Self {
         // TODO divide long lines into blocks to avoid bad performance for long lines
-        let block_line_idx = text.char_to_line(char_idx.min(text.len_chars()));
-        let block_char_idx = text.line_to_char(block_line_idx);
+        let mut block_line_idx = text.char_to_line(char_idx.min(text.len_chars()));
+        let mut block_char_idx = text.line_to_char(block_line_idx);
+        // lines hidden by a fold are part of the block of the fold's first line
+        if let Some(fold) = annotations.fold_hiding(block_char_idx) {
+            block_line_idx = text.char_to_line(fold.start);
+            block_char_idx = text.line_to_char(block_line_idx);
+        }
         annotations.reset_pos(block_char_idx);
 
         DocumentFormatter {
             text_fmt,
             annotations,
+            text,
             visual_pos: Position { row: 0, col: 0 },
             graphemes: text.slice(block_char_idx..).graphemes(),
             char_pos: block_char_idx,
@@ -258,12 +265,28 @@ fn next_inline_annotation_grapheme(
         }
     }
 
+    /// Returns the next grapheme of the document text and the number of chars it spans.
+    ///
+    /// If a closed fold starts at `char_pos` the folded text is skipped and attributed to the
+    /// line ending that ends the fold, so the fold collapses into the line it starts on.
+    fn next_document_grapheme(&mut self, char_pos: usize) -> Option<(RopeSlice<'t>, usize)> {
+        let Some(fold) = self.annotations.fold_at(char_pos) else {
+            let grapheme = self.graphemes.next()?;
+            return Some((grapheme, grapheme.len_chars()));
+        };
+        self.graphemes = self.text.slice(fold.end..).graphemes();
+        let grapheme = self.graphemes.next()?;
+        let next_char_pos = fold.end + grapheme.len_chars();
+        self.annotations.skip_folded(next_char_pos);
+        Some((grapheme, next_char_pos - char_pos))
+    }
+
     fn advance_grapheme(&mut self, col: usize, char_pos: usize) -> Option<GraphemeWithSource<'t>> {
         let (grapheme, source) =
             if let Some((grapheme, highlight)) = self.next_inline_annotation_grapheme(char_pos) {
                 (grapheme.into(), GraphemeSource::VirtualText { highlight })
-            } else if let Some(grapheme) = self.graphemes.next() {
-                let codepoints = grapheme.len_chars() as u32;
+            } else if let Some((grapheme, codepoints)) = self.next_document_grapheme(char_pos) {
+                let codepoints = codepoints as u32;
 
                 let overlay = self.annotations.overlay_at(char_pos);
                 let grapheme = match overlay {
@@ -469,7 +492,8 @@ fn next(&mut self) -> Option<Self::Item> {
             self.visual_pos.row += 1 + virtual_lines;
             self.visual_pos.col = 0;
             if !grapheme.is_virtual() {
-                self.line_pos += 1;
+                // a line ending may close a fold that spans multiple lines
+                self.line_pos = self.text.char_to_line(self.char_pos);
             }
         } else {
             self.visual_pos.col += grapheme.width();
@@ -1,4 +1,5 @@
 use crate::doc_formatter::{DocumentFormatter, TextFormat};
+use crate::fold::Fold;
 use crate::text_annotations::{InlineAnnotation, Overlay, TextAnnotations};
 
 impl TextFormat {
@@ -211,3 +212,55 @@ fn annotation_and_overlay() {
         "fooo  bar "
     );
 }
+
+fn fold_text(
+    text: &str,
+    char_pos: usize,
+    folds: &[Fold],
+    annotations: &[InlineAnnotation],
+) -> String {
+    DocumentFormatter::new_at_prev_checkpoint(
+        text.into(),
+        &TextFormat::new_test(false),
+        TextAnnotations::default()
+            .add_inline_annotations(annotations, None)
+            .add_folds(folds),
+        char_pos,
+    )
+    .collect_to_str()
+}
+
+#[test]
+fn fold() {
+    let text = "a {\n  b\n  c\n}\nd\n";
+    let fold = Fold::from_lines(text.into(), 0, 2).unwrap();
+    assert_eq!(fold_text(text, 0, &[fold], &[]), "a { \n} \nd \n ");
+    // annotations inside the fold are skipped
+    assert_eq!(
+        fold_text(
+            text,
+            0,
+            &[fold],
+            &[
+                InlineAnnotation::new(fold.start, "…"),
+                InlineAnnotation::new(5, "x"),
+                InlineAnnotation::new(13, "y"),
+            ]
+        ),
+        "a {… \n}y \nd \n "
+    );
+    // starting inside a fold starts at the first line of the fold
+    assert_eq!(fold_text(text, 6, &[fold], &[]), "a { \n} \nd \n ");
+
+    let folds = [fold];
+    let mut annotations = TextAnnotations::default();
+    annotations.add_folds(&folds);
+    let text_fmt = TextFormat::new_test(false);
+    let formatter =
+        DocumentFormatter::new_at_prev_checkpoint(text.into(), &text_fmt, &annotations, 0);
+    let lines: Vec<_> = formatter
+        .filter(|grapheme| grapheme.visual_pos.col == 0)
+        .map(|grapheme| (grapheme.line_idx, grapheme.char_idx))
+        .collect();
+    assert_eq!(lines, [(0, 0), (3, 12), (4, 14), (5, 16)]);
+}
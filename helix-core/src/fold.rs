@@ -0,0 +1,241 @@
+//! Code folding.
+//!
+//! A [`Fold`] is a range of whole lines that can be collapsed into the line it starts on. Folds
+//! are computed from LSP `textDocument/foldingRange` responses or, as a fallback, from the
+//! `@fold` captures of the `folds.scm` tree-sitter queries.
+
+use std::cmp::Reverse;
+
+use crate::{
+    line_ending::{get_line_ending, line_end_char_index},
+    syntax::{Loader, QueryIterEvent, Syntax},
+    Assoc, ChangeSet, RopeSlice,
+};
+
+/// A foldable region of a document.
+///
+/// Folds always span whole lines: `start` is the char index of the line ending of the first line
+/// of the region and `end` is the char index of the line ending of the last line. The first line
+/// stays visible when the fold is closed (it acts as the header of the fold) and everything in
+/// `start..end` is hidden, so the header is directly followed by the line after the fold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Fold {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Fold {
+    /// Creates a fold that spans the lines `start_line..=end_line`.
+    ///
+    /// Returns `None` if the fold would not hide any lines. The last line of a document has
+    /// no line ending that could be kept visible so it is never included in a fold.
+    pub fn from_lines(text: RopeSlice, start_line: usize, end_line: usize) -> Option<Self> {
+        let mut end_line = end_line.min(text.len_lines() - 1);
+        if get_line_ending(&text.line(end_line)).is_none() {
+            end_line = end_line.checked_sub(1)?;
+        }
+        if end_line <= start_line {
+            return None;
+        }
+
+        Some(Self {
+            start: line_end_char_index(&text, start_line),
+            end: line_end_char_index(&text, end_line),
+        })
+    }
+
+    /// The line that stays visible when the fold is closed.
+    pub fn start_line(&self, text: RopeSlice) -> usize {
+        text.char_to_line(self.start)
+    }
+
+    /// The last line hidden by the fold.
+    pub fn end_line(&self, text: RopeSlice) -> usize {
+        text.char_to_line(self.end)
+    }
+
+    /// The number of lines hidden when the fold is closed.
+    pub fn hidden_lines(&self, text: RopeSlice) -> usize {
+        self.end_line(text) - self.start_line(text)
+    }
+
+    /// Whether the char at `pos` is hidden when the fold is closed.
+    pub fn hides(&self, pos: usize) -> bool {
+        self.start < pos && pos <= self.end
+    }
+
+    /// Whether `line` is part of the fold, including its header line.
+    pub fn contains_line(&self, text: RopeSlice, line: usize) -> bool {
+        (self.start_line(text)..=self.end_line(text)).contains(&line)
+    }
+
+    /// Maps the fold through `changes` and snaps it back to line boundaries of the changed
+    /// `text`. Returns `None` if the fold no longer hides any lines.
+    pub fn map(self, text: RopeSlice, changes: &ChangeSet) -> Option<Self> {
+        let start = changes.map_pos(self.start, Assoc::Before);
+        let end = changes.map_pos(self.end, Assoc::Before);
+        Self::from_lines(text, text.char_to_line(start), text.char_to_line(end))
+    }
+}
+
+/// Sorts `folds` so that outer folds come before the folds nested inside them and removes
+/// duplicates. All other functions in this module expect folds in this order.
+pub fn sort(folds: &mut Vec<Fold>) {
+    folds.sort_unstable_by_key(|fold| (fold.start, Reverse(fold.end)));
+    folds.dedup();
+}
+
+/// Returns the folds that are not hidden by another fold in `folds`.
+///
+/// This is the set of folds that needs to be skipped when rendering a document with all of
+/// `folds` closed.
+pub fn outermost(folds: &[Fold]) -> Vec<Fold> {
+    let mut outermost: Vec<Fold> = Vec::new();
+    for &fold in folds {
+        match outermost.last() {
+            Some(last) if fold.start <= last.end => (),
+            _ => outermost.push(fold),
+        }
+    }
+    outermost
+}
+
+/// Returns the nesting level of each fold in `folds`. Folds that are not contained in any other
+/// fold have level 1.
+pub fn levels(folds: &[Fold]) -> Vec<usize> {
+    let mut parents: Vec<Fold> = Vec::new();
+    folds
+        .iter()
+        .map(|&fold| {
+            while parents.last().is_some_and(|parent| parent.end < fold.end) {
+                parents.pop();
+            }
+            parents.push(fold);
+            parents.len()
+        })
+        .collect()
+}
+
+/// Returns the innermost fold in `folds` that contains `line`, skipping the folds for which
+/// `skip` returns `true`.
+pub fn innermost_at_line(
+    folds: &[Fold],
+    text: RopeSlice,
+    line: usize,
+    skip: impl Fn(&Fold) -> bool,
+) -> Option<Fold> {
+    folds
+        .iter()
+        .filter(|fold| !skip(fold) && fold.contains_line(text, line))
+        .min_by_key(|fold| fold.end - fold.start)
+        .copied()
+}
+
+/// Computes the folds for a document from the `@fold` captures of the `folds.scm` query of each
+/// injection layer.
+pub fn treesitter_folds(syntax: &Syntax, text: RopeSlice, loader: &Loader) -> Vec<Fold> {
+    let mut folds = Vec::new();
+    let mut iter = syntax.folds(text, loader, ..);
+    while let Some(event) = iter.next() {
+        let QueryIterEvent::Match(mat) = event else {
+            continue;
+        };
+        let query = &loader
+            .fold_query(iter.current_language())
+            .expect("must have a folds query to emit matches")
+            .query;
+        if query.capture_name(mat.capture) != "fold" {
+            continue;
+        }
+
+        let start = text.byte_to_char(mat.node.start_byte() as usize);
+        // nodes that end with a line ending don't extend into the next line
+        let end = text
+            .byte_to_char(mat.node.end_byte() as usize)
+            .saturating_sub(1)
+            .max(start);
+        if let Some(fold) = Fold::from_lines(text, text.char_to_line(start), text.char_to_line(end))
+        {
+            folds.push(fold);
+        }
+    }
+    sort(&mut folds);
+    folds
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Rope, Selection, Transaction};
+
+    fn fold(text: &Rope, start_line: usize, end_line: usize) -> Fold {
+        Fold::from_lines(text.slice(..), start_line, end_line).unwrap()
+    }
+
+    #[test]
+    fn from_lines() {
+        let text = Rope::from("fn a() {\n    b\n}\n");
+        let slice = text.slice(..);
+        assert_eq!(fold(&text, 0, 2), Fold { start: 8, end: 16 });
+        assert_eq!(fold(&text, 0, 2).hidden_lines(slice), 2);
+        // the empty last line has no line ending
+        assert_eq!(fold(&text, 0, 3), fold(&text, 0, 2));
+        assert_eq!(Fold::from_lines(slice, 1, 1), None);
+        assert_eq!(Fold::from_lines(slice, 2, 3), None);
+
+        let text = Rope::from("a\nb");
+        assert_eq!(Fold::from_lines(text.slice(..), 0, 1), None);
+    }
+
+    #[test]
+    fn hides() {
+        let text = Rope::from("a\nb\nc\nd\n");
+        let fold = fold(&text, 0, 2);
+        assert!(!fold.hides(0));
+        assert!(!fold.hides(1));
+        assert!(fold.hides(2));
+        assert!(fold.hides(5));
+        assert!(!fold.hides(6));
+    }
+
+    #[test]
+    fn outermost_and_levels() {
+        let text = Rope::from("a\nb\nc\nd\ne\nf\ng\n");
+        let mut folds = vec![
+            fold(&text, 4, 5),
+            fold(&text, 1, 2),
+            fold(&text, 0, 3),
+            fold(&text, 1, 2),
+        ];
+        sort(&mut folds);
+        assert_eq!(
+            folds,
+            vec![fold(&text, 0, 3), fold(&text, 1, 2), fold(&text, 4, 5)]
+        );
+        assert_eq!(
+            outermost(&folds),
+            vec![fold(&text, 0, 3), fold(&text, 4, 5)]
+        );
+        assert_eq!(levels(&folds), vec![1, 2, 1]);
+        assert_eq!(
+            innermost_at_line(&folds, text.slice(..), 2, |_| false),
+            Some(fold(&text, 1, 2))
+        );
+        assert_eq!(
+            innermost_at_line(&folds, text.slice(..), 6, |_| false),
+            None
+        );
+    }
+
+    #[test]
+    fn map() {
+        let mut text = Rope::from("a\nb\nc\nd\n");
+        let fold = fold(&text, 1, 2);
+        let transaction = Transaction::insert(&text, &Selection::point(0), "x\n".into());
+        transaction.apply(&mut text);
+        // inserting a line before the fold moves it down
+        let mapped = fold.map(text.slice(..), transaction.changes()).unwrap();
+        assert_eq!(mapped.start_line(text.slice(..)), 2);
+        assert_eq!(mapped.end_line(text.slice(..)), 3);
+    }
+}
@@ -7,16 +7,19 @@
 pub mod comment;
 pub mod completion;
 pub mod config;
+pub mod conflict;
 pub mod diagnostic;
 pub mod diff;
 pub mod doc_formatter;
 pub mod editor_config;
+pub mod fold;
 pub mod fuzzy;
 pub mod graphemes;
 pub mod history;
 pub mod increment;
 pub mod indent;
 pub mod line_ending;
+pub mod linked_editing;
 pub mod macros;
 pub mod match_brackets;
 pub mod movement;
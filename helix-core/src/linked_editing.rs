@@ -0,0 +1,179 @@
+//! Linked editing.
+//!
+//! Linked ranges are ranges of a document that always contain the same text, like the names of
+//! an opening and a closing XML tag. While the cursor is inside of one of them, the other ranges
+//! receive a cursor at the same offset so that all of them are edited together. Linked ranges are
+//! computed from LSP `textDocument/linkedEditingRange` responses or, as a fallback, from the tag
+//! names of the tree-sitter syntax tree.
+
+use std::ops;
+
+use crate::{chars::char_is_word, tree_sitter::Node, Range, RopeSlice, Selection, Syntax};
+
+/// Returns whether `ch` can be part of a linked range. Inserting any other character, like the
+/// space that separates a tag name from its attributes, ends linked editing.
+pub fn is_linked_char(ch: char) -> bool {
+    char_is_word(ch) || matches!(ch, '-' | '.' | ':')
+}
+
+/// Returns the names of the opening and closing tag of the element whose tag name contains
+/// `pos`, or `None` if `pos` is not inside of a tag name.
+///
+/// This works for any grammar that represents elements as a node whose first and last children
+/// are the opening and closing tag, each starting with `<` and `</` respectively followed by the
+/// name of the tag, like HTML, XML and JSX.
+pub fn tag_name_ranges(
+    syntax: &Syntax,
+    text: RopeSlice,
+    pos: usize,
+) -> Option<Vec<ops::Range<usize>>> {
+    let byte_pos = text.char_to_byte(pos) as u32;
+    // The cursor may also be placed directly after the name, e.g. `<div|>`.
+    let (open, close) = [byte_pos, byte_pos.saturating_sub(1)]
+        .into_iter()
+        .find_map(|byte_pos| {
+            let node = syntax.descendant_for_byte_range(byte_pos, byte_pos)?;
+            matching_tags(node)
+        })?;
+
+    let open_name = open.named_child(0)?;
+    let close_name = close.named_child(0)?;
+    let name_range = |node: Node| {
+        text.byte_to_char(node.start_byte() as usize)..text.byte_to_char(node.end_byte() as usize)
+    };
+    let (open_name, close_name) = (name_range(open_name), name_range(close_name));
+    if open_name.is_empty() || text.slice(open_name.clone()) != text.slice(close_name.clone()) {
+        return None;
+    }
+    Some(vec![open_name, close_name])
+}
+
+fn is_opening_tag(node: &Node) -> bool {
+    node.child(0).is_some_and(|child| child.kind() == "<")
+        && node.child(1).is_some_and(|child| child.kind() != "/")
+}
+
+/// Closing tags start with either a single `</` token or separate `<` and `/` tokens (JSX).
+fn is_closing_tag(node: &Node) -> bool {
+    match node.child(0) {
+        Some(child) if child.kind() == "</" => true,
+        Some(child) if child.kind() == "<" => {
+            node.child(1).is_some_and(|child| child.kind() == "/")
+        }
+        _ => false,
+    }
+}
+
+/// Walks up from a node inside of a tag name to the opening and closing tag of its element.
+fn matching_tags(node: Node) -> Option<(Node, Node)> {
+    // Tag names can be nested nodes, like `Foo.Bar` in JSX, so a few ancestors are considered.
+    let mut name = node;
+    for _ in 0..3 {
+        let tag = name.parent()?;
+        if is_opening_tag(&tag) || is_closing_tag(&tag) {
+            if tag.named_child(0)? != name {
+                return None;
+            }
+            let element = tag.parent()?;
+            let open = element.child(0)?;
+            let close = element.child(element.child_count().checked_sub(1)?)?;
+            return (is_opening_tag(&open) && is_closing_tag(&close)).then_some((open, close));
+        }
+        name = tag;
+    }
+    None
+}
+
+/// Adds a cursor to each linked range for the primary cursor of a selection that consists of
+/// a single range inside of one of the linked ranges. The added ranges are placed at the same
+/// offset inside of their linked range as the primary range.
+///
+/// Returns `None` if the selection can not be linked to the `ranges`.
+pub fn linked_selection(
+    text: RopeSlice,
+    selection: &Selection,
+    ranges: &[ops::Range<usize>],
+) -> Option<Selection> {
+    if selection.len() != 1 || ranges.len() < 2 {
+        return None;
+    }
+    let primary = selection.primary();
+    let cursor = primary.cursor(text);
+    let current = ranges
+        .iter()
+        .find(|range| range.start <= cursor && cursor <= range.end)?;
+
+    let mut linked = Vec::with_capacity(ranges.len());
+    linked.push(primary);
+    for range in ranges.iter().filter(|&range| range != current) {
+        let shift = |pos: usize| (pos + range.start).checked_sub(current.start);
+        let anchor = shift(primary.anchor)?;
+        let head = shift(primary.head)?;
+        if anchor.max(head) > text.len_chars() {
+            return None;
+        }
+        linked.push(Range::new(anchor, head));
+    }
+    Some(Selection::new(linked.into(), 0))
+}
+
+/// Returns whether every cursor of `selection` is inside of one of the linked `ranges`.
+pub fn is_linked(text: RopeSlice, selection: &Selection, ranges: &[ops::Range<usize>]) -> bool {
+    selection.len() == ranges.len()
+        && selection.iter().all(|range| {
+            let cursor = range.cursor(text);
+            ranges
+                .iter()
+                .any(|range| range.start <= cursor && cursor <= range.end)
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Rope;
+    use smallvec::smallvec;
+
+    #[test]
+    fn link_selection() {
+        let text = Rope::from("<div>a</div>");
+        let slice = text.slice(..);
+        let ranges = [1..4, 8..11];
+
+        let selection = Selection::single(2, 3);
+        let linked = linked_selection(slice, &selection, &ranges).unwrap();
+        assert_eq!(linked.ranges(), &[Range::new(2, 3), Range::new(9, 10)]);
+        assert_eq!(linked.primary(), Range::new(2, 3));
+        assert!(is_linked(slice, &linked, &ranges));
+
+        // The primary range stays primary when it is in the closing tag.
+        let selection = Selection::single(10, 9);
+        let linked = linked_selection(slice, &selection, &ranges).unwrap();
+        assert_eq!(linked.ranges(), &[Range::new(3, 2), Range::new(10, 9)]);
+        assert_eq!(linked.primary(), Range::new(10, 9));
+
+        // Directly after the tag name.
+        let selection = Selection::single(5, 4);
+        let linked = linked_selection(slice, &selection, &ranges).unwrap();
+        assert_eq!(linked.ranges(), &[Range::new(5, 4), Range::new(12, 11)]);
+    }
+
+    #[test]
+    fn unlinked_selection() {
+        let text = Rope::from("<div>a</div>");
+        let slice = text.slice(..);
+        let ranges = [1..4, 8..11];
+
+        assert_eq!(
+            linked_selection(slice, &Selection::single(5, 6), &ranges),
+            None
+        );
+        let selection = Selection::new(smallvec![Range::new(2, 3), Range::new(5, 6)], 0);
+        assert_eq!(linked_selection(slice, &selection, &ranges), None);
+        assert!(!is_linked(slice, &selection, &ranges));
+        assert_eq!(
+            linked_selection(slice, &Selection::single(2, 3), &ranges[..1]),
+            None
+        );
+    }
+}
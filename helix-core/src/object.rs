@@ -24,6 +24,34 @@ pub fn expand_selection(syntax: &Syntax, text: RopeSlice, selection: Selection)
     })
 }
 
+/// Returns the smallest of `ranges` that contains `range` and is larger than it, keeping the
+/// direction of `range`.
+pub fn expand_range_to(range: Range, ranges: impl IntoIterator<Item = Range>) -> Option<Range> {
+    ranges
+        .into_iter()
+        .filter(|candidate| {
+            candidate.from() <= range.from()
+                && range.to() <= candidate.to()
+                && candidate.len() > range.len()
+        })
+        .min_by_key(|candidate| candidate.len())
+        .map(|candidate| candidate.with_direction(range.direction()))
+}
+
+/// Returns the largest of `ranges` that is contained in `range` and smaller than it, keeping the
+/// direction of `range`.
+pub fn shrink_range_to(range: Range, ranges: impl IntoIterator<Item = Range>) -> Option<Range> {
+    ranges
+        .into_iter()
+        .filter(|candidate| {
+            range.from() <= candidate.from()
+                && candidate.to() <= range.to()
+                && candidate.len() < range.len()
+        })
+        .max_by_key(|candidate| candidate.len())
+        .map(|candidate| candidate.with_direction(range.direction()))
+}
+
 pub fn shrink_selection(syntax: &Syntax, text: RopeSlice, selection: Selection) -> Selection {
     select_node_impl(
         syntax,
@@ -138,3 +166,37 @@ fn select_node_impl<F>(
         Range::new(from, to).with_direction(direction.unwrap_or_else(|| range.direction()))
     })
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn expand_and_shrink_range_to() {
+        let ranges = [Range::new(0, 10), Range::new(2, 8), Range::new(3, 5)];
+
+        assert_eq!(
+            expand_range_to(Range::new(3, 5), ranges),
+            Some(Range::new(2, 8))
+        );
+        assert_eq!(
+            expand_range_to(Range::new(5, 3), ranges),
+            Some(Range::new(8, 2))
+        );
+        assert_eq!(
+            expand_range_to(Range::new(4, 9), ranges),
+            Some(Range::new(0, 10))
+        );
+        assert_eq!(expand_range_to(Range::new(0, 10), ranges), None);
+
+        assert_eq!(
+            shrink_range_to(Range::new(0, 10), ranges),
+            Some(Range::new(2, 8))
+        );
+        assert_eq!(
+            shrink_range_to(Range::new(8, 2), ranges),
+            Some(Range::new(5, 3))
+        );
+        assert_eq!(shrink_range_to(Range::new(3, 5), ranges), None);
+    }
+}
@@ -44,6 +44,7 @@ pub struct LanguageData {
     textobject_query: OnceCell<Option<TextObjectQuery>>,
     tag_query: OnceCell<Option<TagQuery>>,
     rainbow_query: OnceCell<Option<RainbowQuery>>,
+    fold_query: OnceCell<Option<FoldQuery>>,
 }
 
 impl LanguageData {
@@ -55,6 +56,7 @@ fn new(config: LanguageConfiguration) -> Self {
             textobject_query: OnceCell::new(),
             tag_query: OnceCell::new(),
             rainbow_query: OnceCell::new(),
+            fold_query: OnceCell::new(),
         }
     }
 
@@ -231,6 +233,36 @@ fn rainbow_query(&self, loader: &Loader) -> Option<&RainbowQuery> {
             .as_ref()
     }
 
+    /// Compiles the folds.scm query for a language.
+    /// This function should only be used by this module or the xtask crate.
+    pub fn compile_fold_query(
+        grammar: Grammar,
+        config: &LanguageConfiguration,
+    ) -> Result<Option<FoldQuery>> {
+        let name = &config.language_id;
+        let text = read_query(name, "folds.scm");
+        if text.is_empty() {
+            return Ok(None);
+        }
+        let query = Query::new(grammar, &text, |_, _| Ok(()))
+            .with_context(|| format!("Failed to compile folds.scm query for '{name}'"))?;
+        Ok(Some(FoldQuery { query }))
+    }
+
+    fn fold_query(&self, loader: &Loader) -> Option<&FoldQuery> {
+        self.fold_query
+            .get_or_init(|| {
+                let grammar = self.syntax_config(loader)?.grammar;
+                Self::compile_fold_query(grammar, &self.config)
+                    .map_err(|err| {
+                        log::error!("{err}");
+                    })
+                    .ok()
+                    .flatten()
+            })
+            .as_ref()
+    }
+
     fn reconfigure(&self, scopes: &[String]) {
         if let Some(Some(config)) = self.syntax.get() {
             reconfigure_highlights(config, scopes);
@@ -424,6 +456,10 @@ fn rainbow_query(&self, lang: Language) -> Option<&RainbowQuery> {
         self.language(lang).rainbow_query(self)
     }
 
+    pub fn fold_query(&self, lang: Language) -> Option<&FoldQuery> {
+        self.language(lang).fold_query(self)
+    }
+
     pub fn language_server_configs(&self) -> &HashMap<String, LanguageServerConfiguration> {
         &self.language_server_configs
     }
@@ -627,6 +663,20 @@ pub fn tags<'a>(
         )
     }
 
+    pub fn folds<'a>(
+        &'a self,
+        source: RopeSlice<'a>,
+        loader: &'a Loader,
+        range: impl RangeBounds<u32>,
+    ) -> QueryIter<'a, 'a, impl FnMut(Language) -> Option<&'a Query> + 'a, ()> {
+        QueryIter::new(
+            &self.inner,
+            source,
+            |lang| loader.fold_query(lang).map(|q| &q.query),
+            range,
+        )
+    }
+
     pub fn rainbow_highlights(
         &self,
         source: RopeSlice,
@@ -1074,6 +1124,11 @@ pub struct TagQuery {
     pub query: Query,
 }
 
+#[derive(Debug)]
+pub struct FoldQuery {
+    pub query: Query,
+}
+
 pub fn pretty_print_tree<W: fmt::Write>(fmt: &mut W, node: Node) -> fmt::Result {
     if node.child_count() == 0 {
         if node_is_visible(&node) {
@@ -106,6 +106,14 @@ pub struct LanguageConfiguration {
     pub persistent_diagnostic_sources: Vec<String>,
     /// Overrides the `editor.rainbow-brackets` config key for the language.
     pub rainbow_brackets: Option<bool>,
+    /// Where `expand_selection` and `shrink_selection` take the ranges to select from.
+    #[serde(default)]
+    pub selection_ranges: SelectionRangeSource,
+    /// Keybindings merged over the global keymap while a document of the language is focused, in
+    /// the same format as the `[keys]` table of `config.toml`. They are parsed by the frontend,
+    /// which knows the commands.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keys: Option<toml::Value>,
 }
 
 impl LanguageConfiguration {
@@ -324,6 +332,14 @@ pub enum LanguageServerFeature {
     InlayHints,
     DocumentColors,
     CallHierarchy,
+    FoldingRanges,
+    SemanticTokens,
+    CodeLens,
+    TypeHierarchy,
+    LinkedEditingRange,
+    SelectionRange,
+    InlineCompletion,
+    InlineValue,
 }
 
 impl Display for LanguageServerFeature {
@@ -351,6 +367,14 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             InlayHints => "inlay-hints",
             DocumentColors => "document-colors",
             CallHierarchy => "call-hierarchy",
+            FoldingRanges => "folding-ranges",
+            SemanticTokens => "semantic-tokens",
+            CodeLens => "code-lens",
+            TypeHierarchy => "type-hierarchy",
+            LinkedEditingRange => "linked-editing-range",
+            SelectionRange => "selection-range",
+            InlineCompletion => "inline-completion",
+            InlineValue => "inline-value",
         };
         write!(f, "{feature}",)
     }
@@ -514,6 +538,20 @@ pub struct IndentationConfiguration {
     pub unit: String,
 }
 
+/// The source of the ranges that `expand_selection` and `shrink_selection` select.
+/// If no language server supports selection ranges, the tree-sitter syntax tree is used.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SelectionRangeSource {
+    /// Use the nodes of the tree-sitter syntax tree.
+    #[default]
+    TreeSitter,
+    /// Use the selection ranges of the language server.
+    Lsp,
+    /// Use both, selecting whichever range is closest to the current selection.
+    Merged,
+}
+
 /// How the indentation for a newly inserted line should be determined.
 /// If the selected heuristic is not available (e.g. because the current
 /// language has no tree-sitter indent queries), a simpler one will be used.
@@ -5,6 +5,7 @@
 use std::ptr::NonNull;
 
 use crate::doc_formatter::FormattedGrapheme;
+use crate::fold::Fold;
 use crate::syntax::{Highlight, OverlayHighlights};
 use crate::{Position, Tendril};
 
@@ -279,6 +280,7 @@ pub struct TextAnnotations<'a> {
     inline_annotations: Vec<Layer<'a, InlineAnnotation, Option<Highlight>>>,
     overlays: Vec<Layer<'a, Overlay, Option<Highlight>>>,
     line_annotations: Vec<(Cell<usize>, RawBox<dyn LineAnnotation + 'a>)>,
+    folds: Option<Layer<'a, Fold, ()>>,
 }
 
 impl Debug for TextAnnotations<'_> {
@@ -286,6 +288,7 @@ fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("TextAnnotations")
             .field("inline_annotations", &self.inline_annotations)
             .field("overlays", &self.overlays)
+            .field("folds", &self.folds)
             .finish_non_exhaustive()
     }
 }
@@ -298,6 +301,9 @@ pub fn reset_pos(&self, char_idx: usize) {
         for (next_anchor, layer) in &self.line_annotations {
             next_anchor.set(unsafe { layer.get().reset_pos(char_idx) });
         }
+        if let Some(folds) = &self.folds {
+            folds.reset_pos(char_idx, |fold| fold.start);
+        }
     }
 
     pub fn collect_overlay_highlights(&self, char_range: Range<usize>) -> OverlayHighlights {
@@ -364,6 +370,23 @@ pub fn add_line_annotation(&mut self, layer: Box<dyn LineAnnotation + 'a>) -> &m
         self
     }
 
+    /// Add closed folds, the text they hide is skipped while rendering.
+    ///
+    /// The folds **must be sorted** by their `start` and **must not overlap**
+    /// (see [`crate::fold::outermost`]). Only a single layer of folds is
+    /// supported, adding folds replaces any previously added folds.
+    pub fn add_folds(&mut self, folds: &'a [Fold]) -> &mut Self {
+        self.folds = (!folds.is_empty()).then(|| (folds, ()).into());
+        self
+    }
+
+    /// Returns the closed fold that hides the char at `char_idx`, if any.
+    pub fn fold_hiding(&self, char_idx: usize) -> Option<&Fold> {
+        let folds = self.folds.as_ref()?.annotations;
+        let idx = folds.partition_point(|fold| fold.start < char_idx);
+        folds[..idx].last().filter(|fold| fold.hides(char_idx))
+    }
+
     /// Removes all line annotations, useful for vertical motions
     /// so that virtual text lines are automatically skipped.
     pub fn clear_line_annotations(&mut self) {
@@ -380,6 +403,20 @@ pub(crate) fn next_inline_annotation_at(
         })
     }
 
+    pub(crate) fn fold_at(&self, char_idx: usize) -> Option<&Fold> {
+        self.folds.as_ref()?.consume(char_idx, |fold| fold.start)
+    }
+
+    /// Skips all inline annotations, overlays and folds before `char_idx`.
+    /// Called after the text of a fold was skipped.
+    pub(crate) fn skip_folded(&self, char_idx: usize) {
+        reset_pos(&self.inline_annotations, char_idx, |annot| annot.char_idx);
+        reset_pos(&self.overlays, char_idx, |annot| annot.char_idx);
+        if let Some(folds) = &self.folds {
+            folds.reset_pos(char_idx, |fold| fold.start);
+        }
+    }
+
     pub(crate) fn overlay_at(&self, char_idx: usize) -> Option<(&Overlay, Option<Highlight>)> {
         let mut overlay = None;
         for layer in &self.overlays {
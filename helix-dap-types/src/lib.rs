@@ -173,6 +173,40 @@ pub struct SourceBreakpoint {
     pub log_message: Option<String>,
 }
 
+#[derive(Debug, Default, PartialEq, Eq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FunctionBreakpoint {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub condition: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hit_condition: Option<String>,
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataBreakpoint {
+    pub data_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub access_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub condition: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hit_condition: Option<String>,
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstructionBreakpoint {
+    pub instruction_reference: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<isize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub condition: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hit_condition: Option<String>,
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Breakpoint {
@@ -332,6 +366,28 @@ pub struct Module {
     pub address_range: Option<String>,
 }
 
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompletionItem {
+    pub label: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sort_text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub ty: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub length: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selection_start: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selection_length: Option<usize>,
+}
+
 fn from_number<'de, D>(deserializer: D) -> Result<String, D::Error>
 where
     D: Deserializer<'de>,
@@ -492,6 +548,125 @@ impl Request for SetBreakpoints {
         const COMMAND: &'static str = "setBreakpoints";
     }
 
+    #[derive(Debug, Default, PartialEq, Eq, Clone, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct SetFunctionBreakpointsArguments {
+        pub breakpoints: Vec<FunctionBreakpoint>,
+    }
+
+    #[derive(Debug, Default, PartialEq, Eq, Clone, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct SetFunctionBreakpointsResponse {
+        pub breakpoints: Vec<Breakpoint>,
+    }
+
+    #[derive(Debug)]
+    pub enum SetFunctionBreakpoints {}
+
+    impl Request for SetFunctionBreakpoints {
+        type Arguments = SetFunctionBreakpointsArguments;
+        type Result = SetFunctionBreakpointsResponse;
+        const COMMAND: &'static str = "setFunctionBreakpoints";
+    }
+
+    #[derive(Debug, Default, PartialEq, Eq, Clone, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct DataBreakpointInfoArguments {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub variables_reference: Option<usize>,
+        pub name: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub frame_id: Option<usize>,
+    }
+
+    #[derive(Debug, Default, PartialEq, Eq, Clone, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct DataBreakpointInfoResponse {
+        pub data_id: Option<String>,
+        pub description: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub access_types: Option<Vec<String>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub can_persist: Option<bool>,
+    }
+
+    #[derive(Debug)]
+    pub enum DataBreakpointInfo {}
+
+    impl Request for DataBreakpointInfo {
+        type Arguments = DataBreakpointInfoArguments;
+        type Result = DataBreakpointInfoResponse;
+        const COMMAND: &'static str = "dataBreakpointInfo";
+    }
+
+    #[derive(Debug, Default, PartialEq, Eq, Clone, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct SetDataBreakpointsArguments {
+        pub breakpoints: Vec<DataBreakpoint>,
+    }
+
+    #[derive(Debug, Default, PartialEq, Eq, Clone, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct SetDataBreakpointsResponse {
+        pub breakpoints: Vec<Breakpoint>,
+    }
+
+    #[derive(Debug)]
+    pub enum SetDataBreakpoints {}
+
+    impl Request for SetDataBreakpoints {
+        type Arguments = SetDataBreakpointsArguments;
+        type Result = SetDataBreakpointsResponse;
+        const COMMAND: &'static str = "setDataBreakpoints";
+    }
+
+    #[derive(Debug, Default, PartialEq, Eq, Clone, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct SetInstructionBreakpointsArguments {
+        pub breakpoints: Vec<InstructionBreakpoint>,
+    }
+
+    #[derive(Debug, Default, PartialEq, Eq, Clone, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct SetInstructionBreakpointsResponse {
+        pub breakpoints: Vec<Breakpoint>,
+    }
+
+    #[derive(Debug)]
+    pub enum SetInstructionBreakpoints {}
+
+    impl Request for SetInstructionBreakpoints {
+        type Arguments = SetInstructionBreakpointsArguments;
+        type Result = SetInstructionBreakpointsResponse;
+        const COMMAND: &'static str = "setInstructionBreakpoints";
+    }
+
+    #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct CompletionsArguments {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub frame_id: Option<usize>,
+        pub text: String,
+        pub column: usize,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub line: Option<usize>,
+    }
+
+    #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct CompletionsResponse {
+        pub targets: Vec<CompletionItem>,
+    }
+
+    #[derive(Debug)]
+    pub enum Completions {}
+
+    impl Request for Completions {
+        type Arguments = CompletionsArguments;
+        type Result = CompletionsResponse;
+        const COMMAND: &'static str = "completions";
+    }
+
     #[derive(Debug, Default, PartialEq, Eq, Clone, Deserialize, Serialize)]
     #[serde(rename_all = "camelCase")]
     pub struct ContinueArguments {
@@ -741,6 +916,75 @@ impl Request for SetExceptionBreakpoints {
         const COMMAND: &'static str = "setExceptionBreakpoints";
     }
 
+    #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct SetVariableArguments {
+        pub variables_reference: usize,
+        pub name: String,
+        pub value: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub format: Option<ValueFormat>,
+    }
+
+    #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct SetVariableResponse {
+        pub value: String,
+        #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+        pub ty: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub variables_reference: Option<usize>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub named_variables: Option<usize>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub indexed_variables: Option<usize>,
+    }
+
+    #[derive(Debug)]
+    pub enum SetVariable {}
+
+    impl Request for SetVariable {
+        type Arguments = SetVariableArguments;
+        type Result = SetVariableResponse;
+        const COMMAND: &'static str = "setVariable";
+    }
+
+    #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct SetExpressionArguments {
+        pub expression: String,
+        pub value: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub frame_id: Option<usize>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub format: Option<ValueFormat>,
+    }
+
+    #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct SetExpressionResponse {
+        pub value: String,
+        #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+        pub ty: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub presentation_hint: Option<VariablePresentationHint>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub variables_reference: Option<usize>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub named_variables: Option<usize>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub indexed_variables: Option<usize>,
+    }
+
+    #[derive(Debug)]
+    pub enum SetExpression {}
+
+    impl Request for SetExpression {
+        type Arguments = SetExpressionArguments;
+        type Result = SetExpressionResponse;
+        const COMMAND: &'static str = "setExpression";
+    }
+
     // Reverse Requests
 
     #[derive(Debug, Default, PartialEq, Eq, Clone, Deserialize, Serialize)]
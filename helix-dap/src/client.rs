@@ -453,6 +453,55 @@ pub async fn set_breakpoints(
         Ok(response.breakpoints)
     }
 
+    pub async fn set_function_breakpoints(
+        &self,
+        breakpoints: Vec<FunctionBreakpoint>,
+    ) -> Result<Vec<Breakpoint>> {
+        let args = requests::SetFunctionBreakpointsArguments { breakpoints };
+
+        let response = self
+            .request::<requests::SetFunctionBreakpoints>(args)
+            .await?;
+        Ok(response.breakpoints)
+    }
+
+    pub async fn data_breakpoint_info(
+        &self,
+        variables_reference: Option<usize>,
+        name: String,
+        frame_id: Option<usize>,
+    ) -> Result<requests::DataBreakpointInfoResponse> {
+        let args = requests::DataBreakpointInfoArguments {
+            variables_reference,
+            name,
+            frame_id,
+        };
+
+        self.request::<requests::DataBreakpointInfo>(args).await
+    }
+
+    pub async fn set_data_breakpoints(
+        &self,
+        breakpoints: Vec<DataBreakpoint>,
+    ) -> Result<Vec<Breakpoint>> {
+        let args = requests::SetDataBreakpointsArguments { breakpoints };
+
+        let response = self.request::<requests::SetDataBreakpoints>(args).await?;
+        Ok(response.breakpoints)
+    }
+
+    pub async fn set_instruction_breakpoints(
+        &self,
+        breakpoints: Vec<InstructionBreakpoint>,
+    ) -> Result<Vec<Breakpoint>> {
+        let args = requests::SetInstructionBreakpointsArguments { breakpoints };
+
+        let response = self
+            .request::<requests::SetInstructionBreakpoints>(args)
+            .await?;
+        Ok(response.breakpoints)
+    }
+
     pub async fn configuration_done(&self) -> Result<()> {
         if !self
             .caps
@@ -552,17 +601,69 @@ pub async fn eval(
         &self,
         expression: String,
         frame_id: Option<usize>,
+        context: Option<&str>,
     ) -> Result<requests::EvaluateResponse> {
         let args = requests::EvaluateArguments {
             expression,
             frame_id,
-            context: None,
+            context: context.map(String::from),
             format: None,
         };
 
         self.request::<requests::Evaluate>(args).await
     }
 
+    pub async fn set_variable(
+        &self,
+        variables_reference: usize,
+        name: String,
+        value: String,
+    ) -> Result<requests::SetVariableResponse> {
+        let args = requests::SetVariableArguments {
+            variables_reference,
+            name,
+            value,
+            format: None,
+        };
+
+        self.request::<requests::SetVariable>(args).await
+    }
+
+    pub async fn set_expression(
+        &self,
+        expression: String,
+        value: String,
+        frame_id: Option<usize>,
+    ) -> Result<requests::SetExpressionResponse> {
+        let args = requests::SetExpressionArguments {
+            expression,
+            value,
+            frame_id,
+            format: None,
+        };
+
+        self.request::<requests::SetExpression>(args).await
+    }
+
+    /// Requests completions for the debug console input `text`, with the cursor at the 1-based
+    /// (character) `column`.
+    pub async fn completions(
+        &self,
+        text: String,
+        column: usize,
+        frame_id: Option<usize>,
+    ) -> Result<Vec<CompletionItem>> {
+        let args = requests::CompletionsArguments {
+            frame_id,
+            text,
+            column,
+            line: None,
+        };
+
+        let response = self.request::<requests::Completions>(args).await?;
+        Ok(response.targets)
+    }
+
     pub fn set_exception_breakpoints(
         &self,
         filters: Vec<String>,
@@ -156,6 +156,15 @@ pub fn workspace_lang_config_file() -> PathBuf {
     find_workspace().0.join(".helix").join("languages.toml")
 }
 
+/// The debug launch configurations of the workspace, in addition to `.vscode/launch.json`.
+pub fn workspace_debug_config_file() -> PathBuf {
+    find_workspace().0.join(".helix").join("debug.toml")
+}
+
+pub fn workspace_launch_json_file() -> PathBuf {
+    find_workspace().0.join(".vscode").join("launch.json")
+}
+
 pub fn lang_config_file() -> PathBuf {
     config_dir().join("languages.toml")
 }
@@ -157,9 +157,10 @@ pub struct WorkspaceTrust {
 #[derive(Clone, Copy)]
 struct CacheEntry {
     status: TrustStatus,
-    /// Whether `.helix/config.toml` or `.helix/languages.toml` exists in the workspace at the time
-    /// of the first uncached query. Snapshotted to keep [`WorkspaceTrust::workspace_restricted`]
-    /// off the syscall path on repeat calls (statusline indicator runs per render).
+    /// Whether `.helix/config.toml`, `.helix/languages.toml`, `.helix/debug.toml` or
+    /// `.vscode/launch.json` exists in the workspace at the time of the first uncached query.
+    /// Snapshotted to keep [`WorkspaceTrust::workspace_restricted`] off the syscall path on
+    /// repeat calls (statusline indicator runs per render).
     has_local_config: bool,
 }
 
@@ -366,6 +367,8 @@ pub fn deny_once(&self, workspace: &Path) {
 fn has_local_config(workspace: &Path) -> bool {
     workspace.join(".helix").join("config.toml").exists()
         || workspace.join(".helix").join("languages.toml").exists()
+        || workspace.join(".helix").join("debug.toml").exists()
+        || workspace.join(".vscode").join("launch.json").exists()
 }
 
 fn demote_for_query(status: TrustStatus, query: TrustQuery) -> TrustStatus {
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-use crate::NumberOrString;
+use crate::{LSPAny, NumberOrString};
 
 pub type ProgressToken = NumberOrString;
 
@@ -20,6 +20,9 @@ pub struct ProgressParams {
 #[serde(untagged)]
 pub enum ProgressParamsValue {
     WorkDone(WorkDoneProgress),
+    /// A partial result of a request that was sent with a `partialResultToken`. Its type depends
+    /// on the request.
+    PartialResult(LSPAny),
 }
 
 /// The `window/workDoneProgress/create` request is sent
@@ -24,6 +24,14 @@ pub struct TypeHierarchyRegistrationOptions {
     pub static_registration_options: StaticRegistrationOptions,
 }
 
+#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum TypeHierarchyServerCapabilities {
+    Simple(bool),
+    Options(TypeHierarchyOptions),
+    RegistrationOptions(TypeHierarchyRegistrationOptions),
+}
+
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
 pub struct TypeHierarchyPrepareParams {
     #[serde(flatten)]
@@ -63,7 +71,7 @@ pub struct TypeHierarchyItem {
 
     /// Tags for this item.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub tags: Option<SymbolTag>,
+    pub tags: Option<Vec<SymbolTag>>,
 
     /// More detail for this item, e.g. the signature of a function.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use serde::{Deserialize, Serialize};
 
 use crate::{
@@ -41,7 +43,7 @@ pub struct PreviousResultId {
 #[serde(rename_all = "camelCase")]
 pub struct WorkspaceDiagnosticParams {
     /// The additional identifier provided during registration.
-    pub identifier: Option<String>,
+    pub identifier: Option<Arc<str>>,
 
     /// The currently known diagnostic reports with their
     /// previous result ids.
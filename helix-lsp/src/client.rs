@@ -407,9 +407,86 @@ pub fn supports_feature(&self, feature: LanguageServerFeature) -> bool {
                         | CallHierarchyServerCapability::Options(_)
                 )
             ),
+            LanguageServerFeature::TypeHierarchy => matches!(
+                capabilities.type_hierarchy_provider,
+                Some(
+                    lsp::TypeHierarchyServerCapabilities::Simple(true)
+                        | lsp::TypeHierarchyServerCapabilities::Options(_)
+                        | lsp::TypeHierarchyServerCapabilities::RegistrationOptions(_)
+                )
+            ),
+            LanguageServerFeature::LinkedEditingRange => matches!(
+                capabilities.linked_editing_range_provider,
+                Some(
+                    lsp::LinkedEditingRangeServerCapabilities::Simple(true)
+                        | lsp::LinkedEditingRangeServerCapabilities::Options(_)
+                        | lsp::LinkedEditingRangeServerCapabilities::RegistrationOptions(_)
+                )
+            ),
+            LanguageServerFeature::SelectionRange => matches!(
+                capabilities.selection_range_provider,
+                Some(
+                    lsp::SelectionRangeProviderCapability::Simple(true)
+                        | lsp::SelectionRangeProviderCapability::Options(_)
+                        | lsp::SelectionRangeProviderCapability::RegistrationOptions(_)
+                )
+            ),
+            LanguageServerFeature::FoldingRanges => matches!(
+                capabilities.folding_range_provider,
+                Some(
+                    FoldingRangeProviderCapability::Simple(true)
+                        | FoldingRangeProviderCapability::FoldingProvider(_)
+                        | FoldingRangeProviderCapability::Options(_)
+                )
+            ),
+            LanguageServerFeature::SemanticTokens => {
+                self.semantic_tokens_options().is_some_and(|options| {
+                    matches!(
+                        options.full,
+                        Some(
+                            SemanticTokensFullOptions::Bool(true)
+                                | SemanticTokensFullOptions::Delta { .. }
+                        )
+                    )
+                })
+            }
+            LanguageServerFeature::CodeLens => capabilities.code_lens_provider.is_some(),
+            LanguageServerFeature::InlineCompletion => matches!(
+                capabilities.inline_completion_provider,
+                Some(OneOf::Left(true) | OneOf::Right(_))
+            ),
+            LanguageServerFeature::InlineValue => matches!(
+                capabilities.inline_value_provider,
+                Some(OneOf::Left(true) | OneOf::Right(_))
+            ),
         }
     }
 
+    fn semantic_tokens_options(&self) -> Option<&lsp::SemanticTokensOptions> {
+        match self.capabilities().semantic_tokens_provider.as_ref()? {
+            lsp::SemanticTokensServerCapabilities::SemanticTokensOptions(options) => Some(options),
+            lsp::SemanticTokensServerCapabilities::SemanticTokensRegistrationOptions(options) => {
+                Some(&options.semantic_tokens_options)
+            }
+        }
+    }
+
+    /// The token types and modifiers the semantic tokens of this server refer to.
+    pub fn semantic_tokens_legend(&self) -> Option<&lsp::SemanticTokensLegend> {
+        self.semantic_tokens_options()
+            .map(|options| &options.legend)
+    }
+
+    /// Whether the server can send semantic tokens as a delta to a previous response.
+    pub fn supports_semantic_tokens_delta(&self) -> bool {
+        self.semantic_tokens_options().is_some_and(|options| {
+            matches!(
+                options.full,
+                Some(lsp::SemanticTokensFullOptions::Delta { delta: Some(true) })
+            )
+        })
+    }
+
     pub fn offset_encoding(&self) -> OffsetEncoding {
         self.capabilities()
             .position_encoding
@@ -631,6 +708,12 @@ pub(crate) async fn initialize(&self, enable_snippets: bool) -> Result<lsp::Init
                     diagnostic: Some(lsp::DiagnosticWorkspaceClientCapabilities {
                         refresh_support: Some(true),
                     }),
+                    semantic_tokens: Some(lsp::SemanticTokensWorkspaceClientCapabilities {
+                        refresh_support: Some(true),
+                    }),
+                    code_lens: Some(lsp::CodeLensWorkspaceClientCapabilities {
+                        refresh_support: Some(true),
+                    }),
                     ..Default::default()
                 }),
                 text_document: Some(lsp::TextDocumentClientCapabilities {
@@ -734,6 +817,78 @@ pub(crate) async fn initialize(&self, enable_snippets: bool) -> Result<lsp::Init
                     call_hierarchy: Some(lsp::DynamicRegistrationClientCapabilities {
                         dynamic_registration: Some(false),
                     }),
+                    type_hierarchy: Some(lsp::DynamicRegistrationClientCapabilities {
+                        dynamic_registration: Some(false),
+                    }),
+                    linked_editing_range: Some(lsp::DynamicRegistrationClientCapabilities {
+                        dynamic_registration: Some(false),
+                    }),
+                    selection_range: Some(lsp::SelectionRangeClientCapabilities {
+                        dynamic_registration: Some(false),
+                    }),
+                    inline_completion: Some(lsp::InlineCompletionClientCapabilities {
+                        dynamic_registration: Some(false),
+                    }),
+                    inline_value: Some(lsp::InlineValueClientCapabilities {
+                        dynamic_registration: Some(false),
+                    }),
+                    folding_range: Some(lsp::FoldingRangeClientCapabilities {
+                        dynamic_registration: Some(false),
+                        line_folding_only: Some(true),
+                        ..Default::default()
+                    }),
+                    code_lens: Some(lsp::CodeLensClientCapabilities {
+                        dynamic_registration: Some(false),
+                    }),
+                    semantic_tokens: Some(lsp::SemanticTokensClientCapabilities {
+                        dynamic_registration: Some(false),
+                        requests: lsp::SemanticTokensClientCapabilitiesRequests {
+                            range: Some(false),
+                            full: Some(lsp::SemanticTokensFullOptions::Delta { delta: Some(true) }),
+                        },
+                        token_types: vec![
+                            lsp::SemanticTokenType::NAMESPACE,
+                            lsp::SemanticTokenType::TYPE,
+                            lsp::SemanticTokenType::CLASS,
+                            lsp::SemanticTokenType::ENUM,
+                            lsp::SemanticTokenType::INTERFACE,
+                            lsp::SemanticTokenType::STRUCT,
+                            lsp::SemanticTokenType::TYPE_PARAMETER,
+                            lsp::SemanticTokenType::PARAMETER,
+                            lsp::SemanticTokenType::VARIABLE,
+                            lsp::SemanticTokenType::PROPERTY,
+                            lsp::SemanticTokenType::ENUM_MEMBER,
+                            lsp::SemanticTokenType::EVENT,
+                            lsp::SemanticTokenType::FUNCTION,
+                            lsp::SemanticTokenType::METHOD,
+                            lsp::SemanticTokenType::MACRO,
+                            lsp::SemanticTokenType::KEYWORD,
+                            lsp::SemanticTokenType::MODIFIER,
+                            lsp::SemanticTokenType::COMMENT,
+                            lsp::SemanticTokenType::STRING,
+                            lsp::SemanticTokenType::NUMBER,
+                            lsp::SemanticTokenType::REGEXP,
+                            lsp::SemanticTokenType::OPERATOR,
+                            lsp::SemanticTokenType::DECORATOR,
+                        ],
+                        token_modifiers: vec![
+                            lsp::SemanticTokenModifier::DECLARATION,
+                            lsp::SemanticTokenModifier::DEFINITION,
+                            lsp::SemanticTokenModifier::READONLY,
+                            lsp::SemanticTokenModifier::STATIC,
+                            lsp::SemanticTokenModifier::DEPRECATED,
+                            lsp::SemanticTokenModifier::ABSTRACT,
+                            lsp::SemanticTokenModifier::ASYNC,
+                            lsp::SemanticTokenModifier::MODIFICATION,
+                            lsp::SemanticTokenModifier::DOCUMENTATION,
+                            lsp::SemanticTokenModifier::DEFAULT_LIBRARY,
+                        ],
+                        formats: vec![lsp::TokenFormat::RELATIVE],
+                        overlapping_token_support: Some(false),
+                        multiline_token_support: Some(false),
+                        server_cancel_support: Some(false),
+                        augments_syntax_tokens: Some(true),
+                    }),
                     document_symbol: Some(lsp::DocumentSymbolClientCapabilities {
                         dynamic_registration: Some(false),
                         symbol_kind: Some(lsp::SymbolKindCapability {
@@ -1213,6 +1368,23 @@ pub fn resolve_code_action(
         Some(self.call_with_ref::<lsp::request::CodeActionResolveRequest>(code_action))
     }
 
+    pub fn resolve_code_lens(
+        &self,
+        code_lens: &lsp::CodeLens,
+    ) -> Option<impl Future<Output = Result<lsp::CodeLens>>> {
+        let capabilities = self.capabilities.get().unwrap();
+
+        // Return early if the server does not support resolving code lenses.
+        match capabilities.code_lens_provider {
+            Some(lsp::CodeLensOptions {
+                resolve_provider: Some(true),
+            }) => (),
+            _ => return None,
+        }
+
+        Some(self.call_with_ref::<lsp::request::CodeLensResolve>(code_lens))
+    }
+
     pub fn text_document_signature_help(
         &self,
         text_document: lsp::TextDocumentIdentifier,
@@ -1281,6 +1453,80 @@ pub fn text_document_document_color(
         Some(self.call::<lsp::request::DocumentColor>(params))
     }
 
+    pub fn text_document_folding_range(
+        &self,
+        text_document: lsp::TextDocumentIdentifier,
+        work_done_token: Option<lsp::ProgressToken>,
+    ) -> Option<impl Future<Output = Result<Option<Vec<lsp::FoldingRange>>>>> {
+        if !self.supports_feature(LanguageServerFeature::FoldingRanges) {
+            return None;
+        }
+
+        let params = lsp::FoldingRangeParams {
+            text_document,
+            work_done_progress_params: lsp::WorkDoneProgressParams { work_done_token },
+            partial_result_params: lsp::PartialResultParams::default(),
+        };
+
+        Some(self.call::<lsp::request::FoldingRangeRequest>(params))
+    }
+
+    pub fn text_document_semantic_tokens_full(
+        &self,
+        text_document: lsp::TextDocumentIdentifier,
+        work_done_token: Option<lsp::ProgressToken>,
+    ) -> Option<impl Future<Output = Result<Option<lsp::SemanticTokensResult>>>> {
+        if !self.supports_feature(LanguageServerFeature::SemanticTokens) {
+            return None;
+        }
+
+        let params = lsp::SemanticTokensParams {
+            text_document,
+            work_done_progress_params: lsp::WorkDoneProgressParams { work_done_token },
+            partial_result_params: lsp::PartialResultParams::default(),
+        };
+
+        Some(self.call::<lsp::request::SemanticTokensFullRequest>(params))
+    }
+
+    pub fn text_document_semantic_tokens_full_delta(
+        &self,
+        text_document: lsp::TextDocumentIdentifier,
+        previous_result_id: String,
+        work_done_token: Option<lsp::ProgressToken>,
+    ) -> Option<impl Future<Output = Result<Option<lsp::SemanticTokensFullDeltaResult>>>> {
+        if !self.supports_semantic_tokens_delta() {
+            return None;
+        }
+
+        let params = lsp::SemanticTokensDeltaParams {
+            text_document,
+            previous_result_id,
+            work_done_progress_params: lsp::WorkDoneProgressParams { work_done_token },
+            partial_result_params: lsp::PartialResultParams::default(),
+        };
+
+        Some(self.call::<lsp::request::SemanticTokensFullDeltaRequest>(params))
+    }
+
+    pub fn text_document_code_lens(
+        &self,
+        text_document: lsp::TextDocumentIdentifier,
+        work_done_token: Option<lsp::ProgressToken>,
+    ) -> Option<impl Future<Output = Result<Option<Vec<lsp::CodeLens>>>>> {
+        if !self.supports_feature(LanguageServerFeature::CodeLens) {
+            return None;
+        }
+
+        let params = lsp::CodeLensParams {
+            text_document,
+            work_done_progress_params: lsp::WorkDoneProgressParams { work_done_token },
+            partial_result_params: lsp::PartialResultParams::default(),
+        };
+
+        Some(self.call::<lsp::request::CodeLensRequest>(params))
+    }
+
     pub fn text_document_document_link(
         &self,
         text_document: lsp::TextDocumentIdentifier,
@@ -1419,6 +1665,48 @@ pub fn text_document_diagnostic(
         Some(self.call::<lsp::request::DocumentDiagnosticRequest>(params))
     }
 
+    /// Returns whether the server supports `workspace/diagnostic` requests.
+    pub fn supports_workspace_diagnostics(&self) -> bool {
+        match self.capabilities().diagnostic_provider.as_ref() {
+            Some(lsp::DiagnosticServerCapabilities::Options(options)) => {
+                options.workspace_diagnostics
+            }
+            Some(lsp::DiagnosticServerCapabilities::RegistrationOptions(options)) => {
+                options.diagnostic_options.workspace_diagnostics
+            }
+            None => false,
+        }
+    }
+
+    pub fn workspace_diagnostic(
+        &self,
+        previous_result_ids: Vec<lsp::PreviousResultId>,
+        partial_result_token: Option<lsp::ProgressToken>,
+    ) -> Option<impl Future<Output = Result<lsp::WorkspaceDiagnosticReportResult>>> {
+        if !self.supports_workspace_diagnostics() {
+            return None;
+        }
+
+        let identifier = match self.capabilities().diagnostic_provider.as_ref()? {
+            lsp::DiagnosticServerCapabilities::Options(cap) => cap.identifier.clone(),
+            lsp::DiagnosticServerCapabilities::RegistrationOptions(cap) => {
+                cap.diagnostic_options.identifier.clone()
+            }
+        };
+
+        let params = lsp::WorkspaceDiagnosticParams {
+            identifier,
+            previous_result_ids,
+            work_done_progress_params: lsp::WorkDoneProgressParams::default(),
+            partial_result_params: lsp::PartialResultParams {
+                partial_result_token,
+            },
+        };
+
+        // Servers may stream partial results for a long time before the request completes.
+        Some(self.call_with_timeout::<lsp::request::WorkspaceDiagnosticRequest>(&params, 300))
+    }
+
     pub fn text_document_document_highlight(
         &self,
         text_document: lsp::TextDocumentIdentifier,
@@ -1447,6 +1735,91 @@ pub fn text_document_document_highlight(
         Some(self.call::<lsp::request::DocumentHighlightRequest>(params))
     }
 
+    pub fn text_document_linked_editing_range(
+        &self,
+        text_document: lsp::TextDocumentIdentifier,
+        position: lsp::Position,
+        work_done_token: Option<lsp::ProgressToken>,
+    ) -> Option<impl Future<Output = Result<Option<lsp::LinkedEditingRanges>>>> {
+        if !self.supports_feature(LanguageServerFeature::LinkedEditingRange) {
+            return None;
+        }
+
+        let params = lsp::LinkedEditingRangeParams {
+            text_document_position_params: lsp::TextDocumentPositionParams {
+                text_document,
+                position,
+            },
+            work_done_progress_params: lsp::WorkDoneProgressParams { work_done_token },
+        };
+
+        Some(self.call::<lsp::request::LinkedEditingRange>(params))
+    }
+
+    pub fn text_document_selection_range(
+        &self,
+        text_document: lsp::TextDocumentIdentifier,
+        positions: Vec<lsp::Position>,
+        work_done_token: Option<lsp::ProgressToken>,
+    ) -> Option<impl Future<Output = Result<Option<Vec<lsp::SelectionRange>>>>> {
+        if !self.supports_feature(LanguageServerFeature::SelectionRange) {
+            return None;
+        }
+
+        let params = lsp::SelectionRangeParams {
+            text_document,
+            positions,
+            work_done_progress_params: lsp::WorkDoneProgressParams { work_done_token },
+            partial_result_params: lsp::PartialResultParams::default(),
+        };
+
+        Some(self.call::<lsp::request::SelectionRangeRequest>(params))
+    }
+
+    pub fn text_document_inline_completion(
+        &self,
+        text_document: lsp::TextDocumentIdentifier,
+        position: lsp::Position,
+        context: lsp::InlineCompletionContext,
+        work_done_token: Option<lsp::ProgressToken>,
+    ) -> Option<impl Future<Output = Result<Option<lsp::InlineCompletionResponse>>>> {
+        if !self.supports_feature(LanguageServerFeature::InlineCompletion) {
+            return None;
+        }
+
+        let params = lsp::InlineCompletionParams {
+            work_done_progress_params: lsp::WorkDoneProgressParams { work_done_token },
+            text_document_position: lsp::TextDocumentPositionParams {
+                text_document,
+                position,
+            },
+            context,
+        };
+
+        Some(self.call::<lsp::request::InlineCompletionRequest>(params))
+    }
+
+    pub fn text_document_inline_value(
+        &self,
+        text_document: lsp::TextDocumentIdentifier,
+        range: lsp::Range,
+        context: lsp::InlineValueContext,
+        work_done_token: Option<lsp::ProgressToken>,
+    ) -> Option<impl Future<Output = Result<Option<Vec<lsp::InlineValue>>>>> {
+        if !self.supports_feature(LanguageServerFeature::InlineValue) {
+            return None;
+        }
+
+        let params = lsp::InlineValueParams {
+            work_done_progress_params: lsp::WorkDoneProgressParams { work_done_token },
+            text_document,
+            range,
+            context,
+        };
+
+        Some(self.call::<lsp::request::InlineValueRequest>(params))
+    }
+
     fn goto_request<
         T: lsp::request::Request<
             Params = lsp::GotoDefinitionParams,
@@ -1691,6 +2064,60 @@ pub fn call_hierarchy_outgoing(
         Some(self.call::<lsp::request::CallHierarchyOutgoingCalls>(params))
     }
 
+    pub fn prepare_type_hierarchy(
+        &self,
+        text_document: lsp::TextDocumentIdentifier,
+        position: lsp::Position,
+    ) -> Option<impl Future<Output = Result<Option<Vec<lsp::TypeHierarchyItem>>>>> {
+        if !self.supports_feature(LanguageServerFeature::TypeHierarchy) {
+            return None;
+        }
+
+        let params = lsp::TypeHierarchyPrepareParams {
+            text_document_position_params: lsp::TextDocumentPositionParams {
+                text_document,
+                position,
+            },
+            work_done_progress_params: lsp::WorkDoneProgressParams::default(),
+        };
+
+        Some(self.call::<lsp::request::TypeHierarchyPrepare>(params))
+    }
+
+    pub fn type_hierarchy_supertypes(
+        &self,
+        item: lsp::TypeHierarchyItem,
+    ) -> Option<impl Future<Output = Result<Option<Vec<lsp::TypeHierarchyItem>>>>> {
+        if !self.supports_feature(LanguageServerFeature::TypeHierarchy) {
+            return None;
+        }
+
+        let params = lsp::TypeHierarchySupertypesParams {
+            item,
+            work_done_progress_params: lsp::WorkDoneProgressParams::default(),
+            partial_result_params: lsp::PartialResultParams::default(),
+        };
+
+        Some(self.call::<lsp::request::TypeHierarchySupertypes>(params))
+    }
+
+    pub fn type_hierarchy_subtypes(
+        &self,
+        item: lsp::TypeHierarchyItem,
+    ) -> Option<impl Future<Output = Result<Option<Vec<lsp::TypeHierarchyItem>>>>> {
+        if !self.supports_feature(LanguageServerFeature::TypeHierarchy) {
+            return None;
+        }
+
+        let params = lsp::TypeHierarchySubtypesParams {
+            item,
+            work_done_progress_params: lsp::WorkDoneProgressParams::default(),
+            partial_result_params: lsp::PartialResultParams::default(),
+        };
+
+        Some(self.call::<lsp::request::TypeHierarchySubtypes>(params))
+    }
+
     pub fn prepare_rename(
         &self,
         text_document: lsp::TextDocumentIdentifier,
@@ -487,6 +487,8 @@ pub enum MethodCall {
     UnregisterCapability(lsp::UnregistrationParams),
     ShowDocument(lsp::ShowDocumentParams),
     WorkspaceDiagnosticRefresh,
+    SemanticTokensRefresh,
+    CodeLensRefresh,
     ShowMessageRequest(lsp::ShowMessageRequestParams),
 }
 
@@ -520,6 +522,8 @@ pub fn parse(method: &str, params: jsonrpc::Params) -> Result<MethodCall> {
                 Self::ShowDocument(params)
             }
             lsp::request::WorkspaceDiagnosticRefresh::METHOD => Self::WorkspaceDiagnosticRefresh,
+            lsp::request::SemanticTokensRefresh::METHOD => Self::SemanticTokensRefresh,
+            lsp::request::CodeLensRefresh::METHOD => Self::CodeLensRefresh,
             lsp::request::ShowMessageRequest::METHOD => {
                 let params: lsp::ShowMessageRequestParams = params.parse()?;
                 Self::ShowMessageRequest(params)
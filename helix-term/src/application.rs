@@ -11,6 +11,7 @@
     document::{DocumentOpenError, DocumentSavedEventResult},
     editor::{ConfigEvent, EditorEvent},
     graphics::Rect,
+    handlers::lsp::{CodeLensEvent, SemanticTokensEvent},
     theme,
     tree::Layout,
     Align, Editor,
@@ -137,14 +138,35 @@ pub fn new(
         let keys = Box::new(Map::new(Arc::clone(&config), |config: &Config| {
             &config.keys
         }));
-        let editor_view = Box::new(ui::EditorView::new(Keymaps::new(keys)));
+        let language_keys = Box::new(Map::new(Arc::clone(&config), |config: &Config| {
+            &config.language_keys
+        }));
+        let editor_view = Box::new(ui::EditorView::new(
+            Keymaps::new(keys).with_language_maps(language_keys),
+        ));
         compositor.push(editor_view);
 
         let jobs = Jobs::new();
 
+        if editor.config().session.persist_macros {
+            editor.restore_macros();
+        }
+
+        // Restored after the macros, as they hold the latest contents of the registers, and
+        // before a named session, whose registers take precedence.
+        if !editor.config().session.persist_registers.is_empty()
+            || editor.config().session.persist_yank_history
+        {
+            editor.restore_registers();
+        }
+
         let mut startup_ripgrep = None;
 
-        if args.load_tutor {
+        if let Some(name) = &args.session {
+            editor.new_file(Action::VerticalSplit);
+            let session = helix_view::session::Session::load(name)?;
+            editor.restore_session(&session)?;
+        } else if args.load_tutor {
             let path = helix_loader::runtime_file(Path::new("tutor"));
             editor.open(&path, Action::VerticalSplit)?;
             // Unset path to prevent accidentally saving to the original tutor file.
@@ -261,6 +283,10 @@ pub fn new(
             startup_ripgrep,
         };
 
+        if app.editor.config().session.persist_breakpoints {
+            app.editor.restore_breakpoints();
+        }
+
         let gc_max_age = app.editor.config().session.gc_max_age;
         if app.editor.config().session.restore_cursor
             && gc_max_age > 0
@@ -457,7 +483,7 @@ pub fn handle_config_events(&mut self, config_event: ConfigEvent) {
 
     fn refresh_config(&mut self) {
         let mut refresh_config = || -> Result<(), Error> {
-            let default_config = Config::load_default()
+            let mut default_config = Config::load_default()
                 .map_err(|err| anyhow::anyhow!("Failed to load config: {}", err))?;
 
             // Apply any change to editor.workspace_trust before reading local language config.
@@ -469,6 +495,7 @@ fn refresh_config(&mut self) {
             // call `Loader::set_scopes` which must be done before the documents are re-parsed for
             // the sake of locals highlighting.
             let lang_loader = helix_core::config::user_lang_loader(&self.editor.workspace_trust)?;
+            default_config.load_language_keys(&lang_loader)?;
             self.editor.syn_loader.store(Arc::new(lang_loader));
             Self::load_configured_theme(
                 &mut self.editor,
@@ -897,19 +924,30 @@ macro_rules! language_server {
                     Notification::LogMessage(params) => {
                         log::info!("window/logMessage: {:?}", params);
                     }
-                    Notification::ProgressMessage(params)
-                        if !self
-                            .compositor
-                            .has_component(std::any::type_name::<ui::Prompt>()) =>
+                    Notification::ProgressMessage(lsp::ProgressParams {
+                        token,
+                        value: lsp::ProgressParamsValue::PartialResult(value),
+                    }) => {
+                        if !handlers::diagnostics::handle_workspace_diagnostic_partial_result(
+                            &mut self.editor,
+                            server_id,
+                            &token,
+                            value,
+                        ) {
+                            log::warn!("Unexpected partial result for token {token:?}");
+                        }
+                    }
+                    Notification::ProgressMessage(lsp::ProgressParams {
+                        token,
+                        value: lsp::ProgressParamsValue::WorkDone(work),
+                    }) if !self
+                        .compositor
+                        .has_component(std::any::type_name::<ui::Prompt>()) =>
                     {
                         let editor_view = self
                             .compositor
                             .find::<ui::EditorView>()
                             .expect("expected at least one EditorView");
-                        let lsp::ProgressParams {
-                            token,
-                            value: lsp::ProgressParamsValue::WorkDone(work),
-                        } = params;
                         let (title, message, percentage) = match &work {
                             lsp::WorkDoneProgress::Begin(lsp::WorkDoneProgressBegin {
                                 title,
@@ -933,6 +971,10 @@ macro_rules! language_server {
                                             &mut self.editor,
                                             server_id,
                                         );
+                                        handlers::diagnostics::request_workspace_diagnostics(
+                                            &mut self.editor,
+                                            server_id,
+                                        );
                                     }
                                     self.editor.clear_status();
 
@@ -1003,6 +1045,7 @@ macro_rules! language_server {
                         }
 
                         self.editor.diagnostics.retain(|_, diags| !diags.is_empty());
+                        self.editor.workspace_diagnostics.remove(&server_id);
 
                         // Clear any diagnostics for documents with this server open.
                         for doc in self.editor.documents_mut() {
@@ -1176,6 +1219,36 @@ macro_rules! language_server {
                             &mut self.editor,
                             server_id,
                         );
+                        handlers::diagnostics::request_workspace_diagnostics(
+                            &mut self.editor,
+                            server_id,
+                        );
+
+                        Ok(serde_json::Value::Null)
+                    }
+                    Ok(MethodCall::SemanticTokensRefresh) => {
+                        let server_id = language_server!().id();
+                        for doc in self.editor.documents() {
+                            if doc.supports_language_server(server_id) {
+                                helix_event::send_blocking(
+                                    &self.editor.handlers.semantic_tokens,
+                                    SemanticTokensEvent(doc.id()),
+                                );
+                            }
+                        }
+
+                        Ok(serde_json::Value::Null)
+                    }
+                    Ok(MethodCall::CodeLensRefresh) => {
+                        let server_id = language_server!().id();
+                        for doc in self.editor.documents() {
+                            if doc.supports_language_server(server_id) {
+                                helix_event::send_blocking(
+                                    &self.editor.handlers.code_lenses,
+                                    CodeLensEvent(doc.id()),
+                                );
+                            }
+                        }
 
                         Ok(serde_json::Value::Null)
                     }
@@ -1394,6 +1467,20 @@ pub async fn close(&mut self) -> Vec<anyhow::Error> {
             self.editor.session_state.save();
         }
 
+        if self.editor.config().session.persist_breakpoints {
+            self.editor.save_breakpoints();
+        }
+
+        if self.editor.config().session.persist_macros {
+            self.editor.save_macros();
+        }
+
+        if !self.editor.config().session.persist_registers.is_empty()
+            || self.editor.config().session.persist_yank_history
+        {
+            self.editor.save_registers();
+        }
+
         if let Err(err) = self
             .jobs
             .finish(&mut self.editor, Some(&mut self.compositor))
@@ -20,6 +20,7 @@ pub struct Args {
     pub config_file: Option<PathBuf>,
     pub files: IndexMap<PathBuf, Vec<Position>>,
     pub working_directory: Option<PathBuf>,
+    pub session: Option<String>,
 }
 
 impl Args {
@@ -91,6 +92,10 @@ pub fn parse_args() -> Result<Args> {
                         anyhow::bail!("--working-dir must specify an initial working directory")
                     }
                 },
+                "--session" => match argv.next() {
+                    Some(name) => args.session = Some(name),
+                    None => anyhow::bail!("--session must specify a session name"),
+                },
                 arg if arg.starts_with("--") => {
                     anyhow::bail!("unexpected double dash argument: {}", arg)
                 }
@@ -121,6 +126,13 @@ pub fn parse_args() -> Result<Args> {
             insert_file_with_position(&arg);
         }
 
+        if args.session.is_some() && !args.files.is_empty() {
+            anyhow::bail!("--session cannot be combined with files");
+        }
+        if args.session.is_some() && args.working_directory.is_some() {
+            anyhow::bail!("--session restores its own working directory and cannot be combined with --working-dir");
+        }
+
         if line_number != 0 {
             if let Some(first_position) = args
                 .files
@@ -28,16 +28,18 @@
 use helix_core::{
     char_idx_at_visual_offset,
     chars::char_is_word,
-    command_line::{self, Args},
+    command_line::{self, Args, Tokenizer},
     comment,
+    conflict::{self, Resolution},
     doc_formatter::TextFormat,
     encoding, find_workspace,
+    fold::{self, Fold},
     graphemes::{self, next_grapheme_boundary},
     history::UndoKind,
     increment,
     indent::{self, IndentStyle},
     line_ending::{get_line_ending_of_str, line_end_char_index},
-    match_brackets,
+    linked_editing, match_brackets,
     movement::{self, Direction, Movement},
     object, pos_at_coords,
     regex::{self, Regex},
@@ -212,10 +214,12 @@ fn make_job_callback<T, F>(
 /// There are three kinds:
 ///
 /// * Static: commands usually bound to keys and used for editing, movement,
-///   etc., for example `move_char_left`.
+///   etc., for example `move_char_left`. They may be bound with arguments, for example
+///   `select_textobject_around f`, see `StaticArgs`.
 /// * Typable: commands executable from command mode, prefixed with a `:`,
 ///   for example `:write!`.
-/// * Macro: a sequence of keys to execute, for example `@miw`.
+/// * Macro: a sequence of keys to execute, for example `@miw`, or the macro recorded in a
+///   register, for example `macro:q`.
 #[derive(Clone)]
 pub enum MappableCommand {
     Typable {
@@ -227,10 +231,14 @@ pub enum MappableCommand {
         name: &'static str,
         fun: fn(cx: &mut Context),
         doc: &'static str,
+        args: StaticArgs,
     },
     Macro {
         name: String,
         keys: Vec<KeyEvent>,
+        /// The register the keys are read from when the macro is executed, in which case `keys`
+        /// is empty.
+        register: Option<char>,
     },
 }
 
@@ -241,7 +249,8 @@ macro_rules! static_commands {
             pub const $name: Self = Self::Static {
                 name: stringify!($name),
                 fun: $name,
-                doc: $doc
+                doc: $doc,
+                args: StaticArgs::NONE,
             };
         )*
 
@@ -270,24 +279,33 @@ pub fn execute(&self, cx: &mut Context) {
                     cx.editor.set_error(format!("no such command: '{name}'"));
                 }
             }
-            Self::Static { fun, .. } => (fun)(cx),
-            Self::Macro { keys, .. } => {
-                // Protect against recursive macros.
-                if cx.editor.macro_replaying.contains(&'@') {
-                    cx.editor.set_error(
-                        "Cannot execute macro because the [@] register is already playing a macro",
-                    );
-                    return;
+            Self::Static {
+                name, fun, args, ..
+            } => {
+                let count = cx.count;
+                if args.count.is_some() {
+                    cx.count = args.count;
                 }
-                cx.editor.macro_replaying.push('@');
-                let keys = keys.clone();
-                cx.callback.push(Box::new(move |compositor, cx| {
-                    for key in keys.into_iter() {
-                        compositor.handle_event(&compositor::Event::Key(key), cx);
-                    }
-                    cx.editor.macro_replaying.pop();
-                }));
+                (fun)(cx);
+                // Answer the keys the command waits for with the bound ones.
+                for &key in &args.keys {
+                    let Some((on_next_key, _)) = cx.on_next_key_callback.take() else {
+                        cx.editor
+                            .set_error(format!("{name} doesn't wait for the bound key {key}"));
+                        break;
+                    };
+                    on_next_key(cx, key);
+                }
+                cx.count = count;
+            }
+            Self::Macro {
+                register: Some(register),
+                ..
+            } => {
+                let count = cx.count();
+                replay_macro_from(cx, *register, count);
             }
+            Self::Macro { keys, .. } => replay_keys(cx, '@', keys.clone(), 1),
         }
     }
 
@@ -429,8 +447,10 @@ pub fn doc(&self) -> &str {
         file_explorer_in_current_buffer_directory, "Open file explorer at current buffer's directory",
         file_explorer_in_current_directory, "Open file explorer at current working directory",
         code_action, "Perform code action",
+        code_lens_picker, "Open code lens picker",
         buffer_picker, "Open buffer picker",
         jumplist_picker, "Open jumplist picker",
+        session_picker, "Open session picker",
         symbol_picker, "Open symbol picker",
         syntax_symbol_picker, "Open symbol picker from syntax information",
         lsp_or_syntax_symbol_picker, "Open symbol picker from LSP or syntax information",
@@ -466,6 +486,8 @@ pub fn doc(&self) -> &str {
         goto_file_hsplit, "Goto files in selections (hsplit)",
         goto_file_vsplit, "Goto files in selections (vsplit)",
         goto_reference, "Goto references",
+        type_hierarchy_supertypes, "Open supertypes picker",
+        type_hierarchy_subtypes, "Open subtypes picker",
         goto_window_top, "Goto window top",
         goto_window_center, "Goto window center",
         goto_window_bottom, "Goto window bottom",
@@ -483,6 +505,15 @@ pub fn doc(&self) -> &str {
         goto_prev_change, "Goto previous change",
         goto_first_change, "Goto first change",
         goto_last_change, "Goto last change",
+        stage_hunk, "Stage the change under the cursor",
+        unstage_hunk, "Unstage the staged change under the cursor",
+        stage_selection, "Stage the changes in the selections",
+        show_hunk_diff, "Toggle showing the lines the change under the cursor replaced",
+        goto_next_conflict, "Goto next merge conflict",
+        goto_prev_conflict, "Goto previous merge conflict",
+        accept_ours, "Resolve the merge conflicts in the selections to our version",
+        accept_theirs, "Resolve the merge conflicts in the selections to their version",
+        accept_both, "Resolve the merge conflicts in the selections to both versions",
         goto_line_start, "Goto line start",
         goto_line_end, "Goto line end",
         goto_column, "Goto column",
@@ -522,6 +553,7 @@ pub fn doc(&self) -> &str {
         yank_joined_to_primary_clipboard, "Join and yank selections to primary clipboard",
         yank_main_selection_to_primary_clipboard, "Yank main selection to primary clipboard",
         replace_with_yanked, "Replace with yanked text",
+        yank_history_picker, "Open picker of the yank history and paste the selected entry",
         replace_selections_with_clipboard, "Replace selections by clipboard content",
         replace_selections_with_primary_clipboard, "Replace selections by primary clipboard",
         paste_after, "Paste after selection",
@@ -543,6 +575,10 @@ pub fn doc(&self) -> &str {
         keep_primary_selection, "Keep primary selection",
         remove_primary_selection, "Remove primary selection",
         completion, "Invoke completion popup",
+        inline_completion_accept, "Accept inline completion",
+        inline_completion_accept_word, "Accept next word of inline completion",
+        inline_completion_next, "Show next inline completion candidate",
+        inline_completion_dismiss, "Dismiss inline completion",
         steal_char_above, "Type the character above you",
         steal_char_below, "Type the character below you",
         hover, "Show docs for item under cursor",
@@ -597,6 +633,11 @@ pub fn doc(&self) -> &str {
         align_view_bottom, "Align view bottom",
         scroll_up, "Scroll view up",
         scroll_down, "Scroll view down",
+        fold, "Fold the innermost open region at the cursor",
+        unfold, "Unfold the closed region at the cursor",
+        toggle_fold, "Toggle the fold at the cursor",
+        fold_all, "Fold all regions (count: minimum nesting level)",
+        unfold_all, "Unfold all regions",
         match_brackets, "Goto matching bracket",
         surround_add, "Surround add",
         surround_replace, "Surround replace",
@@ -628,9 +669,14 @@ pub fn doc(&self) -> &str {
         dap_step_out, "Step out",
         dap_next, "Step to next",
         dap_variables, "List variables",
+        dap_toggle_panel, "Open, focus or close the debug panel",
+        dap_toggle_console, "Open, focus or close the debug console",
         dap_terminate, "End debug session",
         dap_edit_condition, "Edit breakpoint condition on current line",
+        dap_edit_hit_condition, "Edit breakpoint hit condition on current line",
         dap_edit_log, "Edit breakpoint log message on current line",
+        dap_toggle_breakpoint_enabled, "Enable or disable breakpoint on current line",
+        dap_breakpoint_picker, "Open breakpoint picker",
         dap_switch_thread, "Switch current thread",
         dap_switch_stack_frame, "Switch stack frame",
         dap_enable_exceptions, "Enable exception breakpoints",
@@ -646,6 +692,7 @@ pub fn doc(&self) -> &str {
         decrement, "Decrement item under cursor",
         record_macro, "Record macro",
         replay_macro, "Replay macro",
+        replay_macro_until, "Replay macro until it fails or changes nothing",
         command_palette, "Open command palette",
         goto_word, "Jump to a two-character label",
         flash_forward, "Jump forward with a flash",
@@ -675,12 +722,68 @@ pub fn doc(&self) -> &str {
     );
 }
 
+/// The arguments a static command is bound with, e.g. `select_textobject_around f` or
+/// `goto_line --count 10`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StaticArgs {
+    /// Replaces the count typed before the command, given with `--count <n>`.
+    pub count: Option<NonZeroUsize>,
+    /// Keys passed in order to the pending-key prompts of the command instead of waiting for
+    /// the next keypresses.
+    pub keys: Vec<KeyEvent>,
+}
+
+impl StaticArgs {
+    pub const NONE: Self = Self {
+        count: None,
+        keys: Vec::new(),
+    };
+}
+
+impl std::str::FromStr for StaticArgs {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut args = Self::NONE;
+        let mut tokens = Tokenizer::new(s, true);
+        while let Some(token) = tokens.next() {
+            let token = token.map_err(|err| anyhow!("{err}"))?;
+            if token.content == "--count" {
+                let count = tokens
+                    .next()
+                    .transpose()
+                    .map_err(|err| anyhow!("{err}"))?
+                    .ok_or_else(|| anyhow!("Expected a count after '--count'"))?;
+                args.count = Some(
+                    count
+                        .content
+                        .parse()
+                        .map_err(|_| anyhow!("Invalid count '{}'", count.content))?,
+                );
+            } else {
+                args.keys.push(
+                    token
+                        .content
+                        .parse()
+                        .map_err(|err| anyhow!("Invalid key '{}': {err}", token.content))?,
+                );
+            }
+        }
+        Ok(args)
+    }
+}
+
 impl fmt::Debug for MappableCommand {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            MappableCommand::Static { name, .. } => {
+            MappableCommand::Static { name, args, .. } if *args == StaticArgs::NONE => {
                 f.debug_tuple("MappableCommand").field(name).finish()
             }
+            MappableCommand::Static { name, args, .. } => f
+                .debug_tuple("MappableCommand")
+                .field(name)
+                .field(args)
+                .finish(),
             MappableCommand::Typable { name, args, .. } => f
                 .debug_tuple("MappableCommand")
                 .field(name)
@@ -727,13 +830,40 @@ fn from_str(s: &str) -> Result<Self, Self::Err> {
             helix_view::input::parse_macro(suffix).map(|keys| Self::Macro {
                 name: s.to_string(),
                 keys,
+                register: None,
             })
+        } else if let Some(suffix) = s.strip_prefix("macro:") {
+            let mut chars = suffix.chars();
+            match (chars.next(), chars.next()) {
+                (Some(register), None) => Ok(Self::Macro {
+                    name: s.to_string(),
+                    keys: Vec::new(),
+                    register: Some(register),
+                }),
+                _ => Err(anyhow!(
+                    "Expected a register after 'macro:', got '{suffix}'"
+                )),
+            }
         } else {
-            MappableCommand::STATIC_COMMAND_LIST
+            let (name, args, _) = command_line::split(s);
+            let command = MappableCommand::STATIC_COMMAND_LIST
                 .iter()
-                .find(|cmd| cmd.name() == s)
+                .find(|cmd| cmd.name() == name)
                 .cloned()
-                .ok_or_else(|| anyhow!("No command named '{}'", s))
+                .ok_or_else(|| anyhow!("No command named '{}'", name))?;
+            match command {
+                MappableCommand::Static { name, fun, doc, .. } if !args.trim().is_empty() => {
+                    Ok(MappableCommand::Static {
+                        name,
+                        fun,
+                        doc,
+                        args: args
+                            .parse()
+                            .with_context(|| format!("Invalid arguments for '{name}'"))?,
+                    })
+                }
+                command => Ok(command),
+            }
         }
     }
 }
@@ -765,12 +895,16 @@ fn eq(&self, other: &Self) -> bool {
             ) => first_name == second_name && first_args == second_args,
             (
                 MappableCommand::Static {
-                    name: first_name, ..
+                    name: first_name,
+                    args: first_args,
+                    ..
                 },
                 MappableCommand::Static {
-                    name: second_name, ..
+                    name: second_name,
+                    args: second_args,
+                    ..
                 },
-            ) => first_name == second_name,
+            ) => first_name == second_name && first_args == second_args,
             _ => false,
         }
     }
@@ -3764,7 +3898,7 @@ fn delete_selection_impl(cx: &mut Context, op: Operation, yank: YankAction) {
         let reg_name = cx
             .register
             .unwrap_or_else(|| cx.editor.config.load().default_yank_register);
-        if let Err(err) = cx.editor.registers.write(reg_name, values) {
+        if let Err(err) = cx.editor.registers.yank(reg_name, values) {
             cx.editor.set_error(err.to_string());
             return;
         }
@@ -4189,6 +4323,69 @@ struct BufferMeta<'a> {
     cx.push_layer(Box::new(overlaid(picker)));
 }
 
+fn yank_history_picker(cx: &mut Context) {
+    struct YankMeta {
+        values: Vec<String>,
+        /// The values separated by rules, shown in the preview.
+        text: String,
+    }
+
+    let items: Vec<_> = cx
+        .editor
+        .registers
+        .yank_history()
+        .map(|values| YankMeta {
+            values: values.to_vec(),
+            text: values.join("\n────\n"),
+        })
+        .collect();
+    if items.is_empty() {
+        cx.editor.set_error("Yank history is empty");
+        return;
+    }
+
+    let columns = [
+        PickerColumn::new("selections", |meta: &YankMeta, _| {
+            meta.values.len().to_string().into()
+        }),
+        PickerColumn::new("text", |meta: &YankMeta, _| {
+            let mut lines = meta.values.iter().flat_map(|value| value.lines());
+            let first = lines.next().unwrap_or_default().trim();
+            if lines.next().is_some() {
+                format!("{first} …").into()
+            } else {
+                first.to_owned().into()
+            }
+        }),
+    ];
+
+    let count = cx.count();
+    let picker = Picker::new(
+        columns,
+        1,
+        items,
+        (),
+        move |cx, meta: &YankMeta, _action| {
+            let mode = cx.editor.mode;
+            let (view, doc) = current!(cx.editor);
+            paste_impl(
+                &meta.values,
+                doc,
+                view,
+                Paste::After,
+                count,
+                mode,
+                PasteType::Default,
+            );
+            if mode == Mode::Select {
+                cx.editor.mode = Mode::Normal;
+            }
+        },
+    )
+    .with_preview(|_editor, meta| Some((ui::picker::PathOrId::Text(&meta.text), None)));
+    cx.push_layer(Box::new(overlaid(picker)));
+}
+
 fn jumplist_picker(cx: &mut Context) {
     struct JumpMeta<'a> {
         id: DocumentId,
@@ -4277,6 +4474,53 @@ struct JumpMeta<'a> {
     cx.push_layer(Box::new(overlaid(picker)));
 }
 
+fn session_picker(cx: &mut Context) {
+    use helix_view::session::Session;
+
+    struct SessionMeta {
+        name: String,
+        session: Session,
+    }
+
+    let items: Vec<_> = Session::list()
+        .into_iter()
+        .filter_map(|name| match Session::load(&name) {
+            Ok(session) => Some(SessionMeta { name, session }),
+            Err(err) => {
+                log::warn!("{err:#}");
+                None
+            }
+        })
+        .collect();
+    if items.is_empty() {
+        cx.editor.set_error("No saved sessions");
+        return;
+    }
+
+    let columns = [
+        ui::PickerColumn::new("name", |meta: &SessionMeta, _| meta.name.as_str().into()),
+        ui::PickerColumn::new("directory", |meta: &SessionMeta, _| {
+            helix_stdx::path::fold_home_dir(&meta.session.cwd)
+                .to_string_lossy()
+                .into_owned()
+                .into()
+        }),
+        ui::PickerColumn::new("buffers", |meta: &SessionMeta, _| {
+            meta.session.buffers.len().to_string().into()
+        }),
+    ];
+
+    let picker = Picker::new(columns, 0, items, (), |cx, meta, _action| {
+        match cx.editor.restore_session(&meta.session) {
+            Ok(()) => cx
+                .editor
+                .set_status(format!("Loaded session '{}'", meta.name)),
+            Err(err) => cx.editor.set_error(err.to_string()),
+        }
+    });
+    cx.push_layer(Box::new(overlaid(picker)));
+}
+
 fn changed_file_picker(cx: &mut Context) {
     pub struct FileChangeData {
         cwd: PathBuf,
@@ -4385,9 +4629,11 @@ pub fn command_palette(cx: &mut Context) {
 
     cx.callback.push(Box::new(
         move |compositor: &mut Compositor, cx: &mut compositor::Context| {
-            let keymap = compositor.find::<ui::EditorView>().unwrap().keymaps.map()
-                [&cx.editor.mode]
-                .reverse_map();
+            let keymap = compositor
+                .find::<ui::EditorView>()
+                .unwrap()
+                .keymaps
+                .reverse_map(cx.editor.mode);
 
             let commands = MappableCommand::STATIC_COMMAND_LIST.iter().cloned().chain(
                 typed::TYPABLE_COMMAND_LIST
@@ -5134,6 +5380,195 @@ fn hunk_range(hunk: Hunk, text: RopeSlice) -> Range {
     Range::new(anchor, head)
 }
 
+fn stage_hunk(cx: &mut Context) {
+    let (view, doc) = current_ref!(cx.editor);
+    let line = doc
+        .selection(view.id)
+        .primary()
+        .cursor_line(doc.text().slice(..));
+    stage_hunks_impl(cx.editor, &[(line, line)], false);
+}
+
+fn unstage_hunk(cx: &mut Context) {
+    let (view, doc) = current_ref!(cx.editor);
+    let line = doc
+        .selection(view.id)
+        .primary()
+        .cursor_line(doc.text().slice(..));
+    stage_hunks_impl(cx.editor, &[(line, line)], true);
+}
+
+fn stage_selection(cx: &mut Context) {
+    let (view, doc) = current_ref!(cx.editor);
+    let lines: Vec<_> = doc
+        .selection(view.id)
+        .line_ranges(doc.text().slice(..))
+        .collect();
+    stage_hunks_impl(cx.editor, &lines, false);
+}
+
+fn show_hunk_diff(cx: &mut Context) {
+    let (view, doc) = current!(cx.editor);
+    let line = doc
+        .selection(view.id)
+        .primary()
+        .cursor_line(doc.text().slice(..));
+    let hunk = doc
+        .diff_handle()
+        .and_then(|handle| handle.load().hunk_at(line as u32, true));
+    let Some(idx) = hunk else {
+        cx.editor.set_status("No change under the cursor");
+        return;
+    };
+    doc.hunk_diff = if doc.hunk_diff == Some((view.id, idx)) {
+        None
+    } else {
+        Some((view.id, idx))
+    };
+}
+
+fn goto_next_conflict(cx: &mut Context) {
+    goto_next_conflict_impl(cx, Direction::Forward)
+}
+
+fn goto_prev_conflict(cx: &mut Context) {
+    goto_next_conflict_impl(cx, Direction::Backward)
+}
+
+fn goto_next_conflict_impl(cx: &mut Context, direction: Direction) {
+    let count = cx.count() - 1;
+    let motion = move |editor: &mut Editor| {
+        let (view, doc) = current!(editor);
+        if doc.conflicts.is_empty() {
+            editor.set_status("No merge conflicts in current buffer");
+            return;
+        }
+        let text = doc.text().slice(..);
+        let conflicts = &doc.conflicts;
+
+        let selection = doc.selection(view.id).clone().transform(|range| {
+            let line = range.cursor_line(text);
+            let idx = match direction {
+                Direction::Forward => {
+                    let idx = conflicts.partition_point(|conflict| conflict.start <= line);
+                    (idx < conflicts.len()).then(|| (idx + count).min(conflicts.len() - 1))
+                }
+                Direction::Backward => conflicts
+                    .partition_point(|conflict| conflict.end < line)
+                    .checked_sub(1)
+                    .map(|idx| idx.saturating_sub(count)),
+            };
+            let Some(idx) = idx else {
+                return range;
+            };
+            let lines = conflicts[idx].lines();
+            let new_range =
+                Range::new(text.line_to_char(lines.start), text.line_to_char(lines.end));
+            if editor.mode == Mode::Select {
+                let head = if new_range.head < range.anchor {
+                    new_range.anchor
+                } else {
+                    new_range.head
+                };
+
+                Range::new(range.anchor, head)
+            } else {
+                new_range.with_direction(direction)
+            }
+        });
+
+        push_jump(view, doc);
+        doc.set_selection(view.id, selection)
+    };
+    cx.editor.apply_motion(motion);
+}
+
+fn accept_ours(cx: &mut Context) {
+    accept_conflict_impl(cx, Resolution::Ours)
+}
+
+fn accept_theirs(cx: &mut Context) {
+    accept_conflict_impl(cx, Resolution::Theirs)
+}
+
+fn accept_both(cx: &mut Context) {
+    accept_conflict_impl(cx, Resolution::Both)
+}
+
+fn accept_conflict_impl(cx: &mut Context, resolution: Resolution) {
+    let (view, doc) = current!(cx.editor);
+    let text = doc.text().slice(..);
+    let mut conflicts: Vec<_> = doc
+        .selection(view.id)
+        .line_ranges(text)
+        .flat_map(|(first, last)| {
+            doc.conflicts
+                .iter()
+                .filter(move |conflict| conflict.start <= last && first <= conflict.end)
+        })
+        .collect();
+    conflicts.dedup();
+    if conflicts.is_empty() {
+        cx.editor.set_status("No merge conflicts in the selections");
+        return;
+    }
+
+    let changes: Vec<_> = conflicts
+        .into_iter()
+        .map(|conflict| conflict.resolve(text, resolution))
+        .collect();
+    let transaction = Transaction::change(doc.text(), changes.into_iter());
+    doc.apply(&transaction, view.id);
+}
+
+fn stage_hunks_impl(editor: &mut Editor, lines: &[(usize, usize)], unstage: bool) {
+    let doc = doc!(editor);
+    let Some(path) = doc.path().map(ToOwned::to_owned) else {
+        editor.set_error("Cannot stage changes of a buffer without a path");
+        return;
+    };
+    let contents = doc
+        .encoding()
+        .encode(&doc.text().to_string())
+        .0
+        .into_owned();
+    let trust_full = editor
+        .workspace_trust
+        .query(
+            doc.workspace_root(),
+            helix_loader::workspace_trust::TrustQuery::Git,
+        )
+        .is_trusted();
+
+    let result = if unstage {
+        editor
+            .diff_providers
+            .unstage_hunks(&path, &contents, lines, trust_full)
+    } else {
+        editor
+            .diff_providers
+            .stage_hunks(&path, &contents, lines, trust_full)
+    };
+    if let Err(err) = result {
+        let action = if unstage { "unstage" } else { "stage" };
+        editor.set_error(format!("Failed to {action} changes: {err}"));
+        return;
+    }
+
+    // Staged changes are no longer shown when diffing against the index.
+    let doc = doc_mut!(editor);
+    doc.update_diff_base(&editor.diff_providers, trust_full);
+    if doc.blame.is_some() && doc.config.load().diff_base == helix_vcs::DiffBase::Index {
+        let doc_id = doc.id();
+        crate::handlers::blame::request_blame(editor, doc_id, |_, _| {});
+    }
+    editor.set_status(if unstage {
+        "Unstaged changes"
+    } else {
+        "Staged changes"
+    });
+}
+
 pub mod insert {
     use crate::{events::PostInsertChar, key};
 
@@ -5156,6 +5591,10 @@ fn exclude_cursor(text: RopeSlice, range: Range, cursor: Range) -> Range {
     use helix_view::editor::SmartTabConfig;
 
     pub fn insert_char(cx: &mut Context, c: char) {
+        if !linked_editing::is_linked_char(c) {
+            doc_mut!(cx.editor).stop_linked_editing();
+        }
+
         let (view, doc) = current_ref!(cx.editor);
         let text = doc.text();
         let selection = doc.selection(view.id);
@@ -5271,6 +5710,8 @@ fn insert_selection_interactive(cx: &mut Context, old_mode: Mode) {
     }
 
     pub fn insert_newline(cx: &mut Context) {
+        doc_mut!(cx.editor).stop_linked_editing();
+
         let config = cx.editor.config();
         let (view, doc) = current_ref!(cx.editor);
         let loader = cx.editor.syn_loader.load();
@@ -5647,7 +6088,7 @@ fn yank_impl(editor: &mut Editor, register: char) {
         .collect();
     let selections = values.len();
 
-    match editor.registers.write(register, values) {
+    match editor.registers.yank(register, values) {
         Ok(_) => editor.set_status(format!(
             "yanked {selections} selection{} to register {register}",
             if selections == 1 { "" } else { "s" }
@@ -5672,7 +6113,7 @@ fn yank_joined_impl(editor: &mut Editor, separator: &str, register: char) {
             acc
         });
 
-    match editor.registers.write(register, vec![joined]) {
+    match editor.registers.yank(register, vec![joined]) {
         Ok(_) => editor.set_status(format!(
             "joined and yanked {selections} selection{} to register {register}",
             if selections == 1 { "" } else { "s" }
@@ -5710,7 +6151,7 @@ pub(crate) fn yank_main_selection_to_register(editor: &mut Editor, register: cha
 
     let selection = doc.selection(view.id).primary().fragment(text).to_string();
 
-    match editor.registers.write(register, vec![selection]) {
+    match editor.registers.yank(register, vec![selection]) {
         Ok(_) => editor.set_status(format!("yanked primary selection to register {register}",)),
         Err(err) => editor.set_error(err.to_string()),
     }
@@ -6715,6 +7156,13 @@ fn reverse_selection_contents(cx: &mut Context) {
 
 fn expand_selection(cx: &mut Context) {
     let motion = |editor: &mut Editor| {
+        // save current selection so it can be restored using shrink_selection
+        let save_selection =
+            |view: &mut View, selection: Selection| view.object_selections.push(selection);
+        if select_lsp_selection_ranges(editor, true, save_selection) {
+            return;
+        }
+
         let (view, doc) = current!(editor);
 
         if let Some(syntax) = doc.syntax() {
@@ -6749,9 +7197,15 @@ fn shrink_selection(cx: &mut Context) {
                 view.object_selections.clear();
             }
         }
+        if select_lsp_selection_ranges(editor, false, |_, _| {}) {
+            return;
+        }
+
         // if not previous selection, shrink to first child
+        let (view, doc) = current!(editor);
         if let Some(syntax) = doc.syntax() {
             let text = doc.text().slice(..);
+            let current_selection = doc.selection(view.id);
             let selection = object::shrink_selection(syntax, text, current_selection.clone());
             doc.set_selection(view.id, selection);
         }
@@ -7257,6 +7711,101 @@ fn scroll_down(cx: &mut Context) {
     scroll(cx, cx.count(), Direction::Forward, false);
 }
 
+/// Replaces the closed folds of the current view, moving any cursor that would end up hidden
+/// to the start of the fold's first line.
+fn update_folds(doc: &mut Document, view: &View, mut folds: Vec<Fold>) {
+    fold::sort(&mut folds);
+    let visible = fold::outermost(&folds);
+    let text = doc.text().slice(..);
+    let selection = doc.selection(view.id).clone().transform(|range| {
+        match visible.iter().find(|fold| fold.hides(range.cursor(text))) {
+            Some(fold) => Range::point(text.line_to_char(fold.start_line(text))),
+            None => range,
+        }
+    });
+    doc.set_selection(view.id, selection);
+    doc.set_folds(view.id, folds);
+}
+
+fn fold(cx: &mut Context) {
+    let (view, doc) = current!(cx.editor);
+    let text = doc.text().slice(..);
+    let foldable = doc.foldable_ranges();
+    let mut closed = doc.folds(view.id).to_vec();
+    let closed_count = closed.len();
+    for range in doc.selection(view.id) {
+        let line = range.cursor_line(text);
+        if let Some(fold) =
+            fold::innermost_at_line(&foldable, text, line, |fold| closed.contains(fold))
+        {
+            closed.push(fold);
+        }
+    }
+
+    if closed.len() == closed_count {
+        cx.editor.set_status("No region to fold at the cursor");
+        return;
+    }
+    update_folds(doc, view, closed);
+}
+
+fn unfold(cx: &mut Context) {
+    let (view, doc) = current!(cx.editor);
+    let text = doc.text().slice(..);
+    let mut closed = doc.folds(view.id).to_vec();
+    let closed_count = closed.len();
+    for range in doc.selection(view.id) {
+        let line = range.cursor_line(text);
+        // the outermost closed fold is the one that is currently visible
+        if let Some(idx) = closed
+            .iter()
+            .position(|fold| fold.contains_line(text, line))
+        {
+            closed.remove(idx);
+        }
+    }
+
+    if closed.len() == closed_count {
+        cx.editor.set_status("No folded region at the cursor");
+        return;
+    }
+    update_folds(doc, view, closed);
+}
+
+fn toggle_fold(cx: &mut Context) {
+    let (view, doc) = current_ref!(cx.editor);
+    let text = doc.text().slice(..);
+    let line = doc.selection(view.id).primary().cursor_line(text);
+    let folded = doc
+        .folds(view.id)
+        .iter()
+        .any(|fold| fold.contains_line(text, line));
+    if folded {
+        unfold(cx);
+    } else {
+        fold(cx);
+    }
+}
+
+fn fold_all(cx: &mut Context) {
+    let min_level = cx.count();
+    let (view, doc) = current!(cx.editor);
+    let foldable = doc.foldable_ranges();
+    let levels = fold::levels(&foldable);
+    let closed = foldable
+        .into_iter()
+        .zip(levels)
+        .filter(|&(_, level)| level >= min_level)
+        .map(|(fold, _)| fold)
+        .collect();
+    update_folds(doc, view, closed);
+}
+
+fn unfold_all(cx: &mut Context) {
+    let (view, doc) = current!(cx.editor);
+    doc.set_folds(view.id, Vec::new());
+}
+
 fn goto_ts_object_impl(cx: &mut Context, object: &'static str, direction: Direction) {
     let count = cx.count();
     let motion = move |editor: &mut Editor| {
@@ -7442,6 +7991,11 @@ fn find_from_left(text: RopeSlice<'_>, ch: &str, range: Range) -> Range {
                         return;
                     }
 
+                    if ch == 'C' && doc.conflicts.is_empty() {
+                        editor.set_status("No merge conflicts in current buffer");
+                        return;
+                    }
+
                     let textobject_change = |range: Range| -> Range {
                         let diff_handle = doc.diff_handle().unwrap();
                         let diff = diff_handle.load();
@@ -7458,6 +8012,18 @@ fn find_from_left(text: RopeSlice<'_>, ch: &str, range: Range) -> Range {
                         Range::new(start, end).with_direction(Direction::Backward)
                     };
 
+                    let textobject_conflict = |range: Range| -> Range {
+                        let line = range.cursor_line(text);
+                        let Some(conflict) = conflict::conflict_at(&doc.conflicts, line) else {
+                            return range;
+                        };
+                        let lines = match objtype {
+                            textobject::TextObject::Inside => conflict.version_at(line),
+                            _ => conflict.lines(),
+                        };
+                        Range::new(text.line_to_char(lines.start), text.line_to_char(lines.end))
+                    };
+
                     let selection = doc.selection(view.id).clone().transform(|range| {
                         match ch {
                             'w' => {
@@ -7498,6 +8064,10 @@ fn find_from_left(text: RopeSlice<'_>, ch: &str, range: Range) -> Range {
                                 do_behavior = false;
                                 textobject_change(range)
                             }
+                            'C' => {
+                                do_behavior = false;
+                                textobject_conflict(range)
+                            }
                             // TODO: cancel new ranges if inconsistent surround matches across lines
                             ch if !ch.is_ascii_alphanumeric() => {
                                 textobject::textobject_pair_surround(
@@ -7580,6 +8150,7 @@ fn find_from_left(text: RopeSlice<'_>, ch: &str, range: Range) -> Range {
         ("s", "Paragraph"),
         ("d", "Indentation"),
         ("g", "Change"),
+        ("C", "Merge conflict"),
         ("m", "Pair 󰌪"),
         ("a", "Argument 󰌪"),
         ("e", "Entry 󰌪"),
@@ -8411,7 +8982,7 @@ fn record_macro(cx: &mut Context) {
                 }
             })
             .collect::<String>();
-        match cx.editor.registers.write(reg, vec![s]) {
+        match cx.editor.set_macro(reg, s) {
             Ok(_) => cx
                 .editor
                 .set_status(format!("Recorded to register [{}]", reg)),
@@ -8427,52 +8998,133 @@ fn record_macro(cx: &mut Context) {
 
 fn replay_macro(cx: &mut Context) {
     let reg = cx.register.unwrap_or('@');
+    let count = cx.count();
+    replay_macro_from(cx, reg, count);
+}
 
-    if cx.editor.macro_replaying.contains(&reg) {
-        cx.editor.set_error(format!(
-            "Cannot replay from register [{}] because already replaying from same register",
-            reg
-        ));
+/// The number of times `replay_macro_until` replays a macro without a count.
+const MACRO_REPLAY_LIMIT: usize = 1000;
+
+/// The maximum number of keys replayed by a macro, including the keys of the macros it replays.
+/// This stops recursive macros that never fail.
+const MAX_MACRO_KEYS: usize = 100_000;
+
+fn replay_macro_until(cx: &mut Context) {
+    let reg = cx.register.unwrap_or('@');
+    let Some(keys) = macro_keys(cx.editor, reg) else {
         return;
+    };
+    let limit = cx.count.map_or(MACRO_REPLAY_LIMIT, NonZeroUsize::get);
+
+    // The state compared after each replay to stop once the macro changes nothing.
+    fn state(editor: &Editor) -> (ViewId, DocumentId, i32, Selection) {
+        let (view, doc) = current_ref!(editor);
+        (
+            view.id,
+            doc.id(),
+            doc.version(),
+            doc.selection(view.id).clone(),
+        )
     }
 
-    let keys: Vec<KeyEvent> = if let Some(keys) = cx
-        .editor
+    cx.editor.macro_replaying.push(reg);
+    cx.callback.push(Box::new(move |compositor, cx| {
+        let mut replays = 0;
+        while replays < limit {
+            let before = state(cx.editor);
+            let completed = replay_queued(compositor, cx, &keys);
+            replays += 1;
+            if !completed || state(cx.editor) == before {
+                break;
+            }
+        }
+        cx.editor.macro_replaying.pop();
+        if !cx.editor.is_err() {
+            cx.editor
+                .set_status(format!("Replayed macro [{reg}] {replays} times"));
+        }
+    }));
+}
+
+/// Reads the macro in `register`, showing an error if it's empty or invalid.
+fn macro_keys(editor: &mut Editor, register: char) -> Option<Vec<KeyEvent>> {
+    let Some(keys) = editor
         .registers
-        .read(reg, cx.editor)
+        .read(register, editor)
         .filter(|values| values.len() == 1)
         .map(|mut values| values.next().unwrap())
-    {
-        match helix_view::input::parse_macro(&keys) {
-            Ok(keys) => keys,
-            Err(err) => {
-                cx.editor.set_error(format!("Invalid macro: {}", err));
-                return;
-            }
-        }
-    } else {
-        cx.editor.set_error(format!("Register [{}] empty", reg));
-        return;
+    else {
+        editor.set_error(format!("Register [{}] empty", register));
+        return None;
     };
+    match helix_view::input::parse_macro(&keys) {
+        Ok(keys) => Some(keys),
+        Err(err) => {
+            editor.set_error(format!("Invalid macro: {}", err));
+            None
+        }
+    }
+}
 
-    // Once the macro has been fully validated, it's marked as being under replay
-    // to ensure we don't fall into infinite recursion.
-    cx.editor.macro_replaying.push(reg);
+fn replay_macro_from(cx: &mut Context, register: char, count: usize) {
+    if let Some(keys) = macro_keys(cx.editor, register) {
+        replay_keys(cx, register, keys, count);
+    }
+}
 
-    let count = cx.count();
-    cx.callback.push(Box::new(move |compositor, cx| {
+/// Replays `keys` `count` times. A macro replayed by a macro under replay is queued in front of
+/// the remaining keys of that macro instead of being replayed recursively, so recursive macros
+/// don't grow the stack.
+fn replay_keys(cx: &mut Context, register: char, keys: Vec<KeyEvent>, count: usize) {
+    if !cx.editor.macro_replaying.is_empty() {
         for _ in 0..count {
-            for &key in keys.iter() {
-                compositor.handle_event(&compositor::Event::Key(key), cx);
+            for &key in keys.iter().rev() {
+                cx.editor.macro_queue.push_front(key);
             }
         }
+        return;
+    }
+
+    cx.editor.macro_replaying.push(register);
+    cx.callback.push(Box::new(move |compositor, cx| {
+        let keys: Vec<_> = (0..count).flat_map(|_| keys.iter().copied()).collect();
+        replay_queued(compositor, cx, &keys);
         // The macro under replay is cleared at the end of the callback, not in the
-        // macro replay context, or it will not correctly protect the user from
-        // replaying recursively.
+        // macro replay context, so that the keys of macros replayed by it are queued.
         cx.editor.macro_replaying.pop();
     }));
 }
 
+/// Replays `keys` and the keys queued by the macros they replay, stopping at the first key that
+/// fails with an error. Returns whether all keys were replayed.
+fn replay_queued(
+    compositor: &mut Compositor,
+    cx: &mut compositor::Context,
+    keys: &[KeyEvent],
+) -> bool {
+    let outer = std::mem::replace(&mut cx.editor.macro_queue, keys.iter().copied().collect());
+    cx.editor.clear_status();
+    let mut replayed = 0;
+    let mut completed = true;
+    while let Some(key) = cx.editor.macro_queue.pop_front() {
+        if replayed == MAX_MACRO_KEYS {
+            cx.editor.set_error(format!(
+                "Stopped replaying macro after {MAX_MACRO_KEYS} keys"
+            ));
+            completed = false;
+            break;
+        }
+        replayed += 1;
+        compositor.handle_event(&compositor::Event::Key(key), cx);
+        if cx.editor.is_err() {
+            completed = false;
+            break;
+        }
+    }
+    cx.editor.macro_queue = outer;
+    completed
+}
+
 fn goto_word(cx: &mut Context) {
     jump_to_word(cx, Movement::Move)
 }
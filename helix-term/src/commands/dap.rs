@@ -5,10 +5,13 @@
     ui::{self, overlay::overlaid, Picker, Popup, Prompt, PromptEvent, Text},
 };
 use dap::{StackFrame, Thread, ThreadStates};
-use helix_core::syntax::config::{DebugConfigCompletion, DebugTemplate};
+use helix_core::syntax::config::{DebugAdapterConfig, DebugConfigCompletion, DebugTemplate};
+use helix_core::Selection;
 use helix_dap::{self as dap, requests::TerminateArguments};
 use helix_lsp::block_on;
-use helix_view::editor::Breakpoint;
+use helix_view::editor::{Breakpoint, DataBreakpoint, FunctionBreakpoint, InstructionBreakpoint};
+use helix_view::launch_config::{self, LaunchConfig};
+use helix_view::{align_view, Align};
 
 use serde_json::{to_value, Value};
 use tui::text::Spans;
@@ -19,7 +22,10 @@
 
 use anyhow::{anyhow, bail};
 
-use helix_view::handlers::dap::{breakpoints_changed, jump_to_stack_frame, select_thread_id};
+use helix_view::handlers::dap::{
+    breakpoints_changed, data_breakpoints_changed, function_breakpoints_changed,
+    instruction_breakpoints_changed, jump_to_stack_frame, select_thread_id,
+};
 
 fn thread_picker(
     cx: &mut Context,
@@ -113,11 +119,88 @@ fn dap_callback<T, F>(
     jobs.callback(callback);
 }
 
+/// Finds the debug template `name` of the language of the current document or, failing that, the
+/// workspace launch configuration `name`. Without a name the first template is used.
+fn find_template(
+    editor: &Editor,
+    name: Option<&str>,
+) -> anyhow::Result<(DebugAdapterConfig, DebugTemplate)> {
+    let language_debugger = doc!(editor)
+        .language_config()
+        .and_then(|config| config.debugger.as_ref());
+    if let Some(debugger) = language_debugger {
+        let template = match name {
+            Some(name) => debugger.templates.iter().find(|t| t.name == name),
+            None => debugger.templates.first(),
+        };
+        if let Some(template) = template {
+            return Ok((debugger.clone(), template.clone()));
+        }
+    }
+
+    let configs = launch_config::load(&editor.workspace_trust)?;
+    match configs
+        .iter()
+        .find(|config| name.is_none_or(|name| config.name == name))
+    {
+        Some(config) => Ok((
+            launch_config_adapter(editor, config)?,
+            config.template(editor)?,
+        )),
+        None if language_debugger.is_some() || !configs.is_empty() => {
+            bail!("No debug config with given name")
+        }
+        None => bail!("No debug adapter available for language"),
+    }
+}
+
+/// The debug adapter of a workspace launch configuration: the `debugger` section of
+/// `languages.toml` with the name given by its `type`, the debugger of the language with that
+/// name or the debugger of the language of the current document.
+fn launch_config_adapter(
+    editor: &Editor,
+    config: &LaunchConfig,
+) -> anyhow::Result<DebugAdapterConfig> {
+    let loader = editor.syn_loader.load();
+    let by_type = config.adapter.as_deref().and_then(|adapter| {
+        let debuggers = || {
+            loader
+                .language_configs()
+                .filter_map(|language| Some((language, language.debugger.as_ref()?)))
+        };
+        debuggers()
+            .find(|(_, debugger)| debugger.name == adapter)
+            .or_else(|| debuggers().find(|(language, _)| language.language_id == adapter))
+            .map(|(_, debugger)| debugger.clone())
+    });
+    by_type
+        .or_else(|| {
+            doc!(editor)
+                .language_config()
+                .and_then(|config| config.debugger.clone())
+        })
+        .ok_or_else(|| match &config.adapter {
+            Some(adapter) => anyhow!("No debug adapter '{adapter}' configured in languages.toml"),
+            None => anyhow!("No debug adapter available for language"),
+        })
+}
+
 pub fn dap_start_impl(
     cx: &mut compositor::Context,
     name: Option<&str>,
     socket: Option<std::net::SocketAddr>,
     params: Option<Vec<std::borrow::Cow<str>>>,
+) -> Result<(), anyhow::Error> {
+    let (config, template) = find_template(cx.editor, name)?;
+    dap_start_template(cx, &config, &template, socket, params)
+}
+
+fn dap_start_template(
+    cx: &mut compositor::Context,
+    config: &DebugAdapterConfig,
+    template: &DebugTemplate,
+    socket: Option<std::net::SocketAddr>,
+    params: Option<Vec<std::borrow::Cow<str>>>,
 ) -> Result<(), anyhow::Error> {
     // Refuse to spawn a debug adapter in workspace trust restricted mode.
     let workspace = doc!(cx.editor).workspace_root().to_path_buf();
@@ -130,25 +213,12 @@ pub fn dap_start_impl(
         bail!("Workspace is not trusted. Run `:workspace-trust` to enable the debug adapter.");
     }
 
-    let doc = doc!(cx.editor);
-    let config = doc
-        .language_config()
-        .and_then(|config| config.debugger.as_ref())
-        .ok_or_else(|| anyhow!("No debug adapter available for language"))?;
-
     let id = cx
         .editor
         .debug_adapters
         .start_client(socket, config)
         .map_err(|e| anyhow!("Failed to start debug client: {}", e))?;
 
-    // TODO: avoid refetching all of this... pass a config in
-    let template = match name {
-        Some(name) => config.templates.iter().find(|t| t.name == name),
-        None => config.templates.first(),
-    }
-    .ok_or_else(|| anyhow!("No debug config with given name"))?;
-
     let mut args: HashMap<&str, Value> = if let Some(params) = params.as_ref() {
         let preprocessed_params = prepare_dap_params(template, params);
         template
@@ -164,7 +234,9 @@ pub fn dap_start_impl(
             .collect()
     };
 
-    args.insert("cwd", to_value(helix_stdx::env::current_working_dir())?);
+    if !args.contains_key("cwd") {
+        args.insert("cwd", to_value(helix_stdx::env::current_working_dir())?);
+    }
 
     let args = to_value(args).unwrap();
 
@@ -249,38 +321,43 @@ pub fn dap_launch(cx: &mut Context) {
         return;
     }
 
-    let doc = doc!(cx.editor);
-
-    let config = match doc
+    let mut items: Vec<_> = doc!(cx.editor)
         .language_config()
         .and_then(|config| config.debugger.as_ref())
-    {
-        Some(c) => c,
-        None => {
-            cx.editor
-                .set_error("No debug adapter available for language");
-            return;
-        }
-    };
-
-    let templates = config.templates.clone();
+        .map(|debugger| {
+            debugger
+                .templates
+                .iter()
+                .cloned()
+                .map(LaunchItem::Template)
+                .collect()
+        })
+        .unwrap_or_default();
+    match launch_config::load(&cx.editor.workspace_trust) {
+        Ok(configs) => items.extend(configs.into_iter().map(LaunchItem::Config)),
+        Err(err) => cx.editor.set_error(err.to_string()),
+    }
+    if items.is_empty() {
+        cx.editor
+            .set_error("No debug adapter available for language");
+        return;
+    }
 
-    let columns = [ui::PickerColumn::new(
-        "template",
-        |item: &DebugTemplate, _| item.name.as_str().into(),
-    )];
+    let columns = [
+        ui::PickerColumn::new("template", |item: &LaunchItem, _| item.name().into()),
+        ui::PickerColumn::new("source", |item: &LaunchItem, _| match item {
+            LaunchItem::Template(_) => "languages.toml".into(),
+            LaunchItem::Config(config) => config.source.as_str().into(),
+        }),
+    ];
 
     cx.push_layer(Box::new(overlaid(Picker::new(
         columns,
         0,
-        templates,
+        items,
         (),
-        |cx, template, _action| {
-            if template.completion.is_empty() {
-                if let Err(err) = dap_start_impl(cx, Some(&template.name), None, None) {
-                    cx.editor.set_error(err.to_string());
-                }
-            } else {
+        |cx, item, _action| match item {
+            LaunchItem::Template(template) if !template.completion.is_empty() => {
                 let completions = template.completion.clone();
                 let name = template.name.clone();
                 let callback = Box::pin(async move {
@@ -293,10 +370,40 @@ pub fn dap_launch(cx: &mut Context) {
                 });
                 cx.jobs.callback(callback);
             }
+            LaunchItem::Template(template) => {
+                if let Err(err) = dap_start_impl(cx, Some(&template.name), None, None) {
+                    cx.editor.set_error(err.to_string());
+                }
+            }
+            LaunchItem::Config(config) => {
+                let result = launch_config_adapter(cx.editor, config).and_then(|adapter| {
+                    let template = config.template(cx.editor)?;
+                    dap_start_template(cx, &adapter, &template, None, None)
+                });
+                if let Err(err) = result {
+                    cx.editor.set_error(err.to_string());
+                }
+            }
         },
     ))));
 }
 
+/// A debug template of the language of the current document or a launch configuration of the
+/// workspace.
+enum LaunchItem {
+    Template(DebugTemplate),
+    Config(LaunchConfig),
+}
+
+impl LaunchItem {
+    fn name(&self) -> &str {
+        match self {
+            Self::Template(template) => &template.name,
+            Self::Config(config) => &config.name,
+        }
+    }
+}
+
 pub fn dap_restart(cx: &mut Context) {
     let debugger = match cx.editor.debug_adapters.get_active_client() {
         Some(debugger) => debugger,
@@ -443,6 +550,7 @@ pub fn dap_continue(cx: &mut Context) {
 
     if let Some(thread_id) = debugger.thread_id {
         let request = debugger.continue_thread(thread_id);
+        cx.editor.clear_inline_values();
 
         dap_callback(
             cx.jobs,
@@ -473,6 +581,7 @@ pub fn dap_step_in(cx: &mut Context) {
 
     if let Some(thread_id) = debugger.thread_id {
         let request = debugger.step_in(thread_id);
+        cx.editor.clear_inline_values();
 
         dap_callback(cx.jobs, request, |editor, _compositor, _response: ()| {
             debugger!(editor).resume_application();
@@ -488,6 +597,7 @@ pub fn dap_step_out(cx: &mut Context) {
 
     if let Some(thread_id) = debugger.thread_id {
         let request = debugger.step_out(thread_id);
+        cx.editor.clear_inline_values();
         dap_callback(cx.jobs, request, |editor, _compositor, _response: ()| {
             debugger!(editor).resume_application();
         });
@@ -502,6 +612,7 @@ pub fn dap_next(cx: &mut Context) {
 
     if let Some(thread_id) = debugger.thread_id {
         let request = debugger.next(thread_id);
+        cx.editor.clear_inline_values();
         dap_callback(cx.jobs, request, |editor, _compositor, _response: ()| {
             debugger!(editor).resume_application();
         });
@@ -595,6 +706,33 @@ pub fn dap_variables(cx: &mut Context) {
     cx.replace_or_push_layer("dap-variables", popup);
 }
 
+pub fn dap_toggle_panel(cx: &mut Context) {
+    cx.callback.push(Box::new(|compositor, cx| {
+        match compositor.find_id::<ui::DebugPanel>(ui::debug_panel::ID) {
+            Some(panel) if !panel.focused => panel.focused = true,
+            Some(_) => ui::debug_panel::close(compositor, cx.editor),
+            None => {
+                cx.editor.debug_panel.visible = true;
+                block_on(cx.editor.update_debug_panel());
+                compositor.push(Box::new(ui::DebugPanel::new()));
+            }
+        }
+    }));
+}
+
+pub fn dap_toggle_console(cx: &mut Context) {
+    cx.callback.push(Box::new(|compositor, cx| {
+        match compositor.find_id::<ui::DebugConsole>(ui::debug_console::ID) {
+            Some(console) if !console.focused => console.focused = true,
+            Some(_) => ui::debug_console::close(compositor, cx.editor),
+            None => {
+                cx.editor.debug_console.visible = true;
+                compositor.push(Box::new(ui::DebugConsole::new()));
+            }
+        }
+    }));
+}
+
 pub fn dap_terminate(cx: &mut Context) {
     cx.editor.set_status("Terminating debug session...");
     let debugger = debugger!(cx.editor);
@@ -651,16 +789,20 @@ pub fn dap_disable_exceptions(cx: &mut Context) {
     )
 }
 
-// TODO: both edit condition and edit log need to be stable: we might get new breakpoints from the debugger which can change offsets
-pub fn dap_edit_condition(cx: &mut Context) {
-    if let Some((pos, breakpoint)) = get_breakpoint_at_current_line(cx.editor) {
+// TODO: editing breakpoints needs to be stable: we might get new breakpoints from the debugger which can change offsets
+fn dap_edit_breakpoint_impl(
+    cx: &mut Context,
+    prompt: &'static str,
+    field: fn(&mut Breakpoint) -> &mut Option<String>,
+) {
+    if let Some((pos, mut breakpoint)) = get_breakpoint_at_current_line(cx.editor) {
         let Some(path) = doc!(cx.editor).path().map(ToOwned::to_owned) else {
             return;
         };
         let callback = Box::pin(async move {
             let call: Callback = Callback::EditorCompositor(Box::new(move |editor, compositor| {
                 let mut prompt = Prompt::new(
-                    "condition:".into(),
+                    prompt.into(),
                     None,
                     ui::completers::none,
                     move |cx, input: &str, event: PromptEvent| {
@@ -669,21 +811,20 @@ pub fn dap_edit_condition(cx: &mut Context) {
                         }
 
                         let breakpoints = &mut cx.editor.breakpoints.get_mut(&path).unwrap();
-                        breakpoints[pos].condition = match input {
+                        *field(&mut breakpoints[pos]) = match input {
                             "" => None,
                             input => Some(input.to_owned()),
                         };
 
                         let debugger = debugger!(cx.editor);
-
                         if let Err(e) = breakpoints_changed(debugger, path.clone(), breakpoints) {
                             cx.editor
                                 .set_error(format!("Failed to set breakpoints: {}", e));
                         }
                     },
                 );
-                if let Some(condition) = breakpoint.condition {
-                    prompt.insert_str(&condition, editor)
+                if let Some(value) = field(&mut breakpoint) {
+                    prompt.insert_str(value, editor)
                 }
                 compositor.push(Box::new(prompt));
             }));
@@ -693,49 +834,427 @@ pub fn dap_edit_condition(cx: &mut Context) {
     }
 }
 
+pub fn dap_edit_condition(cx: &mut Context) {
+    dap_edit_breakpoint_impl(cx, "condition:", |breakpoint| &mut breakpoint.condition);
+}
+
+pub fn dap_edit_hit_condition(cx: &mut Context) {
+    dap_edit_breakpoint_impl(cx, "hit-condition:", |breakpoint| {
+        &mut breakpoint.hit_condition
+    });
+}
+
 pub fn dap_edit_log(cx: &mut Context) {
-    if let Some((pos, breakpoint)) = get_breakpoint_at_current_line(cx.editor) {
-        let Some(path) = doc!(cx.editor).path().map(ToOwned::to_owned) else {
-            return;
-        };
-        let callback = Box::pin(async move {
-            let call: Callback = Callback::EditorCompositor(Box::new(move |editor, compositor| {
-                let mut prompt = Prompt::new(
-                    "log-message:".into(),
-                    None,
-                    ui::completers::none,
-                    move |cx, input: &str, event: PromptEvent| {
-                        if event != PromptEvent::Validate {
-                            return;
-                        }
+    dap_edit_breakpoint_impl(cx, "log-message:", |breakpoint| &mut breakpoint.log_message);
+}
 
-                        let breakpoints = &mut cx.editor.breakpoints.get_mut(&path).unwrap();
-                        breakpoints[pos].log_message = match input {
-                            "" => None,
-                            input => Some(input.to_owned()),
-                        };
+pub fn dap_toggle_breakpoint_enabled(cx: &mut Context) {
+    let Some((pos, _)) = get_breakpoint_at_current_line(cx.editor) else {
+        cx.editor.set_error("No breakpoint on the current line");
+        return;
+    };
+    let Some(path) = doc!(cx.editor).path().map(ToOwned::to_owned) else {
+        return;
+    };
+    let breakpoints = cx.editor.breakpoints.get_mut(&path).unwrap();
+    breakpoints[pos].enabled = !breakpoints[pos].enabled;
 
-                        let debugger = debugger!(cx.editor);
-                        if let Err(e) = breakpoints_changed(debugger, path.clone(), breakpoints) {
-                            cx.editor
-                                .set_error(format!("Failed to set breakpoints: {}", e));
-                        }
-                    },
-                );
-                if let Some(log_message) = breakpoint.log_message {
-                    prompt.insert_str(&log_message, editor);
-                }
-                compositor.push(Box::new(prompt));
-            }));
-            Ok(call)
+    let debugger = debugger!(cx.editor);
+    if let Err(e) = breakpoints_changed(debugger, path, breakpoints) {
+        cx.editor
+            .set_error(format!("Failed to set breakpoints: {}", e));
+    }
+}
+
+/// Adds a function breakpoint on the function `name` or removes it if there already is one.
+pub fn dap_toggle_function_breakpoint_impl(editor: &mut Editor, name: &str) -> anyhow::Result<()> {
+    let breakpoints = &mut editor.function_breakpoints;
+    if let Some(pos) = breakpoints
+        .iter()
+        .position(|breakpoint| breakpoint.name == name)
+    {
+        breakpoints.remove(pos);
+    } else {
+        breakpoints.push(FunctionBreakpoint {
+            verified: false,
+            message: None,
+            name: name.to_owned(),
+            condition: None,
+            hit_condition: None,
+            enabled: true,
         });
-        cx.jobs.callback(callback);
     }
+
+    match editor.debug_adapters.get_active_client_mut() {
+        Some(debugger) => function_breakpoints_changed(debugger, breakpoints),
+        None => Ok(()),
+    }
+}
+
+/// Adds a data breakpoint on the variable `name` of the variables container
+/// `variables_reference`, or on the expression `name` in the active stack frame if there is no
+/// container. Removes the breakpoint instead if there already is one on the same data.
+pub fn dap_toggle_data_breakpoint_impl(
+    editor: &mut Editor,
+    variables_reference: Option<usize>,
+    name: &str,
+    access_type: Option<&str>,
+) -> anyhow::Result<()> {
+    let debugger = editor
+        .debug_adapters
+        .get_active_client_mut()
+        .ok_or_else(|| anyhow!("Debugger is not running"))?;
+    if !debugger
+        .capabilities()
+        .supports_data_breakpoints
+        .unwrap_or_default()
+    {
+        bail!("Debugger does not support data breakpoints");
+    }
+    let frame_id = match variables_reference {
+        Some(_) => None,
+        None => debugger.current_stack_frame().map(|frame| frame.id),
+    };
+    let info =
+        block_on(debugger.data_breakpoint_info(variables_reference, name.to_owned(), frame_id))?;
+    let Some(data_id) = info.data_id else {
+        bail!(
+            "Can't set a data breakpoint on '{name}': {}",
+            info.description
+        );
+    };
+    if let (Some(access_type), Some(access_types)) = (access_type, &info.access_types) {
+        if !access_types.iter().any(|ty| ty == access_type) {
+            bail!(
+                "Unsupported access type '{access_type}', expected one of: {}",
+                access_types.join(", ")
+            );
+        }
+    }
+
+    let breakpoints = &mut editor.data_breakpoints;
+    if let Some(pos) = breakpoints
+        .iter()
+        .position(|breakpoint| breakpoint.data_id == data_id)
+    {
+        breakpoints.remove(pos);
+    } else {
+        breakpoints.push(DataBreakpoint {
+            verified: false,
+            message: None,
+            data_id,
+            description: info.description,
+            access_type: access_type.map(ToOwned::to_owned),
+            condition: None,
+            hit_condition: None,
+            enabled: true,
+            can_persist: info.can_persist.unwrap_or_default(),
+        });
+    }
+    data_breakpoints_changed(debugger, breakpoints)
+}
+
+/// Adds an instruction breakpoint on the memory reference `reference` or removes it if there
+/// already is one.
+pub fn dap_toggle_instruction_breakpoint_impl(
+    editor: &mut Editor,
+    reference: &str,
+    offset: Option<isize>,
+) -> anyhow::Result<()> {
+    let debugger = editor
+        .debug_adapters
+        .get_active_client_mut()
+        .ok_or_else(|| anyhow!("Debugger is not running"))?;
+    let breakpoints = &mut editor.instruction_breakpoints;
+    if let Some(pos) = breakpoints.iter().position(|breakpoint| {
+        breakpoint.instruction_reference == reference && breakpoint.offset == offset
+    }) {
+        breakpoints.remove(pos);
+    } else {
+        breakpoints.push(InstructionBreakpoint {
+            verified: false,
+            message: None,
+            instruction_reference: reference.to_owned(),
+            offset,
+            condition: None,
+            hit_condition: None,
+            enabled: true,
+        });
+    }
+    instruction_breakpoints_changed(debugger, breakpoints)
+}
+
+/// Identifies a breakpoint of any kind by its index in the editor's list of breakpoints.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BreakpointId {
+    Source(PathBuf, usize),
+    Function(usize),
+    Data(usize),
+    Instruction(usize),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BreakpointChange {
+    ToggleEnabled,
+    Remove,
+}
+
+impl BreakpointId {
+    /// Applies `change` to the breakpoint and sends the changed breakpoints to the debugger.
+    pub fn change(&self, editor: &mut Editor, change: BreakpointChange) -> anyhow::Result<()> {
+        fn apply<T>(
+            breakpoints: &mut Vec<T>,
+            idx: usize,
+            change: BreakpointChange,
+            enabled: impl Fn(&mut T) -> &mut bool,
+        ) {
+            if idx >= breakpoints.len() {
+                return;
+            }
+            match change {
+                BreakpointChange::ToggleEnabled => {
+                    let enabled = enabled(&mut breakpoints[idx]);
+                    *enabled = !*enabled;
+                }
+                BreakpointChange::Remove => {
+                    breakpoints.remove(idx);
+                }
+            }
+        }
+
+        let debugger = editor.debug_adapters.get_active_client_mut();
+        match self {
+            Self::Source(path, idx) => {
+                let Some(breakpoints) = editor.breakpoints.get_mut(path) else {
+                    return Ok(());
+                };
+                apply(breakpoints, *idx, change, |b| &mut b.enabled);
+                match debugger {
+                    Some(debugger) => breakpoints_changed(debugger, path.clone(), breakpoints),
+                    None => Ok(()),
+                }
+            }
+            Self::Function(idx) => {
+                let breakpoints = &mut editor.function_breakpoints;
+                apply(breakpoints, *idx, change, |b| &mut b.enabled);
+                match debugger {
+                    Some(debugger) => function_breakpoints_changed(debugger, breakpoints),
+                    None => Ok(()),
+                }
+            }
+            Self::Data(idx) => {
+                let breakpoints = &mut editor.data_breakpoints;
+                apply(breakpoints, *idx, change, |b| &mut b.enabled);
+                match debugger {
+                    Some(debugger) => data_breakpoints_changed(debugger, breakpoints),
+                    None => Ok(()),
+                }
+            }
+            Self::Instruction(idx) => {
+                let breakpoints = &mut editor.instruction_breakpoints;
+                apply(breakpoints, *idx, change, |b| &mut b.enabled);
+                match debugger {
+                    Some(debugger) => instruction_breakpoints_changed(debugger, breakpoints),
+                    None => Ok(()),
+                }
+            }
+        }
+    }
+}
+
+/// Whether a breakpoint is enabled and, if so, whether the debugger verified it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BreakpointState {
+    Disabled,
+    Enabled,
+    Verified,
+}
+
+impl BreakpointState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Disabled => "disabled",
+            Self::Enabled => "enabled",
+            Self::Verified => "verified",
+        }
+    }
+}
+
+/// A copy of a breakpoint of any kind, used to list the breakpoints.
+#[derive(Clone)]
+pub enum BreakpointItem {
+    Source(PathBuf, Breakpoint),
+    Function(FunctionBreakpoint),
+    Data(DataBreakpoint),
+    Instruction(InstructionBreakpoint),
+}
+
+impl BreakpointItem {
+    pub fn new(editor: &Editor, id: &BreakpointId) -> Option<Self> {
+        let item = match id {
+            BreakpointId::Source(path, idx) => Self::Source(
+                path.clone(),
+                editor.breakpoints.get(path)?.get(*idx)?.clone(),
+            ),
+            BreakpointId::Function(idx) => {
+                Self::Function(editor.function_breakpoints.get(*idx)?.clone())
+            }
+            BreakpointId::Data(idx) => Self::Data(editor.data_breakpoints.get(*idx)?.clone()),
+            BreakpointId::Instruction(idx) => {
+                Self::Instruction(editor.instruction_breakpoints.get(*idx)?.clone())
+            }
+        };
+        Some(item)
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Source(..) => "source",
+            Self::Function(_) => "function",
+            Self::Data(_) => "data",
+            Self::Instruction(_) => "instruction",
+        }
+    }
+
+    pub fn location(&self) -> String {
+        match self {
+            Self::Source(path, breakpoint) => format!(
+                "{}:{}",
+                helix_stdx::path::get_relative_path(path).display(),
+                breakpoint.line + 1
+            ),
+            Self::Function(breakpoint) => breakpoint.name.clone(),
+            Self::Data(breakpoint) => match &breakpoint.access_type {
+                Some(access_type) => format!("{} ({access_type})", breakpoint.description),
+                None => breakpoint.description.clone(),
+            },
+            Self::Instruction(breakpoint) => match breakpoint.offset {
+                Some(offset) => format!("{}{offset:+}", breakpoint.instruction_reference),
+                None => breakpoint.instruction_reference.clone(),
+            },
+        }
+    }
+
+    pub fn conditions(&self) -> String {
+        let (condition, hit_condition, log_message) = match self {
+            Self::Source(_, b) => (&b.condition, &b.hit_condition, &b.log_message),
+            Self::Function(b) => (&b.condition, &b.hit_condition, &None),
+            Self::Data(b) => (&b.condition, &b.hit_condition, &None),
+            Self::Instruction(b) => (&b.condition, &b.hit_condition, &None),
+        };
+        let mut conditions = Vec::new();
+        if let Some(condition) = condition {
+            conditions.push(format!("if {condition}"));
+        }
+        if let Some(hit_condition) = hit_condition {
+            conditions.push(format!("hits {hit_condition}"));
+        }
+        if let Some(log_message) = log_message {
+            conditions.push(format!("log {log_message}"));
+        }
+        conditions.join(", ")
+    }
+
+    pub fn state(&self) -> BreakpointState {
+        let (enabled, verified) = match self {
+            Self::Source(_, b) => (b.enabled, b.verified),
+            Self::Function(b) => (b.enabled, b.verified),
+            Self::Data(b) => (b.enabled, b.verified),
+            Self::Instruction(b) => (b.enabled, b.verified),
+        };
+        match (enabled, verified) {
+            (false, _) => BreakpointState::Disabled,
+            (true, true) => BreakpointState::Verified,
+            (true, false) => BreakpointState::Enabled,
+        }
+    }
+}
+
+pub fn dap_breakpoint_picker(cx: &mut Context) {
+    let mut items: Vec<_> = cx
+        .editor
+        .breakpoints
+        .iter()
+        .flat_map(|(path, breakpoints)| {
+            breakpoints
+                .iter()
+                .map(|breakpoint| BreakpointItem::Source(path.clone(), breakpoint.clone()))
+        })
+        .collect();
+    items.sort_by(|a, b| match (a, b) {
+        (BreakpointItem::Source(a_path, a), BreakpointItem::Source(b_path, b)) => {
+            (a_path, a.line).cmp(&(b_path, b.line))
+        }
+        _ => std::cmp::Ordering::Equal,
+    });
+    items.extend(
+        cx.editor
+            .function_breakpoints
+            .iter()
+            .cloned()
+            .map(BreakpointItem::Function),
+    );
+    items.extend(
+        cx.editor
+            .data_breakpoints
+            .iter()
+            .cloned()
+            .map(BreakpointItem::Data),
+    );
+    items.extend(
+        cx.editor
+            .instruction_breakpoints
+            .iter()
+            .cloned()
+            .map(BreakpointItem::Instruction),
+    );
+    if items.is_empty() {
+        cx.editor.set_status("No breakpoints");
+        return;
+    }
+
+    let columns = [
+        ui::PickerColumn::new("kind", |item: &BreakpointItem, _| item.kind().into()),
+        ui::PickerColumn::new("location", |item: &BreakpointItem, _| {
+            item.location().into()
+        }),
+        ui::PickerColumn::new("conditions", |item: &BreakpointItem, _| {
+            item.conditions().into()
+        }),
+        ui::PickerColumn::new("state", |item: &BreakpointItem, _| {
+            item.state().as_str().into()
+        }),
+    ];
+    let picker = Picker::new(columns, 1, items, (), |cx, item, action| {
+        let BreakpointItem::Source(path, breakpoint) = item else {
+            return;
+        };
+        if let Err(err) = cx.editor.open(path, action) {
+            cx.editor
+                .set_error(format!("Failed to open {}: {err}", path.display()));
+            return;
+        }
+        let (view, doc) = current!(cx.editor);
+        let text = doc.text();
+        let pos = text.line_to_char(breakpoint.line.min(text.len_lines() - 1));
+        doc.set_selection(view.id, Selection::point(pos));
+        align_view(doc, view, Align::Center);
+    })
+    .with_preview(|_editor, item| match item {
+        BreakpointItem::Source(path, breakpoint) => Some((
+            path.as_path().into(),
+            Some((breakpoint.line, breakpoint.line)),
+        )),
+        _ => None,
+    });
+    cx.push_layer(Box::new(overlaid(picker)));
 }
 
 pub fn dap_switch_thread(cx: &mut Context) {
     thread_picker(cx, |editor, thread| {
         block_on(select_thread_id(editor, thread.id, true));
+        editor.update_inline_values();
+        block_on(editor.update_debug_panel());
     })
 }
 pub fn dap_switch_stack_frame(cx: &mut Context) {
@@ -775,6 +1294,8 @@ pub fn dap_switch_stack_frame(cx: &mut Context) {
             let debugger = debugger!(cx.editor);
             debugger.active_frame = Some(*index);
             jump_to_stack_frame(cx.editor, frame);
+            cx.editor.update_inline_values();
+            block_on(cx.editor.update_debug_panel());
         },
     )
     .with_preview(move |_editor, (_index, frame)| {
@@ -5,7 +5,7 @@
         self, CodeAction, CodeActionKind, CodeActionOrCommand, CodeActionTriggerKind,
         DiagnosticSeverity, NumberOrString,
     },
-    util::{diagnostic_to_lsp_diagnostic, lsp_range_to_range, range_to_lsp_range},
+    util::{diagnostic_to_lsp_diagnostic, lsp_range_to_range, pos_to_lsp_pos, range_to_lsp_range},
     Client, LanguageServerId, OffsetEncoding,
 };
 use tokio_stream::StreamExt;
@@ -14,15 +14,21 @@
 use super::{align_view, push_jump, Align, Context, Editor};
 
 use helix_core::{
-    diagnostic::DiagnosticProvider, syntax::config::LanguageServerFeature,
-    text_annotations::InlineAnnotation, Rope, Selection, Uri,
+    chars::char_is_word,
+    diagnostic::DiagnosticProvider,
+    object,
+    syntax::config::{LanguageServerFeature, SelectionRangeSource},
+    text_annotations::InlineAnnotation,
+    Range, Rope, Selection, SmallVec, Transaction, Uri,
 };
 use helix_stdx::path;
 use helix_view::{
     action::Action as CodeActionItem,
-    document::{DocumentInlayHints, DocumentInlayHintsId},
+    document::{
+        DocumentCodeLens, DocumentInlayHints, DocumentInlayHintsId, InlineCompletionCandidate,
+    },
     editor::Action,
-    handlers::lsp::SignatureHelpInvoked,
+    handlers::lsp::{InlineCompletionEvent, SignatureHelpInvoked},
     theme::Style,
     Document, DocumentId, View,
 };
@@ -670,6 +676,68 @@ pub fn code_action(cx: &mut Context) {
     });
 }
 
+/// Builds a picker over the code lenses of the current document. Lenses on the line of the
+/// primary cursor are preferred, all lenses of the document are shown if there are none.
+pub(crate) fn code_lens_picker_impl(editor: &mut Editor) -> Option<Picker<DocumentCodeLens, Rope>> {
+    let (view, doc) = current_ref!(editor);
+    let text = doc.text().slice(..);
+    let cursor_line = doc.selection(view.id).primary().cursor_line(text);
+
+    let lenses: Vec<_> = doc
+        .code_lenses
+        .iter()
+        .filter(|lens| lens.lens.command.is_some())
+        .cloned()
+        .collect();
+    let on_cursor_line: Vec<_> = lenses
+        .iter()
+        .filter(|lens| text.char_to_line(lens.pos.min(text.len_chars())) == cursor_line)
+        .cloned()
+        .collect();
+    let lenses = if on_cursor_line.is_empty() {
+        lenses
+    } else {
+        on_cursor_line
+    };
+
+    if lenses.is_empty() {
+        editor.set_status("No code lenses available");
+        return None;
+    }
+
+    let columns = [
+        // The lens positions are mapped through the edits since the lenses were requested.
+        ui::PickerColumn::new("line", |item: &DocumentCodeLens, text: &Rope| {
+            (text.char_to_line(item.pos.min(text.len_chars())) + 1)
+                .to_string()
+                .into()
+        }),
+        ui::PickerColumn::new("title", |item: &DocumentCodeLens, _| {
+            item.lens
+                .command
+                .as_ref()
+                .map_or("", |command| command.title.as_str())
+                .into()
+        }),
+    ];
+
+    let text = doc.text().clone();
+    let picker = Picker::new(columns, 1, lenses, text, |cx, item, _action| {
+        // Only lenses with a command are shown in the picker.
+        if let Some(command) = &item.lens.command {
+            cx.editor
+                .execute_lsp_command(command.clone(), item.language_server_id);
+        }
+    });
+    Some(picker)
+}
+
+pub fn code_lens_picker(cx: &mut Context) {
+    if let Some(picker) = code_lens_picker_impl(cx.editor) {
+        cx.push_layer(Box::new(overlaid(picker)));
+    }
+}
+
 // Extracting this to a type alias would require boxing this future
 #[allow(clippy::type_complexity)]
 pub(crate) fn code_actions_for_range(
@@ -1087,6 +1155,312 @@ pub fn goto_reference(cx: &mut Context) {
     });
 }
 
+/// How deep the type hierarchy pickers expand the hierarchy of the type under the cursor.
+const TYPE_HIERARCHY_MAX_DEPTH: usize = 16;
+/// How many items the type hierarchy pickers show at most. The hierarchy of widely used types,
+/// e.g. the subtypes of `Object`, would otherwise take thousands of requests.
+const TYPE_HIERARCHY_MAX_ITEMS: usize = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TypeHierarchyDirection {
+    Supertypes,
+    Subtypes,
+}
+
+impl Display for TypeHierarchyDirection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Supertypes => f.write_str("supertypes"),
+            Self::Subtypes => f.write_str("subtypes"),
+        }
+    }
+}
+
+struct TypeHierarchyPickerItem {
+    /// How far the item is removed from the type the hierarchy was requested for, starting at 0
+    /// for its direct supertypes or subtypes.
+    depth: usize,
+    location: Location,
+    item: lsp::TypeHierarchyItem,
+}
+
+async fn type_hierarchy_children(
+    language_server: &Client,
+    item: lsp::TypeHierarchyItem,
+    direction: TypeHierarchyDirection,
+) -> helix_lsp::Result<Option<Vec<lsp::TypeHierarchyItem>>> {
+    match direction {
+        TypeHierarchyDirection::Supertypes => {
+            match language_server.type_hierarchy_supertypes(item) {
+                Some(future) => future.await,
+                None => Ok(None),
+            }
+        }
+        TypeHierarchyDirection::Subtypes => match language_server.type_hierarchy_subtypes(item) {
+            Some(future) => future.await,
+            None => Ok(None),
+        },
+    }
+}
+
+/// Recursively expands the supertypes or subtypes of `roots`, as returned by `children_of`, into
+/// a flat list of items and their depths in depth-first order, so that every item is directly
+/// followed by its own supertypes or subtypes. Items that were already expanded are skipped
+/// which breaks cycles and diamonds in the hierarchy. At most `TYPE_HIERARCHY_MAX_ITEMS` items
+/// are returned.
+async fn expand_type_hierarchy<F, Fut>(
+    roots: Vec<lsp::TypeHierarchyItem>,
+    mut children_of: F,
+) -> Vec<(usize, lsp::TypeHierarchyItem)>
+where
+    F: FnMut(lsp::TypeHierarchyItem) -> Fut,
+    Fut: Future<Output = Vec<lsp::TypeHierarchyItem>>,
+{
+    let mut seen = HashSet::new();
+    let mut items = Vec::new();
+    // The roots are the type under the cursor which is not part of its own hierarchy, hence the
+    // depths on the stack are offset by one.
+    let mut stack: Vec<_> = roots.into_iter().rev().map(|item| (0, item)).collect();
+
+    while let Some((depth, item)) = stack.pop() {
+        if items.len() >= TYPE_HIERARCHY_MAX_ITEMS {
+            break;
+        }
+        if !seen.insert((item.uri.clone(), item.selection_range)) {
+            continue;
+        }
+
+        let children = if depth <= TYPE_HIERARCHY_MAX_DEPTH {
+            children_of(item.clone()).await
+        } else {
+            Vec::new()
+        };
+
+        if depth > 0 {
+            items.push((depth - 1, item));
+        }
+
+        stack.extend(children.into_iter().rev().map(|child| (depth + 1, child)));
+    }
+
+    items
+}
+
+fn type_hierarchy_picker(cx: &mut Context, direction: TypeHierarchyDirection) {
+    let (view, doc) = current_ref!(cx.editor);
+    let language_server =
+        language_server_with_feature!(cx.editor, doc, LanguageServerFeature::TypeHierarchy);
+    let pos = doc.position(view.id, language_server.offset_encoding());
+    let future = language_server
+        .prepare_type_hierarchy(doc.identifier(), pos)
+        .unwrap();
+    let language_server = cx
+        .editor
+        .language_servers
+        .get_by_id(language_server.id())
+        .unwrap()
+        .clone();
+
+    cx.jobs.callback(async move {
+        let roots = future.await?.unwrap_or_default();
+        let offset_encoding = language_server.offset_encoding();
+        let items = expand_type_hierarchy(roots, |item| {
+            let language_server = language_server.clone();
+            async move {
+                match type_hierarchy_children(&language_server, item, direction).await {
+                    Ok(children) => children.unwrap_or_default(),
+                    Err(err) => {
+                        log::error!("Error requesting {direction}: {err}");
+                        Vec::new()
+                    }
+                }
+            }
+        })
+        .await;
+        let truncated = items.len() >= TYPE_HIERARCHY_MAX_ITEMS;
+        let items: Vec<_> = items
+            .into_iter()
+            .filter_map(|(depth, item)| {
+                let location = lsp::Location::new(item.uri.clone(), item.selection_range);
+                Some(TypeHierarchyPickerItem {
+                    depth,
+                    location: lsp_location_to_location(location, offset_encoding)?,
+                    item,
+                })
+            })
+            .collect();
+
+        let call = move |editor: &mut Editor, compositor: &mut Compositor| {
+            if items.is_empty() {
+                editor.set_status(format!("No {direction} found"));
+                return;
+            }
+            if truncated {
+                editor.set_status(format!(
+                    "Showing the first {TYPE_HIERARCHY_MAX_ITEMS} {direction}"
+                ));
+            }
+
+            let columns = [
+                ui::PickerColumn::new("kind", |item: &TypeHierarchyPickerItem, _| {
+                    display_symbol_kind(item.item.kind).into()
+                }),
+                ui::PickerColumn::new("name", |item: &TypeHierarchyPickerItem, _| {
+                    format!("{}{}", "  ".repeat(item.depth), item.item.name).into()
+                }),
+                ui::PickerColumn::new("detail", |item: &TypeHierarchyPickerItem, _| {
+                    item.item.detail.as_deref().unwrap_or_default().into()
+                }),
+                ui::PickerColumn::new("path", |item: &TypeHierarchyPickerItem, _| {
+                    if let Some(path) = item.location.uri.as_path() {
+                        path::get_relative_path(path)
+                            .to_string_lossy()
+                            .to_string()
+                            .into()
+                    } else {
+                        item.item.uri.to_string().into()
+                    }
+                }),
+            ];
+
+            let picker = Picker::new(
+                columns,
+                1, // name column
+                items,
+                (),
+                move |cx, item, action| {
+                    jump_to_location(cx.editor, &item.location, action);
+                },
+            )
+            .with_preview(move |_editor, item| location_to_file_location(&item.location))
+            .truncate_start(false);
+
+            compositor.push(Box::new(overlaid(picker)))
+        };
+
+        Ok(Callback::EditorCompositor(Box::new(call)))
+    });
+}
+
+pub fn type_hierarchy_supertypes(cx: &mut Context) {
+    type_hierarchy_picker(cx, TypeHierarchyDirection::Supertypes)
+}
+
+pub fn type_hierarchy_subtypes(cx: &mut Context) {
+    type_hierarchy_picker(cx, TypeHierarchyDirection::Subtypes)
+}
+
+/// Expands or shrinks every selection to the LSP selection ranges around its cursor, merged with
+/// the nodes of the syntax tree if configured, see [`SelectionRangeSource`].
+///
+/// `on_select` is called with the view and its previous selection right before the new one is
+/// set, once the language server responded.
+///
+/// Returns `false` without doing anything if only the syntax tree should be used, either because
+/// it is configured that way or because no language server supports selection ranges.
+pub(crate) fn select_lsp_selection_ranges(
+    editor: &mut Editor,
+    expand: bool,
+    on_select: impl FnOnce(&mut View, Selection) + Send + 'static,
+) -> bool {
+    let (view, doc) = current_ref!(editor);
+    let source = doc
+        .language_config()
+        .map_or_else(SelectionRangeSource::default, |config| {
+            config.selection_ranges
+        });
+    if source == SelectionRangeSource::TreeSitter {
+        return false;
+    }
+    let Some(language_server) = doc
+        .language_servers_with_feature(LanguageServerFeature::SelectionRange)
+        .next()
+    else {
+        return false;
+    };
+
+    let offset_encoding = language_server.offset_encoding();
+    let text = doc.text();
+    let selection = doc.selection(view.id).clone();
+    let positions = selection
+        .iter()
+        .map(|range| pos_to_lsp_pos(text, range.cursor(text.slice(..)), offset_encoding))
+        .collect();
+    let Some(future) =
+        language_server.text_document_selection_range(doc.identifier(), positions, None)
+    else {
+        return false;
+    };
+    let (doc_id, view_id, version) = (doc.id(), view.id, doc.version());
+
+    tokio::spawn(async move {
+        let response = match future.await {
+            Ok(response) => response.unwrap_or_default(),
+            Err(err) => {
+                log::error!("selection range request failed: {err}");
+                return;
+            }
+        };
+
+        crate::job::dispatch(move |editor, _| {
+            let Some(doc) = editor.documents.get_mut(&doc_id) else {
+                return;
+            };
+            // Drop the response if the selection changed while waiting for it.
+            if doc.version() != version || doc.selections().get(&view_id) != Some(&selection) {
+                return;
+            }
+
+            let text = doc.text().slice(..);
+            let syntax = doc
+                .syntax()
+                .filter(|_| source == SelectionRangeSource::Merged);
+            let ranges: SmallVec<[Range; 1]> = selection
+                .iter()
+                .zip(response.iter().map(Some).chain(std::iter::repeat(None)))
+                .map(|(&range, selection_range)| {
+                    let mut candidates = Vec::new();
+                    let mut selection_range = selection_range;
+                    while let Some(current) = selection_range {
+                        candidates.extend(lsp_range_to_range(
+                            doc.text(),
+                            current.range,
+                            offset_encoding,
+                        ));
+                        selection_range = current.parent.as_deref();
+                    }
+                    if let Some(syntax) = syntax {
+                        let single = Selection::single(range.anchor, range.head);
+                        let node = if expand {
+                            object::expand_selection(syntax, text, single)
+                        } else {
+                            object::shrink_selection(syntax, text, single)
+                        };
+                        candidates.push(node.primary());
+                    }
+
+                    if expand {
+                        object::expand_range_to(range, candidates)
+                    } else {
+                        object::shrink_range_to(range, candidates)
+                    }
+                    .unwrap_or(range)
+                })
+                .collect();
+
+            let new_selection = Selection::new(ranges, selection.primary_index());
+            if new_selection == selection || !editor.tree.contains(view_id) {
+                return;
+            }
+            on_select(editor.tree.get_mut(view_id), selection);
+            doc.set_selection(view_id, new_selection);
+        })
+        .await;
+    });
+
+    true
+}
+
 pub fn signature_help(cx: &mut Context) {
     cx.editor
         .handlers
@@ -1548,3 +1922,161 @@ fn compute_inlay_hints_for_view(
 
     Some(callback)
 }
+
+/// Returns the candidate of the inline completion displayed in the current view together with
+/// the cursor, if any ghost text is displayed.
+fn displayed_inline_completion(editor: &Editor) -> Option<(InlineCompletionCandidate, usize)> {
+    let (view, doc) = current_ref!(editor);
+    let inline_completion = doc
+        .inline_completion
+        .as_ref()
+        .filter(|inline_completion| inline_completion.view_id == view.id)?;
+    let selection = doc.selection(view.id);
+    if selection.len() != 1 {
+        return None;
+    }
+    let text = doc.text().slice(..);
+    let cursor = selection.primary().cursor(text);
+    inline_completion.remainder(text, cursor)?;
+    Some((inline_completion.candidate().clone(), cursor))
+}
+
+pub fn inline_completion_accept(cx: &mut Context) {
+    let Some((candidate, cursor)) = displayed_inline_completion(cx.editor) else {
+        return;
+    };
+    let (view, doc) = current!(cx.editor);
+    let Some(language_server_id) = doc
+        .inline_completion
+        .as_ref()
+        .map(|inline_completion| inline_completion.language_server_id)
+    else {
+        return;
+    };
+    doc.clear_inline_completion();
+
+    // Text after the cursor that is part of the candidate's range is replaced as well.
+    let start = candidate.range.start;
+    let end = candidate.range.end.max(cursor);
+    let pos = start + candidate.insert_text.chars().count();
+    let transaction = Transaction::change(
+        doc.text(),
+        std::iter::once((start, end, Some(candidate.insert_text.into()))),
+    )
+    .with_selection(Selection::point(pos));
+    doc.apply(&transaction, view.id);
+
+    if let Some(command) = candidate.command {
+        cx.editor.execute_lsp_command(command, language_server_id);
+    }
+}
+
+pub fn inline_completion_accept_word(cx: &mut Context) {
+    let Some((candidate, cursor)) = displayed_inline_completion(cx.editor) else {
+        return;
+    };
+    let (view, doc) = current!(cx.editor);
+    let text = doc.text().slice(..);
+    let typed: String = text.slice(candidate.range.start..cursor).into();
+    let remainder = &candidate.insert_text[typed.len()..];
+
+    // Accepts the leading whitespace and the following word, or a single other character.
+    let whitespace = remainder.len() - remainder.trim_start_matches([' ', '\t']).len();
+    let rest = &remainder[whitespace..];
+    let word = rest.len() - rest.trim_start_matches(char_is_word).len();
+    let len = match rest.chars().next() {
+        Some(_) if word > 0 => whitespace + word,
+        Some(ch) => whitespace + ch.len_utf8(),
+        None => whitespace,
+    };
+
+    let transaction =
+        Transaction::insert(doc.text(), doc.selection(view.id), remainder[..len].into());
+    doc.apply(&transaction, view.id);
+}
+
+pub fn inline_completion_next(cx: &mut Context) {
+    let (view, doc) = current!(cx.editor);
+    if let Some(inline_completion) = doc.inline_completion.as_mut().filter(|inline_completion| {
+        inline_completion.view_id == view.id && inline_completion.candidates.len() > 1
+    }) {
+        inline_completion.index =
+            (inline_completion.index + 1) % inline_completion.candidates.len();
+        doc.update_inline_completion_ghost_text();
+        return;
+    }
+
+    // Ask the language server for more candidates.
+    helix_event::send_blocking(
+        &cx.editor.handlers.inline_completion,
+        InlineCompletionEvent::Invoked {
+            document_id: doc.id(),
+            view_id: view.id,
+        },
+    );
+}
+
+pub fn inline_completion_dismiss(cx: &mut Context) {
+    let (_view, doc) = current!(cx.editor);
+    doc.clear_inline_completion();
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn type_item(id: u32) -> lsp::TypeHierarchyItem {
+        let range = lsp::Range::new(lsp::Position::new(id, 0), lsp::Position::new(id, 1));
+        lsp::TypeHierarchyItem {
+            name: id.to_string(),
+            kind: lsp::SymbolKind::CLASS,
+            tags: None,
+            detail: None,
+            uri: lsp::Url::parse("file:///types.rs").unwrap(),
+            range,
+            selection_range: range,
+            data: None,
+        }
+    }
+
+    fn expand(children_of: impl Fn(u32) -> Vec<u32>) -> Vec<(usize, u32)> {
+        let items = helix_lsp::block_on(expand_type_hierarchy(vec![type_item(0)], |item| {
+            let children = children_of(item.selection_range.start.line);
+            async move { children.into_iter().map(type_item).collect() }
+        }));
+        items
+            .into_iter()
+            .map(|(depth, item)| (depth, item.selection_range.start.line))
+            .collect()
+    }
+
+    #[test]
+    fn type_hierarchy_depth_first() {
+        // 1 and 2 both have 3 as a supertype, which has the root as its supertype again.
+        let items = expand(|id| match id {
+            0 => vec![1, 2],
+            1 | 2 => vec![3],
+            3 => vec![0],
+            _ => Vec::new(),
+        });
+        assert_eq!(items, [(0, 1), (1, 3), (0, 2)]);
+    }
+
+    #[test]
+    fn type_hierarchy_limits() {
+        // A chain deeper than the maximum depth.
+        let items = expand(|id| vec![id + 1]);
+        assert_eq!(items.len(), TYPE_HIERARCHY_MAX_DEPTH + 1);
+        assert_eq!(items.last(), Some(&(TYPE_HIERARCHY_MAX_DEPTH, 17)));
+
+        // A hierarchy too wide to expand completely.
+        let items = expand(|id| {
+            if id == 0 {
+                (1..=1000).collect()
+            } else {
+                Vec::new()
+            }
+        });
+        assert_eq!(items.len(), TYPE_HIERARCHY_MAX_ITEMS);
+    }
+}
@@ -15,9 +15,11 @@
 use helix_core::indent::MAX_INDENT;
 use helix_core::line_ending;
 use helix_stdx::path::home_dir;
-use helix_view::document::{read_to_string, DEFAULT_LANGUAGE_NAME};
-use helix_view::editor::{CloseError, ConfigEvent};
+use helix_vcs::LineBlame;
+use helix_view::document::{read_to_string, DocumentDiffBase, DEFAULT_LANGUAGE_NAME};
+use helix_view::editor::{CloseError, ConfigEvent, GutterType};
 use helix_view::expansion;
+use helix_view::session::Session;
 use serde_json::Value;
 use ui::completers::{self, Completer};
 
@@ -502,9 +504,14 @@ fn write_impl(
     path: Option<&str>,
     options: WriteOptions,
 ) -> anyhow::Result<()> {
+    // Writing a buffer opened with `:macro-edit` stores the macro instead.
+    let doc_id = doc!(cx.editor).id();
+    if path.is_none() && cx.editor.macro_buffers.contains_key(&doc_id) {
+        return cx.editor.write_macro_buffer(doc_id);
+    }
+
     let config = cx.editor.config();
     let (view, doc) = current!(cx.editor);
-    let doc_id = doc.id();
     let view_id = view.id;
 
     if doc.trim_trailing_whitespace() {
@@ -1727,12 +1734,17 @@ fn reload(cx: &mut compositor::Context, _args: Args, event: PromptEvent) -> anyh
         .map(|_| {
             view.ensure_cursor_in_view(doc, scrolloff);
         })?;
+    // `HEAD` may have moved since the blame was loaded.
+    let (doc_id, reblame) = (doc.id(), doc.blame.is_some());
     if let Some(path) = doc.path().map(ToOwned::to_owned) {
         cx.editor
             .language_servers
             .file_event_handler
             .file_changed(path);
     }
+    if reblame {
+        crate::handlers::blame::request_blame(cx.editor, doc_id, |_, _| {});
+    }
     Ok(())
 }
 
@@ -1781,6 +1793,7 @@ fn reload_all(cx: &mut compositor::Context, _args: Args, event: PromptEvent) ->
             cx.editor.set_error(format!("{}", error));
             continue;
         }
+        let reblame = doc.blame.is_some();
 
         if let Some(path) = doc.path().map(ToOwned::to_owned) {
             cx.editor
@@ -1802,6 +1815,10 @@ fn reload_all(cx: &mut compositor::Context, _args: Args, event: PromptEvent) ->
                 view.ensure_cursor_in_view(doc, scrolloff);
             }
         }
+
+        if reblame {
+            crate::handlers::blame::request_blame(cx.editor, doc_id, |_, _| {});
+        }
     }
 
     Ok(())
@@ -1929,6 +1946,29 @@ fn lsp_workspace_command(
     Ok(())
 }
 
+fn lsp_code_lens(
+    cx: &mut compositor::Context,
+    _args: Args,
+    event: PromptEvent,
+) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    let callback = async move {
+        let call: job::Callback = Callback::EditorCompositor(Box::new(
+            move |editor: &mut Editor, compositor: &mut Compositor| {
+                if let Some(picker) = code_lens_picker_impl(editor) {
+                    compositor.push(Box::new(overlaid(picker)))
+                }
+            },
+        ));
+        Ok(call)
+    };
+    cx.jobs.callback(callback);
+    Ok(())
+}
+
 fn lsp_restart(cx: &mut compositor::Context, args: Args, event: PromptEvent) -> anyhow::Result<()> {
     if event != PromptEvent::Validate {
         return Ok(());
@@ -2268,12 +2308,53 @@ fn debug_eval(cx: &mut compositor::Context, args: Args, event: PromptEvent) -> a
         // TODO: support no frame_id
 
         let frame_id = debugger.stack_frames[&thread_id][frame].id;
-        let response = helix_lsp::block_on(debugger.eval(args.join(" "), Some(frame_id)))?;
+        let response = helix_lsp::block_on(debugger.eval(args.join(" "), Some(frame_id), None))?;
         cx.editor.set_status(response.result);
     }
     Ok(())
 }
 
+fn debug_function_breakpoint(
+    cx: &mut compositor::Context,
+    args: Args,
+    event: PromptEvent,
+) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    dap_toggle_function_breakpoint_impl(cx.editor, &args[0])
+}
+
+fn debug_data_breakpoint(
+    cx: &mut compositor::Context,
+    args: Args,
+    event: PromptEvent,
+) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    dap_toggle_data_breakpoint_impl(cx.editor, None, &args[0], args.get(1))
+}
+
+fn debug_instruction_breakpoint(
+    cx: &mut compositor::Context,
+    args: Args,
+    event: PromptEvent,
+) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    let offset = args
+        .get(1)
+        .map(|offset| offset.parse::<isize>())
+        .transpose()
+        .map_err(|_| anyhow!("Invalid offset '{}'", &args[1]))?;
+    dap_toggle_instruction_breakpoint_impl(cx.editor, &args[0], offset)
+}
+
 fn debug_start(cx: &mut compositor::Context, args: Args, event: PromptEvent) -> anyhow::Result<()> {
     if event != PromptEvent::Validate {
         return Ok(());
@@ -2988,6 +3069,22 @@ fn clear_register(
     Ok(())
 }
 
+fn macro_edit(cx: &mut compositor::Context, args: Args, event: PromptEvent) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    let register = match args.first() {
+        Some(arg) => {
+            ensure!(arg.chars().count() == 1, format!("Invalid register {arg}"));
+            arg.chars().next().unwrap_or_default()
+        }
+        None => '@',
+    };
+    cx.editor.edit_macro(register, Action::Replace);
+    Ok(())
+}
+
 fn set_register(
     cx: &mut compositor::Context,
     args: Args,
@@ -3179,6 +3276,53 @@ fn echo(cx: &mut compositor::Context, args: Args, event: PromptEvent) -> anyhow:
     Ok(())
 }
 
+fn session_save(
+    cx: &mut compositor::Context,
+    args: Args,
+    event: PromptEvent,
+) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    let name = &args[0];
+    cx.editor.capture_session().save(name)?;
+    cx.editor.set_status(format!("Saved session '{name}'"));
+    Ok(())
+}
+
+fn session_load(
+    cx: &mut compositor::Context,
+    args: Args,
+    event: PromptEvent,
+) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    cx.block_try_flush_writes()?;
+    let name = &args[0];
+    let session = Session::load(name)?;
+    cx.editor.restore_session(&session)?;
+    cx.editor.set_status(format!("Loaded session '{name}'"));
+    Ok(())
+}
+
+fn session_delete(
+    cx: &mut compositor::Context,
+    args: Args,
+    event: PromptEvent,
+) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    let name = &args[0];
+    Session::delete(name)?;
+    cx.editor.set_status(format!("Deleted session '{name}'"));
+    Ok(())
+}
+
 fn noop(_cx: &mut compositor::Context, _args: Args, _event: PromptEvent) -> anyhow::Result<()> {
     Ok(())
 }
@@ -3914,6 +4058,17 @@ fn noop(_cx: &mut compositor::Context, _args: Args, _event: PromptEvent) -> anyh
             ..Signature::DEFAULT
         },
     },
+    TypableCommand {
+        name: "lsp-code-lens",
+        aliases: &[],
+        doc: "Open the code lens picker for the current line, or the whole document if the line has no code lenses.",
+        fun: lsp_code_lens,
+        completer: CommandCompleter::none(),
+        signature: Signature {
+            positionals: (0, Some(0)),
+            ..Signature::DEFAULT
+        },
+    },
     TypableCommand {
         name: "lsp-restart",
         aliases: &[],
@@ -3972,7 +4127,7 @@ fn noop(_cx: &mut compositor::Context, _args: Args, _event: PromptEvent) -> anyh
     TypableCommand {
         name: "debug-start",
         aliases: &["dbg"],
-        doc: "Start a debug session from a given template with given parameters.",
+        doc: "Start a debug session from a given template or workspace launch configuration with given parameters.",
         fun: debug_start,
         completer: CommandCompleter::none(),
         signature: Signature {
@@ -4002,6 +4157,39 @@ fn noop(_cx: &mut compositor::Context, _args: Args, _event: PromptEvent) -> anyh
             ..Signature::DEFAULT
         },
     },
+    TypableCommand {
+        name: "debug-function-breakpoint",
+        aliases: &[],
+        doc: "Toggle a breakpoint on the function with the given name.",
+        fun: debug_function_breakpoint,
+        completer: CommandCompleter::none(),
+        signature: Signature {
+            positionals: (1, Some(1)),
+            ..Signature::DEFAULT
+        },
+    },
+    TypableCommand {
+        name: "debug-data-breakpoint",
+        aliases: &[],
+        doc: "Toggle a data breakpoint on the given expression, optionally only for an access type (`read`, `write` or `readWrite`).",
+        fun: debug_data_breakpoint,
+        completer: CommandCompleter::none(),
+        signature: Signature {
+            positionals: (1, Some(2)),
+            ..Signature::DEFAULT
+        },
+    },
+    TypableCommand {
+        name: "debug-instruction-breakpoint",
+        aliases: &[],
+        doc: "Toggle a breakpoint on the given instruction memory reference, with an optional offset in bytes.",
+        fun: debug_instruction_breakpoint,
+        completer: CommandCompleter::none(),
+        signature: Signature {
+            positionals: (1, Some(2)),
+            ..Signature::DEFAULT
+        },
+    },
     TypableCommand {
         name: "vsplit",
         aliases: &["vs"],
@@ -4276,6 +4464,17 @@ fn noop(_cx: &mut compositor::Context, _args: Args, _event: PromptEvent) -> anyh
             ..Signature::DEFAULT
         },
     },
+    TypableCommand {
+        name: "macro-edit",
+        aliases: &[],
+        doc: "Edit the macro in the given register, `@` by default, in a scratch buffer. Writing the buffer stores the macro back to the register.",
+        fun: macro_edit,
+        completer: CommandCompleter::all(completers::register),
+        signature: Signature {
+            positionals: (0, Some(1)),
+            ..Signature::DEFAULT
+        },
+    },
     TypableCommand {
         name: "set-register",
         aliases: &[],
@@ -4430,6 +4629,94 @@ fn noop(_cx: &mut compositor::Context, _args: Args, _event: PromptEvent) -> anyh
         fun: exclude_workspace,
         completer: CommandCompleter::none(),
         signature: Signature { positionals: (0, None), ..Signature::DEFAULT },
+    },
+    TypableCommand {
+        name: "session-save",
+        aliases: &[],
+        doc: "Save the open buffers, splits, jumplists, registers and working directory as a named session.",
+        fun: session_save,
+        completer: CommandCompleter::positional(&[completers::session]),
+        signature: Signature {
+            positionals: (1, Some(1)),
+            ..Signature::DEFAULT
+        },
+    },
+    TypableCommand {
+        name: "session-load",
+        aliases: &[],
+        doc: "Replace all buffers and splits with a named session.",
+        fun: session_load,
+        completer: CommandCompleter::positional(&[completers::session]),
+        signature: Signature {
+            positionals: (1, Some(1)),
+            ..Signature::DEFAULT
+        },
+    },
+    TypableCommand {
+        name: "session-delete",
+        aliases: &[],
+        doc: "Delete a named session.",
+        fun: session_delete,
+        completer: CommandCompleter::positional(&[completers::session]),
+        signature: Signature {
+            positionals: (1, Some(1)),
+            ..Signature::DEFAULT
+        },
+    },
+    TypableCommand {
+        name: "blame",
+        aliases: &[],
+        doc: "Show the git commit that last changed the cursor line.",
+        fun: blame,
+        completer: CommandCompleter::none(),
+        signature: Signature {
+            positionals: (0, Some(0)),
+            ..Signature::DEFAULT
+        },
+    },
+    TypableCommand {
+        name: "blame-gutter",
+        aliases: &[],
+        doc: "Toggle the git blame gutter of the current buffer.",
+        fun: blame_gutter,
+        completer: CommandCompleter::none(),
+        signature: Signature {
+            positionals: (0, Some(0)),
+            ..Signature::DEFAULT
+        },
+    },
+    TypableCommand {
+        name: "diff-base",
+        aliases: &[],
+        doc: "Compare the current buffer against a git revision, for example a branch, a tag or `HEAD~3`, or against the staged file with `index`.",
+        fun: diff_base,
+        completer: CommandCompleter::none(),
+        signature: Signature {
+            positionals: (1, Some(1)),
+            ..Signature::DEFAULT
+        },
+    },
+    TypableCommand {
+        name: "diff-base-file",
+        aliases: &[],
+        doc: "Compare the current buffer against another file.",
+        fun: diff_base_file,
+        completer: CommandCompleter::positional(&[completers::filename]),
+        signature: Signature {
+            positionals: (1, Some(1)),
+            ..Signature::DEFAULT
+        },
+    },
+    TypableCommand {
+        name: "diff-base-reset",
+        aliases: &[],
+        doc: "Compare the current buffer against the base selected by the `diff-base` option again.",
+        fun: diff_base_reset,
+        completer: CommandCompleter::none(),
+        signature: Signature {
+            positionals: (0, Some(0)),
+            ..Signature::DEFAULT
+        },
     }
 ];
 
@@ -4703,6 +4990,9 @@ pub fn complete_command_args(
         TokenKind::Expansion(ExpansionKind::Register) => {
             complete_register_expansion(editor, &token.content, offset + token.content_start)
         }
+        TokenKind::Expansion(ExpansionKind::Lsp) => {
+            complete_lsp_expansion(editor, &token, offset + token.content_start)
+        }
         TokenKind::ExpansionKind => {
             complete_expansion_kind(&token.content, offset + token.content_start)
         }
@@ -4797,6 +5087,9 @@ fn complete_expand(
                 TokenKind::Expansion(ExpansionKind::Shell) => {
                     return complete_expand(editor, &token, None, local_offset);
                 }
+                TokenKind::Expansion(ExpansionKind::Lsp) => {
+                    return complete_lsp_expansion(editor, &token, local_offset);
+                }
                 TokenKind::ExpansionKind => {
                     return complete_expansion_kind(&token.content, local_offset);
                 }
@@ -4844,6 +5137,22 @@ fn complete_register_expansion(
         .collect()
 }
 
+/// Completes the language server command of an `%lsp{..}` expansion, or else the expansions in
+/// its arguments.
+fn complete_lsp_expansion(
+    editor: &Editor,
+    token: &Token,
+    offset: usize,
+) -> Vec<ui::prompt::Completion> {
+    if token.content.contains(char::is_whitespace) {
+        return complete_expand(editor, token, None, offset);
+    }
+    ui::completers::lsp_workspace_command(editor, &token.content)
+        .into_iter()
+        .map(|(_, span)| (offset.., span))
+        .collect()
+}
+
 fn complete_expansion_kind(content: &str, offset: usize) -> Vec<ui::prompt::Completion> {
     use command_line::ExpansionKind;
 
@@ -4866,6 +5175,143 @@ fn current_workspace(cx: &compositor::Context) -> std::path::PathBuf {
     doc.workspace_root().to_path_buf()
 }
 
+fn blame(cx: &mut compositor::Context, _args: Args, event: PromptEvent) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    let doc = doc!(cx.editor);
+    if doc.path().is_none() {
+        bail!("Cannot blame a buffer without a path");
+    }
+
+    let doc_id = doc.id();
+    if doc.blame.is_some() {
+        let callback = async move {
+            let call: job::Callback = Callback::EditorCompositor(Box::new(show_line_blame));
+            Ok(call)
+        };
+        cx.jobs.callback(callback);
+    } else {
+        crate::handlers::blame::request_blame(cx.editor, doc_id, show_line_blame);
+    }
+
+    Ok(())
+}
+
+fn show_line_blame(editor: &mut Editor, compositor: &mut Compositor) {
+    let (view, doc) = current_ref!(editor);
+    let line = doc
+        .selection(view.id)
+        .primary()
+        .cursor_line(doc.text().slice(..));
+    let contents = match doc.line_blame(line) {
+        Some(LineBlame::Committed(commit)) => format!(
+            "**{}** {} <{}>\n\n{}\n\n{}",
+            commit.id, commit.author, commit.email, commit.date, commit.summary
+        ),
+        Some(LineBlame::Uncommitted) => "Not committed yet".to_string(),
+        None => {
+            editor.set_error("The buffer is not tracked by git");
+            return;
+        }
+    };
+
+    let contents = ui::Markdown::new(contents, editor.syn_loader.clone());
+    let popup = Popup::new("blame", contents).auto_close(true);
+    compositor.replace_or_push("blame", popup);
+}
+
+fn blame_gutter(
+    cx: &mut compositor::Context,
+    _args: Args,
+    event: PromptEvent,
+) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    if !cx
+        .editor
+        .config()
+        .gutters
+        .layout
+        .contains(&GutterType::Blame)
+    {
+        bail!("Add \"blame\" to `editor.gutters.layout` to show the blame gutter");
+    }
+
+    let doc = doc_mut!(cx.editor);
+    doc.blame_gutter = !doc.blame_gutter;
+    if doc.blame_gutter && doc.blame.is_none() {
+        let doc_id = doc.id();
+        crate::handlers::blame::request_blame(cx.editor, doc_id, |_, _| {});
+    }
+
+    Ok(())
+}
+
+fn diff_base(cx: &mut compositor::Context, args: Args, event: PromptEvent) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    let base = match &args[0] {
+        "index" => DocumentDiffBase::Index,
+        rev => DocumentDiffBase::Revision(rev.to_string()),
+    };
+    set_diff_base_override(cx.editor, Some(base))
+}
+
+fn diff_base_file(
+    cx: &mut compositor::Context,
+    args: Args,
+    event: PromptEvent,
+) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    let path = helix_stdx::path::canonicalize(helix_stdx::path::expand_tilde(Path::new(&args[0])));
+    set_diff_base_override(cx.editor, Some(DocumentDiffBase::File(path)))
+}
+
+fn diff_base_reset(
+    cx: &mut compositor::Context,
+    _args: Args,
+    event: PromptEvent,
+) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    set_diff_base_override(cx.editor, None)
+}
+
+fn set_diff_base_override(
+    editor: &mut Editor,
+    base: Option<DocumentDiffBase>,
+) -> anyhow::Result<()> {
+    let trust_full = doc_trust_full(editor);
+    let doc = doc_mut!(editor);
+    let Some(path) = doc.path() else {
+        bail!("Cannot diff a buffer without a path");
+    };
+    match base {
+        Some(base) => {
+            let diff_base = base.read(path, &editor.diff_providers, trust_full)?;
+            doc.set_diff_base(diff_base);
+            doc.diff_base_override = Some(base);
+            doc.update_blame_diff_base(&editor.diff_providers, trust_full);
+        }
+        None => {
+            doc.diff_base_override = None;
+            doc.update_diff_base(&editor.diff_providers, trust_full);
+        }
+    }
+    Ok(())
+}
+
 /// Whether the currently focused document's workspace is trusted for git operations (gix
 /// `Trust::Full`).
 fn doc_trust_full(editor: &helix_view::Editor) -> bool {
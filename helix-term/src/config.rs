@@ -1,5 +1,7 @@
 use crate::keymap;
 use crate::keymap::{merge_keys, KeyTrie};
+use anyhow::Context as _;
+use helix_core::syntax;
 use helix_loader::merge_toml_values;
 use helix_view::{document::Mode, theme};
 use serde::Deserialize;
@@ -13,6 +15,8 @@
 pub struct Config {
     pub theme: Option<theme::Config>,
     pub keys: HashMap<Mode, KeyTrie>,
+    /// The keymaps of the languages with a `keys` table in `languages.toml`, by language name.
+    pub language_keys: HashMap<String, HashMap<Mode, KeyTrie>>,
     pub editor: helix_view::editor::Config,
 }
 
@@ -29,6 +33,7 @@ fn default() -> Config {
         Config {
             theme: None,
             keys: keymap::default(),
+            language_keys: HashMap::new(),
             editor: helix_view::editor::Config::default(),
         }
     }
@@ -87,6 +92,7 @@ pub fn load(
                 Config {
                     theme: local.theme.or(global.theme),
                     keys,
+                    language_keys: HashMap::new(),
                     editor,
                 }
             }
@@ -103,6 +109,7 @@ pub fn load(
                 Config {
                     theme: config.theme,
                     keys,
+                    language_keys: HashMap::new(),
                     editor: config.editor.map_or_else(
                         || Ok(helix_view::editor::Config::default()),
                         |val| val.try_into().map_err(ConfigLoadError::BadConfig),
@@ -117,6 +124,26 @@ pub fn load(
         Ok(res)
     }
 
+    /// Builds the keymaps of the languages with a `keys` table: the global keymap with the keys
+    /// of the language merged over it.
+    pub fn load_language_keys(&mut self, loader: &syntax::Loader) -> anyhow::Result<()> {
+        let mut language_keys = HashMap::new();
+        for language in loader.language_configs() {
+            let Some(keys) = &language.keys else {
+                continue;
+            };
+            let delta: HashMap<Mode, KeyTrie> = keys
+                .clone()
+                .try_into()
+                .with_context(|| format!("Invalid keys for language {}", language.language_id))?;
+            let mut merged = self.keys.clone();
+            merge_keys(&mut merged, delta);
+            language_keys.insert(language.language_id.clone(), merged);
+        }
+        self.language_keys = language_keys;
+        Ok(())
+    }
+
     pub fn load_default() -> Result<Config, ConfigLoadError> {
         let global_config =
             fs::read_to_string(helix_loader::config_file()).map_err(ConfigLoadError::Error)?;
@@ -199,6 +226,52 @@ fn parsing_keymaps_config_file() {
         );
     }
 
+    #[test]
+    fn language_keys() {
+        use crate::keymap;
+        use helix_core::hashmap;
+        use helix_view::document::Mode;
+
+        let languages = r#"
+            [[language]]
+            name = "markdown"
+            scope = "text.markdown"
+            file-types = ["md"]
+
+            [language.keys.normal]
+            y = "move_line_down"
+            space = { x = "goto_next_diag" }
+
+            [[language]]
+            name = "rust"
+            scope = "source.rust"
+            file-types = ["rs"]
+        "#;
+        let loader = syntax::Loader::new(toml::from_str(languages).unwrap()).unwrap();
+        let mut config = Config::load_test("[keys.normal]\nz = \"move_char_left\"");
+        config.load_language_keys(&loader).unwrap();
+
+        let mut keys = config.keys.clone();
+        merge_keys(
+            &mut keys,
+            hashmap! {
+                Mode::Normal => keymap!({ "Normal mode"
+                    "y" => move_line_down,
+                    "space" => { ""
+                        "x" => goto_next_diag,
+                    },
+                }),
+            },
+        );
+        assert_eq!(config.language_keys.len(), 1);
+        assert_eq!(config.language_keys["markdown"], keys);
+        // The other bindings of the space menu are kept.
+        let space = config.language_keys["markdown"][&Mode::Normal]
+            .search(&["space".parse().unwrap()])
+            .unwrap();
+        assert!(space.node().unwrap().len() > 1);
+    }
+
     #[test]
     fn keys_resolve_to_correct_defaults() {
         // From serde default
@@ -12,17 +12,32 @@
 
 pub use helix_view::handlers::{word_index, Handlers};
 
+use self::code_lens::CodeLensHandler;
 use self::document_colors::DocumentColorsHandler;
 use self::document_links::DocumentLinksHandler;
+use self::folding_ranges::FoldingRangesHandler;
+use self::inline_completion::InlineCompletionHandler;
+use self::inline_values::InlineValuesHandler;
+use self::linked_editing::LinkedEditingHandler;
+use self::semantic_tokens::SemanticTokensHandler;
 
 mod auto_save;
+pub mod blame;
 mod code_action_hint;
+pub mod code_lens;
 pub mod completion;
+mod conflicts;
 pub mod diagnostics;
 mod document_colors;
 mod document_highlight;
 mod document_links;
+mod folding_ranges;
+mod hunk_diff;
+mod inline_completion;
+mod inline_values;
+mod linked_editing;
 mod prompt;
+mod semantic_tokens;
 mod signature_help;
 mod snippet;
 mod workspace_trust;
@@ -36,6 +51,12 @@ pub fn setup(config: Arc<ArcSwap<Config>>) -> Handlers {
     let code_action_hint = code_action_hint::Handler::default().spawn();
     let document_colors = DocumentColorsHandler::default().spawn();
     let document_links = DocumentLinksHandler::default().spawn();
+    let folding_ranges = FoldingRangesHandler::default().spawn();
+    let semantic_tokens = SemanticTokensHandler::default().spawn();
+    let code_lenses = CodeLensHandler::default().spawn();
+    let inline_values = InlineValuesHandler::default().spawn();
+    let linked_editing = LinkedEditingHandler::default().spawn();
+    let inline_completion = InlineCompletionHandler::default().spawn();
     let word_index = word_index::Handler::spawn();
     let pull_diagnostics = PullDiagnosticsHandler::default().spawn();
     let pull_all_documents_diagnostics = PullAllDocumentsDiagnosticHandler::default().spawn();
@@ -46,6 +67,12 @@ pub fn setup(config: Arc<ArcSwap<Config>>) -> Handlers {
         auto_save,
         document_colors,
         document_links,
+        folding_ranges,
+        semantic_tokens,
+        code_lenses,
+        inline_values,
+        linked_editing,
+        inline_completion,
         word_index,
         pull_diagnostics,
         pull_all_documents_diagnostics,
@@ -58,10 +85,18 @@ pub fn setup(config: Arc<ArcSwap<Config>>) -> Handlers {
     document_highlight::register_hooks(&handlers);
     code_action_hint::register_hooks(&handlers);
     auto_save::register_hooks(&handlers);
+    blame::register_hooks(&handlers);
+    conflicts::register_hooks(&handlers);
     diagnostics::register_hooks(&handlers);
     snippet::register_hooks(&handlers);
     document_colors::register_hooks(&handlers);
     document_links::register_hooks(&handlers);
+    folding_ranges::register_hooks(&handlers);
+    hunk_diff::register_hooks(&handlers);
+    semantic_tokens::register_hooks(&handlers);
+    code_lens::register_hooks(&handlers);
+    linked_editing::register_hooks(&handlers);
+    inline_completion::register_hooks(&handlers);
     prompt::register_hooks(&handlers);
     workspace_trust::register_hooks(&handlers);
     handlers
@@ -0,0 +1,83 @@
+use helix_event::{cancelable_future, register_hook};
+use helix_loader::workspace_trust::TrustQuery;
+use helix_view::{
+    events::{ConfigDidChange, DocumentDidOpen},
+    handlers::Handlers,
+    DocumentId, Editor,
+};
+
+use crate::{compositor::Compositor, job};
+
+/// Blame a document in the background, attach the result to it and then call `on_done`.
+///
+/// The document's blame is cleared if it can't be blamed, for example because it isn't tracked
+/// by git. `on_done` is not called if the document has no path or is closed in the meantime.
+pub fn request_blame(
+    editor: &mut Editor,
+    doc_id: DocumentId,
+    on_done: impl FnOnce(&mut Editor, &mut Compositor) + Send + 'static,
+) {
+    let Some(doc) = editor.document(doc_id) else {
+        return;
+    };
+    let Some(path) = doc.path().map(ToOwned::to_owned) else {
+        return;
+    };
+    let trust_full = editor
+        .workspace_trust
+        .query(doc.workspace_root(), TrustQuery::Git)
+        .is_trusted();
+    let base = doc.config.load().diff_base;
+    let diff_providers = editor.diff_providers.clone();
+    let cancel = doc_mut!(editor, &doc_id).blame_controller.restart();
+
+    tokio::spawn(async move {
+        let blame =
+            tokio::task::spawn_blocking(move || diff_providers.blame(&path, base, trust_full));
+        let Some(Ok(blame)) = cancelable_future(blame, &cancel).await else {
+            return;
+        };
+
+        job::dispatch(move |editor, compositor| {
+            let Some(doc) = editor.document_mut(doc_id) else {
+                return;
+            };
+            doc.blame = blame;
+            on_done(editor, compositor);
+        })
+        .await;
+    });
+}
+
+pub(super) fn register_hooks(_handlers: &Handlers) {
+    register_hook!(move |event: &mut DocumentDidOpen<'_>| {
+        if event.editor.config().inline_blame.enable {
+            request_blame(event.editor, event.doc, |_, _| {});
+        }
+        Ok(())
+    });
+
+    register_hook!(move |event: &mut ConfigDidChange<'_>| {
+        let enabled = event.new.inline_blame.enable && !event.old.inline_blame.enable;
+        // The blame of lines depends on whether staged changes are committed.
+        let rebase = event.new.diff_base != event.old.diff_base;
+        if enabled || rebase {
+            let doc_ids: Vec<_> = event
+                .editor
+                .documents()
+                .filter(|doc| {
+                    if doc.blame.is_some() {
+                        rebase
+                    } else {
+                        event.new.inline_blame.enable
+                    }
+                })
+                .map(|doc| doc.id())
+                .collect();
+            for doc_id in doc_ids {
+                request_blame(event.editor, doc_id, |_, _| {});
+            }
+        }
+        Ok(())
+    });
+}
@@ -0,0 +1,286 @@
+use std::{collections::HashSet, ops::Range, time::Duration};
+
+use futures_util::{stream::FuturesUnordered, StreamExt};
+use helix_core::{syntax::config::LanguageServerFeature, Assoc, RopeSlice};
+use helix_event::{cancelable_future, register_hook};
+use helix_view::{
+    document::DocumentCodeLens,
+    events::{DocumentDidChange, DocumentDidOpen, LanguageServerExited, LanguageServerInitialized},
+    handlers::{lsp::CodeLensEvent, Handlers},
+    DocumentId, Editor,
+};
+use tokio::time::Instant;
+
+use crate::job;
+
+#[derive(Default)]
+pub(super) struct CodeLensHandler {
+    docs: HashSet<DocumentId>,
+}
+
+const DOCUMENT_CHANGE_DEBOUNCE: Duration = Duration::from_millis(250);
+
+impl helix_event::AsyncHook for CodeLensHandler {
+    type Event = CodeLensEvent;
+
+    fn handle_event(&mut self, event: Self::Event, _timeout: Option<Instant>) -> Option<Instant> {
+        let CodeLensEvent(doc_id) = event;
+        self.docs.insert(doc_id);
+        Some(Instant::now() + DOCUMENT_CHANGE_DEBOUNCE)
+    }
+
+    fn finish_debounce(&mut self) {
+        let docs = std::mem::take(&mut self.docs);
+
+        job::dispatch_blocking(move |editor, _compositor| {
+            for doc in docs {
+                request_code_lenses(editor, doc);
+            }
+        });
+    }
+}
+
+/// Request code lenses for a specific document. Unresolved code lenses are resolved once they are
+/// visible, see [`resolve_visible_code_lenses`].
+fn request_code_lenses(editor: &mut Editor, doc_id: DocumentId) {
+    let Some(doc) = editor.document_mut(doc_id) else {
+        return;
+    };
+    let cancel = doc.code_lens_controller.restart();
+    let doc = &editor.documents[&doc_id];
+
+    let mut seen_language_servers = HashSet::new();
+    let mut futures: FuturesUnordered<_> = doc
+        .language_servers_with_feature(LanguageServerFeature::CodeLens)
+        .filter(|ls| seen_language_servers.insert(ls.id()))
+        .filter_map(|language_server| {
+            let text = doc.text().clone();
+            let offset_encoding = language_server.offset_encoding();
+            let language_server_id = language_server.id();
+            let future = language_server.text_document_code_lens(doc.identifier(), None)?;
+
+            Some(async move {
+                let lenses: Vec<_> = future
+                    .await?
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter_map(|lens| {
+                        let pos = helix_lsp::util::lsp_pos_to_pos(
+                            &text,
+                            lens.range.start,
+                            offset_encoding,
+                        )?;
+                        Some(DocumentCodeLens {
+                            pos,
+                            lens,
+                            language_server_id,
+                            resolve_requested: false,
+                        })
+                    })
+                    .collect();
+                anyhow::Ok(lenses)
+            })
+        })
+        .collect();
+
+    if futures.is_empty() {
+        return;
+    }
+
+    tokio::spawn(async move {
+        let mut all_lenses = Vec::new();
+        loop {
+            match cancelable_future(futures.next(), &cancel).await {
+                Some(Some(Ok(items))) => all_lenses.extend(items),
+                Some(Some(Err(err))) => log::error!("code lens request failed: {err}"),
+                Some(None) => break,
+                None => return,
+            }
+        }
+
+        job::dispatch(move |editor, _| attach_code_lenses(editor, doc_id, all_lenses)).await;
+    });
+}
+
+fn attach_code_lenses(editor: &mut Editor, doc_id: DocumentId, mut lenses: Vec<DocumentCodeLens>) {
+    let Some(doc) = editor.documents.get_mut(&doc_id) else {
+        return;
+    };
+
+    lenses.sort_by_key(|lens| lens.pos);
+    doc.code_lenses = lenses;
+    resolve_visible_code_lenses(editor);
+}
+
+/// Resolves the unresolved code lenses of the lines shown in any view, so that their titles can
+/// be displayed. Each lens is resolved at most once.
+pub(crate) fn resolve_visible_code_lenses(editor: &mut Editor) {
+    if !editor.config().lsp.display_code_lenses {
+        return;
+    }
+
+    let views: Vec<_> = editor
+        .tree
+        .views()
+        .map(|(view, _)| (view.doc, view.id, view.inner_height()))
+        .collect();
+    for (doc_id, view_id, height) in views {
+        let Some(doc) = editor.documents.get_mut(&doc_id) else {
+            continue;
+        };
+        let text = doc.text().slice(..);
+        let first_line = text.char_to_line(doc.view_offset(view_id).anchor.min(text.len_chars()));
+        let indices = unresolved_in_lines(&doc.code_lenses, text, first_line..first_line + height);
+
+        for idx in indices {
+            let code_lens = &mut doc.code_lenses[idx];
+            code_lens.resolve_requested = true;
+            let Some(future) = editor
+                .language_servers
+                .get_by_id(code_lens.language_server_id)
+                .and_then(|language_server| language_server.resolve_code_lens(&code_lens.lens))
+            else {
+                continue;
+            };
+            let lens = code_lens.lens.clone();
+            let language_server_id = code_lens.language_server_id;
+
+            tokio::spawn(async move {
+                let resolved = match future.await {
+                    Ok(resolved) => resolved,
+                    Err(err) => {
+                        log::error!("code lens resolve request failed: {err}");
+                        return;
+                    }
+                };
+                job::dispatch(move |editor, _| {
+                    let Some(doc) = editor.documents.get_mut(&doc_id) else {
+                        return;
+                    };
+                    // The lenses may have been requested again in the meantime.
+                    if let Some(code_lens) = doc.code_lenses.iter_mut().find(|code_lens| {
+                        code_lens.language_server_id == language_server_id && code_lens.lens == lens
+                    }) {
+                        code_lens.lens = resolved;
+                    }
+                })
+                .await;
+            });
+        }
+    }
+}
+
+/// The indices of the lenses in `lines` that have no command yet and haven't been resolved.
+fn unresolved_in_lines(
+    lenses: &[DocumentCodeLens],
+    text: RopeSlice,
+    lines: Range<usize>,
+) -> Vec<usize> {
+    lenses
+        .iter()
+        .enumerate()
+        .filter(|(_, code_lens)| {
+            code_lens.lens.command.is_none()
+                && !code_lens.resolve_requested
+                && lines.contains(&text.char_to_line(code_lens.pos.min(text.len_chars())))
+        })
+        .map(|(idx, _)| idx)
+        .collect()
+}
+
+pub(super) fn register_hooks(handlers: &Handlers) {
+    register_hook!(move |event: &mut DocumentDidOpen<'_>| {
+        request_code_lenses(event.editor, event.doc);
+        Ok(())
+    });
+
+    let tx = handlers.code_lenses.clone();
+    register_hook!(move |event: &mut DocumentDidChange<'_>| {
+        event.changes.update_positions(
+            event
+                .doc
+                .code_lenses
+                .iter_mut()
+                .map(|lens| (&mut lens.pos, Assoc::After)),
+        );
+
+        if !event.ghost_transaction {
+            event.doc.code_lens_controller.cancel();
+            helix_event::send_blocking(&tx, CodeLensEvent(event.doc.id()));
+        }
+
+        Ok(())
+    });
+
+    register_hook!(move |event: &mut LanguageServerInitialized<'_>| {
+        let doc_ids: Vec<_> = event.editor.documents().map(|doc| doc.id()).collect();
+
+        for doc_id in doc_ids {
+            request_code_lenses(event.editor, doc_id);
+        }
+
+        Ok(())
+    });
+
+    register_hook!(move |event: &mut LanguageServerExited<'_>| {
+        for doc in event.editor.documents_mut() {
+            doc.code_lenses
+                .retain(|lens| lens.language_server_id != event.server_id);
+        }
+
+        let doc_ids: Vec<_> = event.editor.documents().map(|doc| doc.id()).collect();
+
+        for doc_id in doc_ids {
+            request_code_lenses(event.editor, doc_id);
+        }
+
+        Ok(())
+    });
+}
+
+#[cfg(test)]
+mod test {
+    use helix_core::Rope;
+    use helix_lsp::{lsp, LanguageServerId};
+
+    use super::*;
+
+    fn code_lens(pos: usize, title: Option<&str>) -> DocumentCodeLens {
+        DocumentCodeLens {
+            pos,
+            lens: lsp::CodeLens {
+                range: lsp::Range::default(),
+                command: title.map(|title| lsp::Command {
+                    title: title.to_string(),
+                    command: "run".to_string(),
+                    arguments: None,
+                }),
+                data: None,
+            },
+            language_server_id: LanguageServerId::default(),
+            resolve_requested: false,
+        }
+    }
+
+    #[test]
+    fn resolve_only_visible_lenses() {
+        let text = Rope::from("a\nb\nc\nd\n");
+        let mut lenses = vec![
+            code_lens(0, None),
+            code_lens(2, Some("1 reference")),
+            code_lens(3, None),
+            code_lens(4, None),
+            code_lens(6, None),
+        ];
+        lenses[3].resolve_requested = true;
+
+        // Lines 1 and 2 are visible: of their other lenses one has a command already and one was
+        // requested before.
+        assert_eq!(unresolved_in_lines(&lenses, text.slice(..), 1..3), [2]);
+        assert_eq!(
+            unresolved_in_lines(&lenses, text.slice(..), 0..10),
+            [0, 2, 4]
+        );
+        assert!(unresolved_in_lines(&lenses, text.slice(..), 5..10).is_empty());
+    }
+}
@@ -0,0 +1,26 @@
+use helix_core::conflict::find_conflicts;
+use helix_event::register_hook;
+use helix_view::{
+    events::{DocumentDidChange, DocumentDidOpen},
+    handlers::Handlers,
+};
+
+pub(super) fn register_hooks(_handlers: &Handlers) {
+    register_hook!(move |event: &mut DocumentDidOpen<'_>| {
+        let doc = doc_mut!(event.editor, &event.doc);
+        doc.conflicts = find_conflicts(doc.text().slice(..));
+        Ok(())
+    });
+
+    register_hook!(move |event: &mut DocumentDidChange<'_>| {
+        // Conflicts only need to be found again if one could have been resolved or markers were
+        // inserted, for example when the document is reloaded after a merge.
+        let inserted_markers = event.changes.changes_iter().any(|(_, _, text)| {
+            text.is_some_and(|text| text.contains("<<<<<<<") || text.contains(">>>>>>>"))
+        });
+        if !event.doc.conflicts.is_empty() || inserted_markers {
+            event.doc.conflicts = find_conflicts(event.doc.text().slice(..));
+        }
+        Ok(())
+    });
+}
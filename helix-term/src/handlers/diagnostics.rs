@@ -1,6 +1,7 @@
 use futures_util::stream::FuturesUnordered;
 use std::collections::HashSet;
 use std::mem;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::Duration;
 use tokio::time::Instant;
 use tokio_stream::StreamExt;
@@ -22,6 +23,9 @@
 use crate::events::OnModeSwitch;
 use crate::job;
 
+/// Counter for the partial result tokens of `workspace/diagnostic` requests.
+static WORKSPACE_DIAGNOSTIC_REQUESTS: AtomicUsize = AtomicUsize::new(0);
+
 pub(super) fn register_hooks(handlers: &Handlers) {
     register_hook!(move |event: &mut DiagnosticsDidChange<'_>| {
         if event.editor.mode != Mode::Insert {
@@ -94,6 +98,7 @@ pub(super) fn register_hooks(handlers: &Handlers) {
         for doc_id in doc_ids {
             request_document_diagnostics(event.editor, doc_id);
         }
+        request_workspace_diagnostics(event.editor, event.server_id);
 
         Ok(())
     });
@@ -155,6 +160,9 @@ fn finish_debounce(&mut self) {
                     language_servers.clone(),
                 );
             }
+            for language_server in language_servers {
+                request_workspace_diagnostics(editor, language_server);
+            }
         })
     }
 }
@@ -273,6 +281,176 @@ pub fn request_all_document_diagnostics_for_language_server(
     }
 }
 
+/// Requests the diagnostics of all documents of the workspace, including documents that are not
+/// open, from a language server that supports `workspace/diagnostic`.
+///
+/// Only one request is in flight per language server. If diagnostics are requested while one is
+/// pending, another request is sent after it finished.
+pub fn request_workspace_diagnostics(editor: &mut Editor, server_id: LanguageServerId) {
+    let Some(language_server) = editor.language_servers.get_by_id(server_id) else {
+        return;
+    };
+    if !language_server.is_initialized() || !language_server.supports_workspace_diagnostics() {
+        return;
+    }
+    // Respect the language configurations that disabled pull diagnostics for this server.
+    let enabled = editor.documents().any(|doc| {
+        doc.language_servers_with_feature(LanguageServerFeature::PullDiagnostics)
+            .any(|ls| ls.id() == server_id)
+    });
+    if !enabled {
+        return;
+    }
+
+    let state = editor.workspace_diagnostics.entry(server_id).or_default();
+    if state.partial_result_token.is_some() {
+        state.refresh = true;
+        return;
+    }
+
+    let previous_result_ids = state
+        .result_ids
+        .iter()
+        .map(|(uri, result_id)| lsp::PreviousResultId {
+            uri: uri.clone(),
+            value: result_id.clone(),
+        })
+        .collect();
+    let token = lsp::ProgressToken::String(format!(
+        "helix/workspace-diagnostic/{}",
+        WORKSPACE_DIAGNOSTIC_REQUESTS.fetch_add(1, Ordering::Relaxed)
+    ));
+    let Some(future) =
+        language_server.workspace_diagnostic(previous_result_ids, Some(token.clone()))
+    else {
+        return;
+    };
+    state.partial_result_token = Some(token);
+    state.refresh = false;
+
+    tokio::spawn(async move {
+        let result = future.await;
+
+        job::dispatch(move |editor, _| {
+            let Some(state) = editor.workspace_diagnostics.get_mut(&server_id) else {
+                // The language server exited in the meantime.
+                return;
+            };
+            state.partial_result_token = None;
+            let mut refresh = mem::take(&mut state.refresh);
+
+            match result {
+                Ok(
+                    lsp::WorkspaceDiagnosticReportResult::Report(lsp::WorkspaceDiagnosticReport {
+                        items,
+                    })
+                    | lsp::WorkspaceDiagnosticReportResult::Partial(
+                        lsp::WorkspaceDiagnosticReportPartialResult { items },
+                    ),
+                ) => handle_workspace_diagnostic_reports(editor, server_id, items),
+                Err(helix_lsp::Error::Rpc(error)) => {
+                    let retrigger = error.data.and_then(|data| {
+                        serde_json::from_value::<lsp::DiagnosticServerCancellationData>(data).ok()
+                    });
+                    if retrigger.is_some_and(|data| data.retrigger_request) {
+                        refresh = true;
+                    } else {
+                        log::error!("Workspace diagnostic request failed: {}", error.message);
+                    }
+                }
+                Err(err) => log::error!("Workspace diagnostic request failed: {err}"),
+            }
+
+            if refresh {
+                request_workspace_diagnostics(editor, server_id);
+            }
+        })
+        .await;
+    });
+}
+
+/// Handles a `$/progress` notification that reports a partial result of a pending
+/// `workspace/diagnostic` request. Returns `false` if the token does not belong to one.
+pub fn handle_workspace_diagnostic_partial_result(
+    editor: &mut Editor,
+    server_id: LanguageServerId,
+    token: &lsp::ProgressToken,
+    value: serde_json::Value,
+) -> bool {
+    let is_pending = editor
+        .workspace_diagnostics
+        .get(&server_id)
+        .is_some_and(|state| state.partial_result_token.as_ref() == Some(token));
+    if !is_pending {
+        return false;
+    }
+
+    match serde_json::from_value::<lsp::WorkspaceDiagnosticReportPartialResult>(value) {
+        Ok(partial_result) => {
+            handle_workspace_diagnostic_reports(editor, server_id, partial_result.items)
+        }
+        Err(err) => log::error!("Invalid workspace diagnostic partial result: {err}"),
+    }
+    true
+}
+
+fn handle_workspace_diagnostic_reports(
+    editor: &mut Editor,
+    server_id: LanguageServerId,
+    reports: Vec<lsp::WorkspaceDocumentDiagnosticReport>,
+) {
+    let Some(language_server) = editor.language_servers.get_by_id(server_id) else {
+        return;
+    };
+    let identifier = language_server
+        .capabilities()
+        .diagnostic_provider
+        .as_ref()
+        .and_then(|diagnostic_provider| match diagnostic_provider {
+            lsp::DiagnosticServerCapabilities::Options(options) => options.identifier.clone(),
+            lsp::DiagnosticServerCapabilities::RegistrationOptions(options) => {
+                options.diagnostic_options.identifier.clone()
+            }
+        });
+    let provider = DiagnosticProvider::Lsp {
+        server_id,
+        identifier,
+    };
+
+    for report in reports {
+        let (url, result_id) = match report {
+            lsp::WorkspaceDocumentDiagnosticReport::Full(report) => {
+                let url = report.uri;
+                let result_id = report.full_document_diagnostic_report.result_id;
+                match Uri::try_from(url.clone()) {
+                    Ok(uri) => editor.handle_lsp_diagnostics(
+                        &provider,
+                        uri,
+                        report.version.and_then(|version| version.try_into().ok()),
+                        report.full_document_diagnostic_report.items,
+                    ),
+                    Err(err) => log::error!("{err}"),
+                }
+                (url, result_id)
+            }
+            lsp::WorkspaceDocumentDiagnosticReport::Unchanged(report) => (
+                report.uri,
+                Some(report.unchanged_document_diagnostic_report.result_id),
+            ),
+        };
+
+        let state = editor.workspace_diagnostics.entry(server_id).or_default();
+        match result_id {
+            Some(result_id) => {
+                state.result_ids.insert(url, result_id);
+            }
+            None => {
+                state.result_ids.remove(&url);
+            }
+        }
+    }
+}
+
 pub fn request_document_diagnostics(editor: &mut Editor, doc_id: DocumentId) {
     let Some(doc) = editor.document(doc_id) else {
         return;
@@ -0,0 +1,177 @@
+use std::{collections::HashSet, time::Duration};
+
+use futures_util::{stream::FuturesUnordered, StreamExt};
+use helix_core::{
+    fold::{self, Fold},
+    syntax::config::LanguageServerFeature,
+};
+use helix_event::{cancelable_future, register_hook};
+use helix_view::{
+    events::{
+        DocumentDidChange, DocumentDidOpen, LanguageServerExited, LanguageServerInitialized,
+        SelectionDidChange,
+    },
+    handlers::{lsp::FoldingRangesEvent, Handlers},
+    DocumentId, Editor,
+};
+use tokio::time::Instant;
+
+use crate::job;
+
+#[derive(Default)]
+pub(super) struct FoldingRangesHandler {
+    docs: HashSet<DocumentId>,
+}
+
+const DOCUMENT_CHANGE_DEBOUNCE: Duration = Duration::from_millis(250);
+
+impl helix_event::AsyncHook for FoldingRangesHandler {
+    type Event = FoldingRangesEvent;
+
+    fn handle_event(&mut self, event: Self::Event, _timeout: Option<Instant>) -> Option<Instant> {
+        let FoldingRangesEvent(doc_id) = event;
+        self.docs.insert(doc_id);
+        Some(Instant::now() + DOCUMENT_CHANGE_DEBOUNCE)
+    }
+
+    fn finish_debounce(&mut self) {
+        let docs = std::mem::take(&mut self.docs);
+
+        job::dispatch_blocking(move |editor, _compositor| {
+            for doc in docs {
+                request_folding_ranges(editor, doc);
+            }
+        });
+    }
+}
+
+/// Request folding ranges for a specific document and cache them for the fold commands.
+fn request_folding_ranges(editor: &mut Editor, doc_id: DocumentId) {
+    let Some(doc) = editor.document_mut(doc_id) else {
+        return;
+    };
+
+    let cancel = doc.folding_range_controller.restart();
+
+    let mut seen_language_servers = HashSet::new();
+    let mut futures: FuturesUnordered<_> = doc
+        .language_servers_with_feature(LanguageServerFeature::FoldingRanges)
+        .filter(|ls| seen_language_servers.insert(ls.id()))
+        .filter_map(|language_server| {
+            let text = doc.text().clone();
+            let future = language_server.text_document_folding_range(doc.identifier(), None)?;
+
+            Some(async move {
+                let ranges = future.await?.unwrap_or_default();
+                let folds: Vec<_> = ranges
+                    .into_iter()
+                    .filter_map(|range| {
+                        Fold::from_lines(
+                            text.slice(..),
+                            range.start_line as usize,
+                            range.end_line as usize,
+                        )
+                    })
+                    .collect();
+                anyhow::Ok(folds)
+            })
+        })
+        .collect();
+
+    if futures.is_empty() {
+        return;
+    }
+
+    tokio::spawn(async move {
+        let mut all_folds = Vec::new();
+        loop {
+            match cancelable_future(futures.next(), &cancel).await {
+                Some(Some(Ok(items))) => all_folds.extend(items),
+                Some(Some(Err(err))) => log::error!("folding range request failed: {err}"),
+                Some(None) => break,
+                None => return,
+            }
+        }
+
+        job::dispatch(move |editor, _| attach_folding_ranges(editor, doc_id, all_folds)).await;
+    });
+}
+
+fn attach_folding_ranges(editor: &mut Editor, doc_id: DocumentId, mut folds: Vec<Fold>) {
+    let Some(doc) = editor.documents.get_mut(&doc_id) else {
+        return;
+    };
+
+    fold::sort(&mut folds);
+    doc.folding_ranges = folds;
+}
+
+pub(super) fn register_hooks(handlers: &Handlers) {
+    register_hook!(move |event: &mut DocumentDidOpen<'_>| {
+        request_folding_ranges(event.editor, event.doc);
+        Ok(())
+    });
+
+    let tx = handlers.folding_ranges.clone();
+    register_hook!(move |event: &mut DocumentDidChange<'_>| {
+        // The cached ranges are mapped through the changes by the document itself.
+        if !event.ghost_transaction {
+            event.doc.folding_range_controller.cancel();
+            helix_event::send_blocking(&tx, FoldingRangesEvent(event.doc.id()));
+        }
+
+        Ok(())
+    });
+
+    // Open any closed fold that would hide a cursor so that the cursor always stays visible.
+    register_hook!(move |event: &mut SelectionDidChange<'_>| {
+        let doc = &mut *event.doc;
+        let folds = doc.folds(event.view);
+        if folds.is_empty() {
+            return Ok(());
+        }
+
+        let text = doc.text().slice(..);
+        let cursors: Vec<_> = doc
+            .selection(event.view)
+            .iter()
+            .map(|range| range.cursor(text))
+            .collect();
+        let still_closed: Vec<_> = folds
+            .iter()
+            .filter(|fold| !cursors.iter().any(|&cursor| fold.hides(cursor)))
+            .copied()
+            .collect();
+        if still_closed.len() != folds.len() {
+            doc.set_folds(event.view, still_closed);
+        }
+
+        Ok(())
+    });
+
+    register_hook!(move |event: &mut LanguageServerInitialized<'_>| {
+        let doc_ids: Vec<_> = event.editor.documents().map(|doc| doc.id()).collect();
+
+        for doc_id in doc_ids {
+            request_folding_ranges(event.editor, doc_id);
+        }
+
+        Ok(())
+    });
+
+    register_hook!(move |event: &mut LanguageServerExited<'_>| {
+        for doc in event.editor.documents_mut() {
+            if doc.supports_language_server(event.server_id) {
+                doc.folding_ranges.clear();
+            }
+        }
+
+        let doc_ids: Vec<_> = event.editor.documents().map(|doc| doc.id()).collect();
+
+        for doc_id in doc_ids {
+            request_folding_ranges(event.editor, doc_id);
+        }
+
+        Ok(())
+    });
+}
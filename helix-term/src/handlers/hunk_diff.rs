@@ -0,0 +1,24 @@
+use helix_event::register_hook;
+use helix_view::{events::SelectionDidChange, handlers::Handlers};
+
+pub(super) fn register_hooks(_handlers: &Handlers) {
+    // Hide the base lines shown with `show_hunk_diff` once the cursor leaves their change.
+    register_hook!(move |event: &mut SelectionDidChange<'_>| {
+        let Some((view_id, idx)) = event.doc.hunk_diff else {
+            return Ok(());
+        };
+        if view_id != event.view {
+            return Ok(());
+        }
+        let text = event.doc.text().slice(..);
+        let line = event.doc.selection(view_id).primary().cursor_line(text);
+        let hunk = event
+            .doc
+            .diff_handle()
+            .and_then(|handle| handle.load().hunk_at(line as u32, true));
+        if hunk != Some(idx) {
+            event.doc.hunk_diff = None;
+        }
+        Ok(())
+    });
+}
@@ -0,0 +1,247 @@
+use std::time::Duration;
+
+use helix_core::syntax::config::LanguageServerFeature;
+use helix_event::{cancelable_future, register_hook, send_blocking};
+use helix_lsp::{
+    lsp::{self, InlineCompletionTriggerKind},
+    util::lsp_range_to_range,
+};
+use helix_view::{
+    document::{DocumentInlineCompletion, InlineCompletionCandidate, Mode},
+    events::{DocumentDidChange, LanguageServerExited, SelectionDidChange},
+    handlers::{lsp::InlineCompletionEvent, Handlers},
+    DocumentId, Editor, ViewId,
+};
+use tokio::time::Instant;
+
+use crate::{events::OnModeSwitch, job};
+
+#[derive(Default)]
+pub(super) struct InlineCompletionHandler {
+    trigger: Option<(DocumentId, ViewId, InlineCompletionTriggerKind)>,
+}
+
+const DOCUMENT_CHANGE_DEBOUNCE: Duration = Duration::from_millis(150);
+
+impl helix_event::AsyncHook for InlineCompletionHandler {
+    type Event = InlineCompletionEvent;
+
+    fn handle_event(&mut self, event: Self::Event, timeout: Option<Instant>) -> Option<Instant> {
+        match event {
+            InlineCompletionEvent::Trigger {
+                document_id,
+                view_id,
+            } => {
+                // Don't downgrade a pending manual request.
+                if self.trigger.is_some_and(|(doc, view, kind)| {
+                    (doc, view) == (document_id, view_id)
+                        && kind == InlineCompletionTriggerKind::Invoked
+                }) {
+                    return timeout;
+                }
+                self.trigger = Some((document_id, view_id, InlineCompletionTriggerKind::Automatic));
+                Some(Instant::now() + DOCUMENT_CHANGE_DEBOUNCE)
+            }
+            InlineCompletionEvent::Invoked {
+                document_id,
+                view_id,
+            } => {
+                self.trigger = Some((document_id, view_id, InlineCompletionTriggerKind::Invoked));
+                Some(Instant::now())
+            }
+        }
+    }
+
+    fn finish_debounce(&mut self) {
+        let Some((doc_id, view_id, trigger_kind)) = self.trigger.take() else {
+            return;
+        };
+
+        job::dispatch_blocking(move |editor, _compositor| {
+            request_inline_completion(editor, doc_id, view_id, trigger_kind);
+        });
+    }
+}
+
+/// Requests inline completions for the cursor of a view. Only a single cursor is supported as
+/// the candidates are specific to the text around it.
+fn request_inline_completion(
+    editor: &mut Editor,
+    doc_id: DocumentId,
+    view_id: ViewId,
+    trigger_kind: InlineCompletionTriggerKind,
+) {
+    if editor.mode != Mode::Insert
+        || (trigger_kind == InlineCompletionTriggerKind::Automatic
+            && !editor.config().lsp.inline_completion)
+    {
+        return;
+    }
+    let Some(doc) = editor.documents.get_mut(&doc_id) else {
+        return;
+    };
+    let Some(selection) = doc.selections().get(&view_id).cloned() else {
+        return;
+    };
+    if selection.len() != 1 {
+        return;
+    }
+
+    let cancel = doc.inline_completion_controller.restart();
+
+    let Some(language_server) = doc
+        .language_servers_with_feature(LanguageServerFeature::InlineCompletion)
+        .next()
+    else {
+        return;
+    };
+
+    let language_server_id = language_server.id();
+    let offset_encoding = language_server.offset_encoding();
+    let pos = doc.position(view_id, offset_encoding);
+    let context = lsp::InlineCompletionContext {
+        trigger_kind,
+        selected_completion_info: None,
+    };
+    let Some(future) =
+        language_server.text_document_inline_completion(doc.identifier(), pos, context, None)
+    else {
+        return;
+    };
+    let version = doc.version();
+    // The previously displayed candidate is skipped when looking for more candidates.
+    let displayed = doc
+        .inline_completion
+        .as_ref()
+        .filter(|_| trigger_kind == InlineCompletionTriggerKind::Invoked)
+        .map(|inline_completion| inline_completion.candidate().insert_text.clone());
+
+    tokio::spawn(async move {
+        let response = match cancelable_future(future, &cancel).await {
+            Some(Ok(Some(response))) => response,
+            Some(Ok(None)) | None => return,
+            Some(Err(err)) => {
+                log::error!("inline completion request failed: {err}");
+                return;
+            }
+        };
+
+        job::dispatch(move |editor, _| {
+            if editor.mode != Mode::Insert {
+                return;
+            }
+            let Some(doc) = editor.documents.get_mut(&doc_id) else {
+                return;
+            };
+            // The response is outdated if the cursor moved or the document changed since.
+            if doc.version() != version || doc.selections().get(&view_id) != Some(&selection) {
+                return;
+            }
+
+            let items = match response {
+                lsp::InlineCompletionResponse::Array(items) => items,
+                lsp::InlineCompletionResponse::List(list) => list.items,
+            };
+            let text = doc.text();
+            let cursor = selection.primary().cursor(text.slice(..));
+            let candidates: Vec<_> = items
+                .into_iter()
+                // Snippets are not supported: their text is not what is inserted.
+                .filter(|item| item.insert_text_format != Some(lsp::InsertTextFormat::SNIPPET))
+                .filter_map(|item| {
+                    let range = match item.range {
+                        Some(range) => {
+                            let range = lsp_range_to_range(text, range, offset_encoding)?;
+                            range.from()..range.to()
+                        }
+                        None => cursor..cursor,
+                    };
+                    Some(InlineCompletionCandidate {
+                        range,
+                        insert_text: item.insert_text,
+                        command: item.command,
+                    })
+                })
+                .collect();
+
+            let index = candidates
+                .iter()
+                .position(|candidate| Some(&candidate.insert_text) == displayed.as_ref())
+                .map_or(0, |idx| (idx + 1) % candidates.len());
+            doc.inline_completion =
+                DocumentInlineCompletion::new(view_id, language_server_id, candidates).map(
+                    |mut inline_completion| {
+                        inline_completion.index = index;
+                        inline_completion
+                    },
+                );
+            doc.update_inline_completion_ghost_text();
+        })
+        .await;
+    });
+}
+
+pub(super) fn register_hooks(handlers: &Handlers) {
+    let tx = handlers.inline_completion.clone();
+    register_hook!(move |event: &mut OnModeSwitch<'_, '_>| {
+        match (event.old_mode, event.new_mode) {
+            (Mode::Insert, _) => {
+                for doc in event.cx.editor.documents_mut() {
+                    doc.clear_inline_completion();
+                }
+            }
+            (_, Mode::Insert) => {
+                let (view, doc) = current_ref!(event.cx.editor);
+                send_blocking(
+                    &tx,
+                    InlineCompletionEvent::Trigger {
+                        document_id: doc.id(),
+                        view_id: view.id,
+                    },
+                );
+            }
+            _ => (),
+        }
+        Ok(())
+    });
+
+    let tx = handlers.inline_completion.clone();
+    register_hook!(move |event: &mut DocumentDidChange<'_>| {
+        if let Some(inline_completion) = &mut event.doc.inline_completion {
+            inline_completion.map(event.changes);
+        }
+        event.doc.update_inline_completion_ghost_text();
+
+        if !event.ghost_transaction && event.doc.config.load().lsp.inline_completion {
+            event.doc.inline_completion_controller.cancel();
+            send_blocking(
+                &tx,
+                InlineCompletionEvent::Trigger {
+                    document_id: event.doc.id(),
+                    view_id: event.view,
+                },
+            );
+        }
+        Ok(())
+    });
+
+    register_hook!(move |event: &mut SelectionDidChange<'_>| {
+        event.doc.update_inline_completion_ghost_text();
+        Ok(())
+    });
+
+    register_hook!(move |event: &mut LanguageServerExited<'_>| {
+        for doc in event.editor.documents_mut() {
+            if doc
+                .inline_completion
+                .as_ref()
+                .is_some_and(|inline_completion| {
+                    inline_completion.language_server_id == event.server_id
+                })
+            {
+                doc.clear_inline_completion();
+            }
+        }
+        Ok(())
+    });
+}
@@ -0,0 +1,50 @@
+use std::time::Duration;
+
+use helix_event::{cancelable_future, TaskController};
+use helix_lsp::block_on;
+use helix_view::handlers::lsp::InlineValuesEvent;
+use tokio::time::Instant;
+
+use crate::job;
+
+/// Requests the inline values of the active stack frame in the background, so that a slow
+/// language server doesn't block stepping through the program or switching stack frames.
+#[derive(Default)]
+pub(super) struct InlineValuesHandler {
+    task_controller: TaskController,
+}
+
+/// Switching stack frames quickly only requests the values of the last one.
+const FRAME_CHANGE_DEBOUNCE: Duration = Duration::from_millis(50);
+
+impl helix_event::AsyncHook for InlineValuesHandler {
+    type Event = InlineValuesEvent;
+
+    fn handle_event(&mut self, _event: Self::Event, _timeout: Option<Instant>) -> Option<Instant> {
+        Some(Instant::now() + FRAME_CHANGE_DEBOUNCE)
+    }
+
+    fn finish_debounce(&mut self) {
+        let cancel = self.task_controller.restart();
+
+        job::dispatch_blocking(move |editor, _compositor| {
+            let Some((request, future)) = editor.request_inline_values() else {
+                return;
+            };
+            tokio::spawn(async move {
+                let lsp_values = match future {
+                    Some(future) => match cancelable_future(future, &cancel).await {
+                        Some(lsp_values) => lsp_values,
+                        None => return,
+                    },
+                    None => None,
+                };
+                job::dispatch(move |editor, _compositor| {
+                    // Looking up the values only waits for the debugger.
+                    block_on(editor.apply_inline_values(request, lsp_values));
+                })
+                .await;
+            });
+        });
+    }
+}
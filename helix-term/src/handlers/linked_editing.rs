@@ -0,0 +1,189 @@
+use std::{collections::HashSet, time::Duration};
+
+use helix_core::{linked_editing, syntax::config::LanguageServerFeature, Assoc};
+use helix_event::{cancelable_future, register_hook, send_blocking};
+use helix_lsp::util::lsp_range_to_range;
+use helix_view::{
+    document::{LinkedEditing, Mode},
+    events::{DocumentDidChange, SelectionDidChange},
+    handlers::{lsp::LinkedEditingEvent, Handlers},
+    Document, DocumentId, Editor, ViewId,
+};
+use tokio::time::Instant;
+
+use crate::{events::OnModeSwitch, job};
+
+#[derive(Default)]
+pub(super) struct LinkedEditingHandler {
+    views: HashSet<(DocumentId, ViewId)>,
+}
+
+const SELECTION_CHANGE_DEBOUNCE: Duration = Duration::from_millis(50);
+
+impl helix_event::AsyncHook for LinkedEditingHandler {
+    type Event = LinkedEditingEvent;
+
+    fn handle_event(&mut self, event: Self::Event, _timeout: Option<Instant>) -> Option<Instant> {
+        self.views.insert((event.document_id, event.view_id));
+        Some(Instant::now() + SELECTION_CHANGE_DEBOUNCE)
+    }
+
+    fn finish_debounce(&mut self) {
+        let views = std::mem::take(&mut self.views);
+
+        job::dispatch_blocking(move |editor, _compositor| {
+            for (doc_id, view_id) in views {
+                update_linked_editing(editor, doc_id, view_id);
+            }
+        });
+    }
+}
+
+/// Links the cursor of a view to the linked ranges it is in, or ends linked editing if the
+/// cursors left the linked ranges.
+fn update_linked_editing(editor: &mut Editor, doc_id: DocumentId, view_id: ViewId) {
+    if editor.mode != Mode::Insert || !editor.config().linked_editing {
+        return;
+    }
+    let Some(doc) = editor.documents.get_mut(&doc_id) else {
+        return;
+    };
+    let Some(selection) = doc.selections().get(&view_id) else {
+        return;
+    };
+
+    if let Some(linked_editing) = &doc.linked_editing {
+        if linked_editing.view_id == view_id
+            && linked_editing::is_linked(doc.text().slice(..), selection, &linked_editing.ranges)
+        {
+            return;
+        }
+        doc.stop_linked_editing();
+    }
+
+    let selection = doc.selection(view_id).clone();
+    if selection.len() != 1 {
+        return;
+    }
+
+    let cancel = doc.linked_editing_controller.restart();
+
+    let Some(language_server) = doc
+        .language_servers_with_feature(LanguageServerFeature::LinkedEditingRange)
+        .next()
+    else {
+        // Fall back to the tag names of the syntax tree if no language server supports it.
+        let text = doc.text().slice(..);
+        let ranges = doc.syntax().and_then(|syntax| {
+            linked_editing::tag_name_ranges(syntax, text, selection.primary().cursor(text))
+        });
+        if let Some(ranges) = ranges {
+            link_selection(doc, view_id, ranges);
+        }
+        return;
+    };
+
+    let offset_encoding = language_server.offset_encoding();
+    let pos = doc.position(view_id, offset_encoding);
+    let Some(future) =
+        language_server.text_document_linked_editing_range(doc.identifier(), pos, None)
+    else {
+        return;
+    };
+    let version = doc.version();
+
+    tokio::spawn(async move {
+        let response = match cancelable_future(future, &cancel).await {
+            Some(Ok(Some(response))) => response,
+            Some(Ok(None)) | None => return,
+            Some(Err(err)) => {
+                log::error!("linked editing range request failed: {err}");
+                return;
+            }
+        };
+
+        job::dispatch(move |editor, _| {
+            if editor.mode != Mode::Insert {
+                return;
+            }
+            let Some(doc) = editor.documents.get_mut(&doc_id) else {
+                return;
+            };
+            // The response is outdated if the cursor moved or the document changed since.
+            if doc.version() != version || doc.selections().get(&view_id) != Some(&selection) {
+                return;
+            }
+
+            let ranges = response
+                .ranges
+                .into_iter()
+                .filter_map(|range| lsp_range_to_range(doc.text(), range, offset_encoding))
+                .map(|range| range.from()..range.to())
+                .collect();
+            link_selection(doc, view_id, ranges);
+        })
+        .await;
+    });
+}
+
+fn link_selection(doc: &mut Document, view_id: ViewId, ranges: Vec<std::ops::Range<usize>>) {
+    let text = doc.text().slice(..);
+    let Some(selection) = linked_editing::linked_selection(text, doc.selection(view_id), &ranges)
+    else {
+        return;
+    };
+    doc.linked_editing = Some(LinkedEditing { view_id, ranges });
+    doc.set_selection(view_id, selection);
+}
+
+pub(super) fn register_hooks(handlers: &Handlers) {
+    let tx = handlers.linked_editing.clone();
+    register_hook!(move |event: &mut OnModeSwitch<'_, '_>| {
+        match (event.old_mode, event.new_mode) {
+            (Mode::Insert, _) => {
+                for doc in event.cx.editor.documents_mut() {
+                    doc.stop_linked_editing();
+                }
+            }
+            (_, Mode::Insert) => {
+                let (view, doc) = current_ref!(event.cx.editor);
+                send_blocking(
+                    &tx,
+                    LinkedEditingEvent {
+                        document_id: doc.id(),
+                        view_id: view.id,
+                    },
+                );
+            }
+            _ => (),
+        }
+        Ok(())
+    });
+
+    let tx = handlers.linked_editing.clone();
+    register_hook!(move |event: &mut SelectionDidChange<'_>| {
+        if event.doc.config.load().linked_editing {
+            send_blocking(
+                &tx,
+                LinkedEditingEvent {
+                    document_id: event.doc.id(),
+                    view_id: event.view,
+                },
+            );
+        }
+        Ok(())
+    });
+
+    register_hook!(move |event: &mut DocumentDidChange<'_>| {
+        // Linked ranges grow when text is inserted at their boundaries.
+        if let Some(linked_editing) = &mut event.doc.linked_editing {
+            event
+                .changes
+                .update_positions(linked_editing.ranges.iter_mut().flat_map(|range| {
+                    std::iter::once((&mut range.start, Assoc::Before))
+                        .chain(std::iter::once((&mut range.end, Assoc::After)))
+                }));
+        }
+        Ok(())
+    });
+}
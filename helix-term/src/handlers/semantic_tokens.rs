@@ -0,0 +1,369 @@
+use std::{cmp::Reverse, collections::HashSet, time::Duration};
+
+use futures_util::FutureExt;
+use helix_core::{syntax::config::LanguageServerFeature, Assoc, Rope};
+use helix_event::{cancelable_future, register_hook};
+use helix_lsp::{lsp, LanguageServerId, OffsetEncoding};
+use helix_view::{
+    document::{DocumentSemanticTokens, SemanticToken},
+    events::{DocumentDidChange, DocumentDidOpen, LanguageServerExited, LanguageServerInitialized},
+    handlers::{lsp::SemanticTokensEvent, Handlers},
+    DocumentId, Editor,
+};
+use tokio::time::Instant;
+
+use crate::job;
+
+#[derive(Default)]
+pub(super) struct SemanticTokensHandler {
+    docs: HashSet<DocumentId>,
+}
+
+const DOCUMENT_CHANGE_DEBOUNCE: Duration = Duration::from_millis(250);
+
+impl helix_event::AsyncHook for SemanticTokensHandler {
+    type Event = SemanticTokensEvent;
+
+    fn handle_event(&mut self, event: Self::Event, _timeout: Option<Instant>) -> Option<Instant> {
+        let SemanticTokensEvent(doc_id) = event;
+        self.docs.insert(doc_id);
+        Some(Instant::now() + DOCUMENT_CHANGE_DEBOUNCE)
+    }
+
+    fn finish_debounce(&mut self) {
+        let docs = std::mem::take(&mut self.docs);
+
+        job::dispatch_blocking(move |editor, _compositor| {
+            for doc in docs {
+                request_semantic_tokens(editor, doc);
+            }
+        });
+    }
+}
+
+/// Request semantic tokens for a document. A delta to the previous response is requested if the
+/// language server supports it.
+fn request_semantic_tokens(editor: &mut Editor, doc_id: DocumentId) {
+    if !editor.config().lsp.display_semantic_tokens {
+        return;
+    }
+
+    let Some(doc) = editor.document_mut(doc_id) else {
+        return;
+    };
+
+    let cancel = doc.semantic_tokens_controller.restart();
+
+    // Tokens from multiple language servers can't be merged in a meaningful way so only the
+    // first language server with semantic tokens support is used.
+    let Some(language_server) = doc
+        .language_servers_with_feature(LanguageServerFeature::SemanticTokens)
+        .next()
+    else {
+        return;
+    };
+    let Some(legend) = language_server.semantic_tokens_legend() else {
+        return;
+    };
+    let type_scopes: Vec<_> = legend
+        .token_types
+        .iter()
+        .map(|token_type| format!("lsp.type.{}", token_type.as_str()))
+        .collect();
+    let modifier_scopes: Vec<_> = legend
+        .token_modifiers
+        .iter()
+        .map(|modifier| format!("lsp.mod.{}", modifier.as_str()))
+        .collect();
+    let language_server_id = language_server.id();
+    let offset_encoding = language_server.offset_encoding();
+
+    let previous_result_id = doc
+        .semantic_tokens
+        .as_ref()
+        .filter(|tokens| tokens.language_server_id == language_server_id)
+        .and_then(|tokens| tokens.result_id.clone());
+    let future = match previous_result_id.and_then(|result_id| {
+        language_server.text_document_semantic_tokens_full_delta(doc.identifier(), result_id, None)
+    }) {
+        Some(future) => future.boxed(),
+        None => {
+            let Some(future) =
+                language_server.text_document_semantic_tokens_full(doc.identifier(), None)
+            else {
+                return;
+            };
+            async move {
+                let response = future.await?.map(|response| match response {
+                    lsp::SemanticTokensResult::Tokens(tokens) => {
+                        lsp::SemanticTokensFullDeltaResult::Tokens(tokens)
+                    }
+                    lsp::SemanticTokensResult::Partial(partial) => {
+                        lsp::SemanticTokensFullDeltaResult::Tokens(lsp::SemanticTokens {
+                            result_id: None,
+                            data: partial.data,
+                        })
+                    }
+                });
+                Ok(response)
+            }
+            .boxed()
+        }
+    };
+
+    tokio::spawn(async move {
+        let response = match cancelable_future(future, &cancel).await {
+            Some(Ok(response)) => response,
+            Some(Err(err)) => {
+                log::error!("semantic tokens request failed: {err}");
+                return;
+            }
+            None => return,
+        };
+
+        job::dispatch(move |editor, _| {
+            let Some(doc) = editor.documents.get_mut(&doc_id) else {
+                return;
+            };
+            // Deltas refer to the previous response of the same server. The current tokens are
+            // only taken once it's clear that they are replaced.
+            let mut take_previous = || {
+                doc.semantic_tokens
+                    .take_if(|tokens| tokens.language_server_id == language_server_id)
+            };
+            let (result_id, data) = match response {
+                None => return,
+                Some(lsp::SemanticTokensFullDeltaResult::Tokens(tokens)) => {
+                    (tokens.result_id, tokens.data)
+                }
+                Some(lsp::SemanticTokensFullDeltaResult::TokensDelta(delta)) => {
+                    let Some(previous) = take_previous() else {
+                        return;
+                    };
+                    (delta.result_id, apply_edits(previous.data, delta.edits))
+                }
+                Some(lsp::SemanticTokensFullDeltaResult::PartialTokensDelta { edits }) => {
+                    let Some(previous) = take_previous() else {
+                        return;
+                    };
+                    (None, apply_edits(previous.data, edits))
+                }
+            };
+
+            let tokens = decode_tokens(doc.text(), &data, offset_encoding);
+            doc.semantic_tokens = Some(DocumentSemanticTokens {
+                language_server_id,
+                result_id,
+                data,
+                type_scopes,
+                modifier_scopes,
+                tokens,
+            });
+        })
+        .await;
+    });
+}
+
+/// Applies the edits of a delta response to the tokens of the previous response.
+fn apply_edits(
+    mut data: Vec<lsp::SemanticToken>,
+    mut edits: Vec<lsp::SemanticTokensEdit>,
+) -> Vec<lsp::SemanticToken> {
+    // Edits refer to the previous response, applying them back to front keeps indices valid.
+    // `start` and `delete_count` count integers, each token is encoded as five integers.
+    edits.sort_unstable_by_key(|edit| Reverse(edit.start));
+    for edit in edits {
+        let start = (edit.start as usize / 5).min(data.len());
+        let end = (start + edit.delete_count as usize / 5).min(data.len());
+        data.splice(start..end, edit.data.unwrap_or_default());
+    }
+    data
+}
+
+/// Decodes the relative LSP token encoding into char ranges of `text`. Tokens that overlap a
+/// previous token or are out of bounds are dropped.
+fn decode_tokens(
+    text: &Rope,
+    data: &[lsp::SemanticToken],
+    offset_encoding: OffsetEncoding,
+) -> Vec<SemanticToken> {
+    let mut tokens: Vec<SemanticToken> = Vec::with_capacity(data.len());
+    let mut line = 0;
+    let mut character = 0;
+    for token in data {
+        if token.delta_line != 0 {
+            line += token.delta_line;
+            character = 0;
+        }
+        character += token.delta_start;
+
+        let start = lsp::Position::new(line, character);
+        let end = lsp::Position::new(line, character + token.length);
+        let (Some(start), Some(end)) = (
+            helix_lsp::util::lsp_pos_to_pos(text, start, offset_encoding),
+            helix_lsp::util::lsp_pos_to_pos(text, end, offset_encoding),
+        ) else {
+            continue;
+        };
+        if start >= end || tokens.last().is_some_and(|last| start < last.end) {
+            continue;
+        }
+
+        tokens.push(SemanticToken {
+            start,
+            end,
+            token_type: token.token_type,
+            modifiers: token.token_modifiers_bitset,
+        });
+    }
+    tokens
+}
+
+fn clear_semantic_tokens(editor: &mut Editor, server_id: LanguageServerId) {
+    for doc in editor.documents_mut() {
+        if doc
+            .semantic_tokens
+            .as_ref()
+            .is_some_and(|tokens| tokens.language_server_id == server_id)
+        {
+            doc.semantic_tokens = None;
+        }
+    }
+}
+
+pub(super) fn register_hooks(handlers: &Handlers) {
+    register_hook!(move |event: &mut DocumentDidOpen<'_>| {
+        request_semantic_tokens(event.editor, event.doc);
+        Ok(())
+    });
+
+    let tx = handlers.semantic_tokens.clone();
+    register_hook!(move |event: &mut DocumentDidChange<'_>| {
+        if let Some(semantic_tokens) = &mut event.doc.semantic_tokens {
+            event
+                .changes
+                .update_positions(semantic_tokens.tokens.iter_mut().flat_map(|token| {
+                    std::iter::once((&mut token.start, Assoc::After))
+                        .chain(std::iter::once((&mut token.end, Assoc::Before)))
+                }));
+        }
+
+        if !event.ghost_transaction {
+            event.doc.semantic_tokens_controller.cancel();
+            helix_event::send_blocking(&tx, SemanticTokensEvent(event.doc.id()));
+        }
+
+        Ok(())
+    });
+
+    register_hook!(move |event: &mut LanguageServerInitialized<'_>| {
+        let doc_ids: Vec<_> = event.editor.documents().map(|doc| doc.id()).collect();
+
+        for doc_id in doc_ids {
+            request_semantic_tokens(event.editor, doc_id);
+        }
+
+        Ok(())
+    });
+
+    register_hook!(move |event: &mut LanguageServerExited<'_>| {
+        clear_semantic_tokens(event.editor, event.server_id);
+
+        let doc_ids: Vec<_> = event.editor.documents().map(|doc| doc.id()).collect();
+
+        for doc_id in doc_ids {
+            request_semantic_tokens(event.editor, doc_id);
+        }
+
+        Ok(())
+    });
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn token(
+        delta_line: u32,
+        delta_start: u32,
+        length: u32,
+        token_type: u32,
+    ) -> lsp::SemanticToken {
+        lsp::SemanticToken {
+            delta_line,
+            delta_start,
+            length,
+            token_type,
+            token_modifiers_bitset: 0,
+        }
+    }
+
+    fn edit(
+        start: u32,
+        delete_count: u32,
+        data: Option<Vec<lsp::SemanticToken>>,
+    ) -> lsp::SemanticTokensEdit {
+        lsp::SemanticTokensEdit {
+            start,
+            delete_count,
+            data,
+        }
+    }
+
+    #[test]
+    fn apply_delta_edits() {
+        let data: Vec<_> = (0..4).map(|i| token(1, 0, 1, i)).collect();
+        // The edits are given out of order and refer to the indices of the previous response.
+        let edits = vec![
+            edit(20, 0, Some(vec![token(1, 0, 1, 10)])),
+            edit(0, 5, None),
+            edit(5, 5, Some(vec![token(1, 0, 1, 11), token(1, 0, 1, 12)])),
+        ];
+        let types: Vec<_> = apply_edits(data, edits)
+            .iter()
+            .map(|token| token.token_type)
+            .collect();
+        assert_eq!(types, [11, 12, 2, 3, 10]);
+
+        // Edits past the end of the previous response are clamped to it.
+        let data = vec![token(0, 0, 1, 0)];
+        let types: Vec<_> = apply_edits(data, vec![edit(50, 10, Some(vec![token(0, 0, 1, 1)]))])
+            .iter()
+            .map(|token| token.token_type)
+            .collect();
+        assert_eq!(types, [0, 1]);
+    }
+
+    #[test]
+    fn decode_relative_positions() {
+        let text = Rope::from("fn main() {\n    let x = 1;\n}\n");
+        let data = [
+            token(0, 0, 2, 0),
+            token(0, 3, 4, 1),
+            // A new line resets the start character.
+            token(1, 4, 3, 0),
+            token(0, 4, 1, 2),
+            // Overlaps the previous token.
+            token(0, 0, 1, 3),
+            // Out of bounds.
+            token(5, 0, 1, 3),
+        ];
+        let ranges: Vec<_> = decode_tokens(&text, &data, OffsetEncoding::Utf8)
+            .iter()
+            .map(|token| (token.start, token.end, token.token_type))
+            .collect();
+        assert_eq!(ranges, [(0, 2, 0), (3, 7, 1), (16, 19, 0), (20, 21, 2)]);
+    }
+
+    #[test]
+    fn decode_utf16_positions() {
+        // The emoji is two UTF-16 code units but a single char.
+        let text = Rope::from("ä😀b c\n");
+        let data = [token(0, 3, 1, 0), token(0, 2, 1, 1)];
+        let ranges: Vec<_> = decode_tokens(&text, &data, OffsetEncoding::Utf16)
+            .iter()
+            .map(|token| (token.start, token.end))
+            .collect();
+        assert_eq!(ranges, [(2, 3), (4, 5)]);
+    }
+}
@@ -261,8 +261,14 @@ pub enum KeymapResult {
 /// A map of command names to keybinds that will execute the command.
 pub type ReverseKeymap = HashMap<String, Vec<Vec<KeyEvent>>>;
 
+pub type LanguageKeymaps = HashMap<String, HashMap<Mode, KeyTrie>>;
+
 pub struct Keymaps {
     pub map: Box<dyn DynAccess<HashMap<Mode, KeyTrie>>>,
+    /// The keymaps used instead of `map` while a document of the language is focused.
+    language_maps: Option<Box<dyn DynAccess<LanguageKeymaps>>>,
+    /// The language of the focused document.
+    language: Option<String>,
     /// Stores pending keys waiting for the next key. This is relative to a
     /// sticky node if one is in use.
     state: Vec<KeyEvent>,
@@ -274,15 +280,60 @@ impl Keymaps {
     pub fn new(map: Box<dyn DynAccess<HashMap<Mode, KeyTrie>>>) -> Self {
         Self {
             map,
+            language_maps: None,
+            language: None,
             state: Vec::new(),
             sticky: None,
         }
     }
 
+    pub fn with_language_maps(
+        mut self,
+        language_maps: Box<dyn DynAccess<LanguageKeymaps>>,
+    ) -> Self {
+        self.language_maps = Some(language_maps);
+        self
+    }
+
+    /// Sets the language of the focused document, whose keymap is used if it has one.
+    pub fn set_language(&mut self, language: Option<&str>) {
+        if self.language.as_deref() != language {
+            self.language = language.map(String::from);
+        }
+    }
+
     pub fn map(&self) -> DynGuard<HashMap<Mode, KeyTrie>> {
         self.map.load()
     }
 
+    /// The keymaps of the languages, if the language of the focused document has one.
+    fn language_maps(&self) -> Option<DynGuard<LanguageKeymaps>> {
+        let maps = self.language_maps.as_ref()?.load();
+        maps.contains_key(self.language.as_deref()?).then_some(maps)
+    }
+
+    /// The keymap of `mode` for the language of the focused document, falling back to the global
+    /// keymap.
+    fn keymap<'a>(
+        &self,
+        global: &'a HashMap<Mode, KeyTrie>,
+        language_maps: &'a Option<DynGuard<LanguageKeymaps>>,
+        mode: Mode,
+    ) -> &'a KeyTrie {
+        match (language_maps, &self.language) {
+            (Some(maps), Some(language)) => &maps[language][&mode],
+            _ => &global[&mode],
+        }
+    }
+
+    /// The keybindings of the commands in `mode`, taking the keymap of the language of the focused
+    /// document into account.
+    pub fn reverse_map(&self, mode: Mode) -> ReverseKeymap {
+        let global = self.map();
+        let language_maps = self.language_maps();
+        self.keymap(&global, &language_maps, mode).reverse_map()
+    }
+
     /// Returns list of keys waiting to be disambiguated in current mode.
     pub fn pending(&self) -> &[KeyEvent] {
         &self.state
@@ -293,8 +344,9 @@ pub fn sticky(&self) -> Option<&KeyTrieNode> {
     }
 
     pub fn contains_key(&self, mode: Mode, key: KeyEvent) -> bool {
-        let keymaps = &*self.map();
-        let keymap = &keymaps[&mode];
+        let keymaps = self.map();
+        let language_maps = self.language_maps();
+        let keymap = self.keymap(&keymaps, &language_maps, mode);
         keymap
             .search(self.pending())
             .and_then(KeyTrie::node)
@@ -306,8 +358,9 @@ pub fn contains_key(&self, mode: Mode, key: KeyEvent) -> bool {
     /// sticky node is in use, it will be cleared.
     pub fn get(&mut self, mode: Mode, key: KeyEvent) -> KeymapResult {
         // TODO: remove the sticky part and look up manually
-        let keymaps = &*self.map();
-        let keymap = &keymaps[&mode];
+        let keymaps = self.map();
+        let language_maps = self.language_maps();
+        let keymap = self.keymap(&keymaps, &language_maps, mode);
 
         if key!(Esc) == key {
             if !self.state.is_empty() {
@@ -377,11 +430,12 @@ pub fn merge_keys(dst: &mut HashMap<Mode, KeyTrie>, mut delta: HashMap<Mode, Key
 mod tests {
     use super::macros::keymap;
     use super::*;
-    use crate::commands::MappableCommand;
+    use crate::commands::{MappableCommand, StaticArgs};
     use arc_swap::access::Constant;
     use helix_core::hashmap;
     use helix_view::input::{KeyCode, KeyEvent, KeyModifiers};
     use indexmap::indexmap;
+    use std::num::NonZeroUsize;
 
     #[test]
     #[should_panic]
@@ -398,6 +452,44 @@ fn check_duplicate_keys_in_default_keymap() {
         Keymaps::default();
     }
 
+    #[test]
+    fn language_keymaps() {
+        let mut markdown = default();
+        merge_keys(
+            &mut markdown,
+            hashmap! {
+                Mode::Normal => keymap!({ "Normal mode"
+                    "i" => normal_mode,
+                }),
+            },
+        );
+        let mut keymap = Keymaps::new(Box::new(Constant(default()))).with_language_maps(Box::new(
+            Constant(hashmap! { "markdown".to_owned() => markdown }),
+        ));
+
+        keymap.set_language(Some("markdown"));
+        assert_eq!(
+            keymap.get(Mode::Normal, key!('i')),
+            KeymapResult::Matched(MappableCommand::normal_mode),
+        );
+        assert_eq!(
+            keymap.get(Mode::Normal, key!('a')),
+            KeymapResult::Matched(MappableCommand::append_mode),
+        );
+        assert!(keymap.reverse_map(Mode::Normal)["normal_mode"].contains(&vec![key!('i')]));
+
+        keymap.set_language(Some("rust"));
+        assert_eq!(
+            keymap.get(Mode::Normal, key!('i')),
+            KeymapResult::Matched(MappableCommand::insert_mode),
+        );
+        keymap.set_language(None);
+        assert_eq!(
+            keymap.get(Mode::Normal, key!('i')),
+            KeymapResult::Matched(MappableCommand::insert_mode),
+        );
+    }
+
     #[test]
     fn merge_partial_keys() {
         let keymap = hashmap! {
@@ -615,4 +707,62 @@ fn escaped_keymap() {
 
         assert_eq!(toml::from_str(keys), Ok(expectation));
     }
+
+    #[test]
+    fn static_command_args() {
+        let with_args = |command, args| match command {
+            MappableCommand::Static { name, fun, doc, .. } => MappableCommand::Static {
+                name,
+                fun,
+                doc,
+                args,
+            },
+            _ => unreachable!(),
+        };
+        let keys = r#"
+f = "select_textobject_around f"
+"(" = "surround_add '('"
+g = ["goto_line --count 10", "select_all"]
+        "#;
+
+        let expectation = KeyTrie::Node(KeyTrieNode::new(
+            "",
+            indexmap! {
+                key!('f') => KeyTrie::MappableCommand(with_args(
+                    MappableCommand::select_textobject_around,
+                    StaticArgs {
+                        count: None,
+                        keys: vec![key!('f')],
+                    },
+                )),
+                key!('(') => KeyTrie::MappableCommand(with_args(
+                    MappableCommand::surround_add,
+                    StaticArgs {
+                        count: None,
+                        keys: vec![key!('(')],
+                    },
+                )),
+                key!('g') => KeyTrie::Sequence(vec![
+                    with_args(
+                        MappableCommand::goto_line,
+                        StaticArgs {
+                            count: NonZeroUsize::new(10),
+                            keys: Vec::new(),
+                        },
+                    ),
+                    MappableCommand::select_all,
+                ]),
+            },
+        ));
+
+        assert_eq!(toml::from_str(keys), Ok(expectation));
+        assert_ne!(
+            "select_textobject_around f"
+                .parse::<MappableCommand>()
+                .unwrap(),
+            MappableCommand::select_textobject_around
+        );
+        assert!("goto_line --count".parse::<MappableCommand>().is_err());
+        assert!("surround_add not-a-key".parse::<MappableCommand>().is_err());
+    }
 }
@@ -114,6 +114,7 @@ pub fn default() -> HashMap<Mode, KeyTrie> {
             "D" => goto_first_diag,
             "g" => goto_prev_change,
             "G" => goto_first_change,
+            "C" => goto_prev_conflict,
             "f" => goto_prev_function,
             "t" => goto_prev_class,
             "a" => goto_prev_parameter,
@@ -129,6 +130,7 @@ pub fn default() -> HashMap<Mode, KeyTrie> {
             "D" => goto_last_diag,
             "g" => goto_next_change,
             "G" => goto_last_change,
+            "C" => goto_next_conflict,
             "f" => goto_next_function,
             "t" => goto_next_class,
             "a" => goto_next_parameter,
@@ -243,14 +245,19 @@ pub fn default() -> HashMap<Mode, KeyTrie> {
                 "l" => dap_launch,
                 "r" => dap_restart,
                 "b" => dap_toggle_breakpoint,
+                "d" => dap_toggle_breakpoint_enabled,
+                "B" => dap_breakpoint_picker,
                 "c" => dap_continue,
                 "h" => dap_pause,
                 "i" => dap_step_in,
                 "o" => dap_step_out,
                 "n" => dap_next,
                 "v" => dap_variables,
+                "p" => dap_toggle_panel,
+                "R" => dap_toggle_console,
                 "t" => dap_terminate,
                 "C-c" => dap_edit_condition,
+                "C-n" => dap_edit_hit_condition,
                 "C-l" => dap_edit_log,
                 "s" => { "Switch"
                     "t" => dap_switch_thread,
@@ -312,6 +319,12 @@ pub fn default() -> HashMap<Mode, KeyTrie> {
             "?" => rsearch,
             "n" => search_next,
             "N" => search_prev,
+
+            "f" => fold,
+            "o" => unfold,
+            "a" => toggle_fold,
+            "M" => fold_all,
+            "R" => unfold_all,
         },
         "Z" => { "View" sticky=true
             "z" | "c" => align_view_center,
@@ -329,6 +342,12 @@ pub fn default() -> HashMap<Mode, KeyTrie> {
             "?" => rsearch,
             "n" => search_next,
             "N" => search_prev,
+
+            "f" => fold,
+            "o" => unfold,
+            "a" => toggle_fold,
+            "M" => fold_all,
+            "R" => unfold_all,
         },
 
         "\"" => select_register,
@@ -387,6 +406,10 @@ pub fn default() -> HashMap<Mode, KeyTrie> {
         "C-s" => commit_undo_checkpoint,
         "C-x" => completion,
         "C-r" => insert_register,
+        "A-l" => inline_completion_accept,
+        "A-w" => inline_completion_accept_word,
+        "A-n" => inline_completion_next,
+        "A-x" => inline_completion_dismiss,
 
         "C-w" | "A-backspace" => delete_word_backward,
         "A-d" | "A-del" => delete_word_forward,
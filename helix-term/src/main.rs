@@ -61,6 +61,7 @@ async fn main_impl() -> Result<i32> {
     --vsplit                       Split all given files vertically into different windows
     --hsplit                       Split all given files horizontally into different windows
     -w, --working-dir <path>       Specify an initial working directory
+    --session <name>               Restore a session saved with `:session-save`
     +[N]                           Open the first given file at line number N, or the last line, if
                                    N is not specified.
 ",
@@ -106,6 +107,9 @@ async fn main_impl() -> Result<i32> {
     // Application::new() depends on this logic so it must be updated if this changes.
     if let Some(path) = &args.working_directory {
         helix_stdx::env::set_current_working_dir(path)?;
+    } else if let Some(name) = &args.session {
+        let session = helix_view::session::Session::load(name)?;
+        helix_stdx::env::set_current_working_dir(&session.cwd)?;
     } else if let Some((path, _)) = args.files.first().filter(|p| p.0.is_dir()) {
         // If the first file is a directory, it will be the working directory unless -w was specified
         helix_stdx::env::set_current_working_dir(path)?;
@@ -115,7 +119,7 @@ async fn main_impl() -> Result<i32> {
         return Ok(1);
     }
 
-    let config = match Config::load_default() {
+    let mut config = match Config::load_default() {
         Ok(config) => config,
         Err(ConfigLoadError::Error(err)) if err.kind() == std::io::ErrorKind::NotFound => {
             Config::default()
@@ -142,6 +146,12 @@ async fn main_impl() -> Result<i32> {
             let _ = std::io::stdin().read(&mut []);
             helix_core::config::default_lang_loader()
         });
+    if let Err(err) = config.load_language_keys(&lang_loader) {
+        eprintln!("{:#}", err);
+        eprintln!("Press <ENTER> to continue without the language keymaps");
+        use std::io::Read;
+        let _ = std::io::stdin().read(&mut []);
+    }
 
     // TODO: use the thread local executor to spawn the application task separately from the work pool
     let mut app = Application::new(args, config, lang_loader, workspace_trust)
@@ -0,0 +1,291 @@
+use std::sync::{Arc, Mutex};
+
+use helix_dap::{requests, CompletionItem};
+use helix_lsp::block_on;
+use helix_view::{debug_console::ConsoleLineKind, graphics::Rect, Editor};
+use tui::{
+    buffer::Buffer as Surface,
+    text::Span,
+    widgets::{Block, Borders, Widget as _},
+};
+
+use crate::{
+    compositor::{Callback, Component, Compositor, Context, Event, EventResult},
+    ctrl, job, key,
+    ui::{prompt::Completion, Prompt, PromptEvent},
+};
+
+pub const ID: &str = "debug-console";
+
+/// A console docked below the editor views (above the debug panel) showing the output of the
+/// debugged program and the expressions evaluated in it with their results.
+pub struct DebugConsole {
+    pub focused: bool,
+    /// The number of lines scrolled up from the end of the scrollback.
+    scroll: usize,
+    /// The number of lines shown at the last render.
+    viewport: usize,
+}
+
+impl Default for DebugConsole {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DebugConsole {
+    pub fn new() -> Self {
+        Self {
+            focused: true,
+            scroll: 0,
+            viewport: 0,
+        }
+    }
+}
+
+/// The debugger's completions of the last input they were requested for, `None` while the
+/// request is pending.
+#[derive(Default)]
+struct CompletionCache {
+    input: String,
+    completions: Option<Vec<Completion>>,
+}
+
+/// Returns the completions of `input` once the debugger responded to the request for them. The
+/// request is sent in the background and the prompt's completions are recalculated when it
+/// completes, so typing doesn't block on the debugger.
+fn completions(
+    cache: &Arc<Mutex<CompletionCache>>,
+    editor: &Editor,
+    input: &str,
+) -> Vec<Completion> {
+    let mut cached = cache.lock().unwrap();
+    if cached.input == input {
+        return cached.completions.clone().unwrap_or_default();
+    }
+    cached.input = input.to_owned();
+    cached.completions = None;
+    drop(cached);
+
+    let Some(debugger) = editor.debug_adapters.get_active_client() else {
+        return Vec::new();
+    };
+    if !debugger
+        .caps
+        .as_ref()
+        .and_then(|caps| caps.supports_completions_request)
+        .unwrap_or(false)
+    {
+        return Vec::new();
+    }
+    let args = requests::CompletionsArguments {
+        frame_id: debugger.current_stack_frame().map(|frame| frame.id),
+        text: input.to_owned(),
+        column: input.encode_utf16().count() + 1,
+        line: None,
+    };
+    let request = debugger.call::<requests::Completions>(args);
+    let cache = cache.clone();
+    let input = input.to_owned();
+    tokio::spawn(async move {
+        let targets = match request
+            .await
+            .and_then(|response| Ok(serde_json::from_value(response)?))
+        {
+            Ok(requests::CompletionsResponse { targets }) => targets,
+            Err(err) => {
+                log::debug!("debugger completions failed: {err}");
+                Vec::new()
+            }
+        };
+        let completions = completions_from_targets(&input, targets);
+        {
+            let mut cached = cache.lock().unwrap();
+            if cached.input != input {
+                return;
+            }
+            cached.completions = Some(completions);
+        }
+        job::dispatch(move |editor, compositor| {
+            if let Some(prompt) = compositor.find::<Prompt>() {
+                if *prompt.line() == input {
+                    prompt.recalculate_completion(editor);
+                }
+            }
+        })
+        .await;
+    });
+    Vec::new()
+}
+
+fn completions_from_targets(input: &str, targets: Vec<CompletionItem>) -> Vec<Completion> {
+    // Completions without a start replace the word before the cursor.
+    let word_start = input
+        .char_indices()
+        .rev()
+        .take_while(|(_, ch)| ch.is_alphanumeric() || *ch == '_')
+        .last()
+        .map_or(input.len(), |(idx, _)| idx);
+    targets
+        .into_iter()
+        .map(|target| {
+            // `start` is a 1-based offset in UTF-16 code units.
+            let start = match target.start {
+                Some(start) => utf16_to_byte(input, start.saturating_sub(1)),
+                None => word_start,
+            };
+            (start.., Span::raw(target.text.unwrap_or(target.label)))
+        })
+        .collect()
+}
+
+fn utf16_to_byte(text: &str, offset: usize) -> usize {
+    let mut utf16 = 0;
+    for (idx, ch) in text.char_indices() {
+        if utf16 >= offset {
+            return idx;
+        }
+        utf16 += ch.len_utf16();
+    }
+    text.len()
+}
+
+/// The prompt to enter an expression to evaluate. It is opened again after every evaluation, so
+/// expressions can be entered one after another until it's closed with `Esc`.
+fn eval_prompt() -> Prompt {
+    let cache = Arc::default();
+    Prompt::new(
+        "repl:".into(),
+        None,
+        move |editor: &Editor, input: &str| completions(&cache, editor, input),
+        |cx, input: &str, event: PromptEvent| {
+            if event != PromptEvent::Validate || input.is_empty() {
+                return;
+            }
+            block_on(cx.editor.eval_in_debug_console(input));
+            cx.jobs.callback(async {
+                let call: job::Callback = job::Callback::EditorCompositor(Box::new(
+                    |_editor: &mut Editor, compositor: &mut Compositor| {
+                        compositor.push(Box::new(eval_prompt()))
+                    },
+                ));
+                Ok(call)
+            });
+        },
+    )
+}
+
+/// Closes the debug console.
+pub fn close(compositor: &mut Compositor, editor: &mut Editor) {
+    compositor.remove(ID);
+    editor.debug_console.visible = false;
+}
+
+impl Component for DebugConsole {
+    fn handle_event(&mut self, event: &Event, cx: &mut Context) -> EventResult {
+        let Event::Key(key) = event else {
+            return EventResult::Ignored(None);
+        };
+        if !self.focused {
+            return EventResult::Ignored(None);
+        }
+
+        let max_scroll = cx.editor.debug_console.lines.len().saturating_sub(1);
+        let page = (self.viewport / 2).max(1);
+        let callback: Option<Callback> = match key {
+            key!('k') | key!(Up) | ctrl!('p') => {
+                self.scroll = (self.scroll + 1).min(max_scroll);
+                None
+            }
+            key!('j') | key!(Down) | ctrl!('n') => {
+                self.scroll = self.scroll.saturating_sub(1);
+                None
+            }
+            ctrl!('u') | key!(PageUp) => {
+                self.scroll = (self.scroll + page).min(max_scroll);
+                None
+            }
+            ctrl!('d') | key!(PageDown) => {
+                self.scroll = self.scroll.saturating_sub(page);
+                None
+            }
+            key!('g') | key!(Home) => {
+                self.scroll = max_scroll;
+                None
+            }
+            key!('G') | key!(End) => {
+                self.scroll = 0;
+                None
+            }
+            key!('i') | key!('a') | key!(Enter) => {
+                self.scroll = 0;
+                Some(Box::new(|compositor: &mut Compositor, _| {
+                    compositor.push(Box::new(eval_prompt()))
+                }))
+            }
+            key!('c') => {
+                cx.editor.debug_console.clear();
+                self.scroll = 0;
+                None
+            }
+            key!(Esc) => {
+                self.focused = false;
+                None
+            }
+            key!('q') => Some(Box::new(|compositor: &mut Compositor, cx| {
+                close(compositor, cx.editor)
+            })),
+            _ => None,
+        };
+        EventResult::Consumed(callback)
+    }
+
+    fn render(&mut self, area: Rect, surface: &mut Surface, cx: &mut Context) {
+        // The console is docked right above the debug panel, which keeps the last row free for
+        // the status message.
+        let area = area.clip_bottom(1);
+        let height = cx.editor.debug_console.height(area.height);
+        let area = area.clip_bottom(cx.editor.debug_panel.height(area.height));
+        let area = area.clip_top(area.height.saturating_sub(height));
+        let theme = &cx.editor.theme;
+        surface.clear_with(area, theme.get("ui.background"));
+        let title_style = if self.focused {
+            theme.get("ui.text.focus")
+        } else {
+            theme.get("ui.text")
+        };
+        let block = Block::new()
+            .borders(Borders::TOP)
+            .border_style(theme.get("ui.window"))
+            .title(Span::styled(" Debug console ", title_style));
+        let inner = block.inner(area);
+        block.render(area, surface);
+        self.viewport = inner.height as usize;
+
+        let lines = &cx.editor.debug_console.lines;
+        self.scroll = self.scroll.min(lines.len().saturating_sub(1));
+        let end = lines.len() - self.scroll.min(lines.len());
+        let start = end.saturating_sub(inner.height as usize);
+        for (row, line) in lines.range(start..end).enumerate() {
+            let (prefix, style) = match line.kind {
+                ConsoleLineKind::Input => ("> ", theme.get("ui.text.focus")),
+                ConsoleLineKind::Result => ("", theme.get("ui.text")),
+                ConsoleLineKind::Error => ("", theme.get("error")),
+                ConsoleLineKind::Stdout => ("", theme.get("ui.text")),
+                ConsoleLineKind::Stderr => ("", theme.get("warning")),
+                ConsoleLineKind::Console => ("", theme.get("ui.text.inactive")),
+            };
+            surface.set_stringn(
+                inner.x + 1,
+                inner.y + row as u16,
+                &format!("{prefix}{}", line.text),
+                inner.width.saturating_sub(1) as usize,
+                style,
+            );
+        }
+    }
+
+    fn id(&self) -> Option<&'static str> {
+        Some(ID)
+    }
+}
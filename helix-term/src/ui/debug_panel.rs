@@ -0,0 +1,534 @@
+use anyhow::{anyhow, bail};
+use helix_core::Selection;
+use helix_lsp::block_on;
+use helix_view::{
+    align_view,
+    debug_panel::{DebugNodePath, DebugRoot, DebugVariable},
+    editor::Action,
+    graphics::{Modifier, Rect},
+    handlers::dap::jump_to_stack_frame,
+    Align, Editor,
+};
+use tui::{
+    buffer::Buffer as Surface,
+    text::{Span, Spans},
+    widgets::{Block, Borders, Widget as _},
+};
+
+use crate::{
+    commands::{
+        dap_toggle_data_breakpoint_impl, BreakpointChange, BreakpointId, BreakpointItem,
+        BreakpointState,
+    },
+    compositor::{Callback, Component, Compositor, Context, Event, EventResult},
+    ctrl, key,
+    ui::{self, Prompt, PromptEvent},
+};
+
+pub const ID: &str = "debug-panel";
+
+/// A line of the debug panel.
+enum Row {
+    Header(&'static str),
+    /// A watch expression, variable or scope.
+    Node {
+        path: DebugNodePath,
+        depth: usize,
+    },
+    /// A watch expression that wasn't evaluated yet or failed to evaluate.
+    Watch(usize),
+    /// A frame of the call stack of the active thread.
+    Frame(usize),
+    Breakpoint(BreakpointId),
+}
+
+fn push_node(rows: &mut Vec<Row>, node: &DebugVariable, path: DebugNodePath, depth: usize) {
+    let children = node.children.iter().flatten().enumerate();
+    rows.push(Row::Node {
+        path: path.clone(),
+        depth,
+    });
+    for (idx, child) in children {
+        let mut path = path.clone();
+        path.children.push(idx);
+        push_node(rows, child, path, depth + 1);
+    }
+}
+
+fn rows(editor: &Editor) -> Vec<Row> {
+    let panel = &editor.debug_panel;
+    let mut rows = vec![Row::Header("Watches")];
+    for (idx, watch) in panel.watches.iter().enumerate() {
+        match &watch.result {
+            Some(Ok(node)) => push_node(
+                &mut rows,
+                node,
+                DebugNodePath {
+                    root: DebugRoot::Watch(idx),
+                    children: Vec::new(),
+                },
+                0,
+            ),
+            _ => rows.push(Row::Watch(idx)),
+        }
+    }
+
+    rows.push(Row::Header("Variables"));
+    for (idx, scope) in panel.scopes.iter().enumerate() {
+        let path = DebugNodePath {
+            root: DebugRoot::Scope(idx),
+            children: Vec::new(),
+        };
+        push_node(&mut rows, scope, path, 0);
+    }
+
+    rows.push(Row::Header("Call stack"));
+    if let Some(frames) = editor
+        .debug_adapters
+        .get_active_client()
+        .and_then(|debugger| debugger.stack_frames.get(&debugger.thread_id?))
+    {
+        rows.extend((0..frames.len()).map(Row::Frame));
+    }
+
+    rows.push(Row::Header("Breakpoints"));
+    let mut paths: Vec<_> = editor.breakpoints.iter().collect();
+    paths.sort_unstable_by_key(|(path, _)| *path);
+    for (path, breakpoints) in paths {
+        rows.extend(
+            (0..breakpoints.len())
+                .map(|idx| Row::Breakpoint(BreakpointId::Source(path.clone(), idx))),
+        );
+    }
+    rows.extend(
+        (0..editor.function_breakpoints.len())
+            .map(|idx| Row::Breakpoint(BreakpointId::Function(idx))),
+    );
+    rows.extend(
+        (0..editor.data_breakpoints.len()).map(|idx| Row::Breakpoint(BreakpointId::Data(idx))),
+    );
+    rows.extend(
+        (0..editor.instruction_breakpoints.len())
+            .map(|idx| Row::Breakpoint(BreakpointId::Instruction(idx))),
+    );
+    rows
+}
+
+/// A panel docked below the editor views showing the watch expressions, the variables of the
+/// active stack frame, the call stack and the breakpoints of a debug session.
+pub struct DebugPanel {
+    pub focused: bool,
+    cursor: usize,
+    scroll: usize,
+}
+
+impl Default for DebugPanel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DebugPanel {
+    pub fn new() -> Self {
+        Self {
+            focused: true,
+            cursor: 0,
+            scroll: 0,
+        }
+    }
+
+    fn toggle(&mut self, editor: &mut Editor, row: &Row) -> anyhow::Result<()> {
+        match row {
+            Row::Node { path, .. } => {
+                if editor
+                    .debug_panel
+                    .node(path)
+                    .is_some_and(|node| node.is_expanded())
+                {
+                    editor.debug_panel.collapse(path);
+                } else {
+                    expand(editor, path)?;
+                }
+            }
+            Row::Frame(idx) => select_frame(editor, *idx),
+            Row::Breakpoint(BreakpointId::Source(path, idx)) => {
+                let line = editor.breakpoints[path][*idx].line;
+                editor.open(path, Action::Replace)?;
+                let (view, doc) = current!(editor);
+                let pos = doc
+                    .text()
+                    .line_to_char(line.min(doc.text().len_lines() - 1));
+                doc.set_selection(view.id, Selection::point(pos));
+                align_view(doc, view, Align::Center);
+                self.focused = false;
+            }
+            Row::Header(_) | Row::Watch(_) | Row::Breakpoint(_) => (),
+        }
+        Ok(())
+    }
+
+    fn delete(&mut self, editor: &mut Editor, row: &Row) -> anyhow::Result<()> {
+        match row {
+            Row::Node { path, .. } if path.children.is_empty() => {
+                if let DebugRoot::Watch(idx) = path.root {
+                    editor.debug_panel.remove_watch(idx);
+                }
+            }
+            Row::Watch(idx) => editor.debug_panel.remove_watch(*idx),
+            Row::Breakpoint(id) => id.change(editor, BreakpointChange::Remove)?,
+            _ => (),
+        }
+        Ok(())
+    }
+
+    fn render_row(&self, editor: &Editor, row: &Row) -> Spans<'static> {
+        let theme = &editor.theme;
+        let header_style = theme.get("ui.linenr.selected");
+        let type_style = theme.get("ui.text");
+        let text_style = theme.get("ui.text.focus");
+        let inactive_style = theme.get("ui.text.inactive");
+
+        let mut spans = Vec::new();
+        match row {
+            Row::Header(title) => {
+                spans.push(Span::styled(
+                    *title,
+                    header_style.add_modifier(Modifier::BOLD),
+                ));
+            }
+            Row::Node { path, depth } => {
+                let Some(node) = editor.debug_panel.node(path) else {
+                    return Spans::default();
+                };
+                let marker = match (node.is_expandable(), node.is_expanded()) {
+                    (true, true) => "▾ ",
+                    (true, false) => "▸ ",
+                    (false, _) => "  ",
+                };
+                spans.push(Span::raw(format!("{}{marker}", "  ".repeat(depth + 1))));
+                spans.push(Span::styled(node.name.clone(), text_style));
+                if let Some(ty) = &node.ty {
+                    spans.push(Span::raw(": "));
+                    spans.push(Span::styled(ty.clone(), type_style));
+                }
+                if !node.value.is_empty() {
+                    spans.push(Span::raw(" = "));
+                    spans.push(Span::styled(node.value.clone(), text_style));
+                }
+            }
+            Row::Watch(idx) => {
+                let watch = &editor.debug_panel.watches[*idx];
+                spans.push(Span::raw("    "));
+                spans.push(Span::styled(watch.expression.clone(), text_style));
+                match &watch.result {
+                    Some(Err(err)) => {
+                        spans.push(Span::raw(" = "));
+                        spans.push(Span::styled(err.clone(), theme.get("error")));
+                    }
+                    _ => spans.push(Span::styled(" = <not available>", inactive_style)),
+                }
+            }
+            Row::Frame(idx) => {
+                let Some(debugger) = editor.debug_adapters.get_active_client() else {
+                    return Spans::default();
+                };
+                let Some(frame) = debugger
+                    .thread_id
+                    .and_then(|thread_id| debugger.stack_frames.get(&thread_id)?.get(*idx))
+                else {
+                    return Spans::default();
+                };
+                let marker = if debugger.active_frame == Some(*idx) {
+                    "  → "
+                } else {
+                    "    "
+                };
+                spans.push(Span::raw(marker));
+                spans.push(Span::styled(frame.name.clone(), text_style));
+                if let Some(path) = frame
+                    .source
+                    .as_ref()
+                    .and_then(|source| source.path.as_ref())
+                {
+                    let path = helix_stdx::path::get_relative_path(path);
+                    spans.push(Span::styled(
+                        format!("  {}:{}", path.display(), frame.line),
+                        inactive_style,
+                    ));
+                }
+            }
+            Row::Breakpoint(id) => {
+                let Some(item) = BreakpointItem::new(editor, id) else {
+                    return Spans::default();
+                };
+                let state = item.state();
+                let style = if state == BreakpointState::Verified {
+                    text_style
+                } else {
+                    inactive_style
+                };
+                spans.push(Span::raw("    "));
+                if !matches!(id, BreakpointId::Source(..)) {
+                    spans.push(Span::styled(format!("{} ", item.kind()), type_style));
+                }
+                spans.push(Span::styled(item.location(), style));
+                let conditions = item.conditions();
+                if !conditions.is_empty() {
+                    spans.push(Span::styled(format!("  {conditions}"), type_style));
+                }
+                if state == BreakpointState::Disabled {
+                    spans.push(Span::styled("  (disabled)", inactive_style));
+                }
+            }
+        }
+        Spans::from(spans)
+    }
+}
+
+fn expand(editor: &mut Editor, path: &DebugNodePath) -> anyhow::Result<()> {
+    let debugger = editor
+        .debug_adapters
+        .get_active_client()
+        .ok_or_else(|| anyhow!("Debugger is not running"))?;
+    block_on(editor.debug_panel.expand(debugger, path))
+}
+
+fn select_frame(editor: &mut Editor, idx: usize) {
+    let debugger = debugger!(editor);
+    let Some(frame) = debugger
+        .thread_id
+        .and_then(|thread_id| debugger.stack_frames.get(&thread_id)?.get(idx))
+        .cloned()
+    else {
+        return;
+    };
+    debugger.active_frame = Some(idx);
+    jump_to_stack_frame(editor, &frame);
+    editor.update_inline_values();
+    block_on(editor.update_debug_panel());
+}
+
+/// Changes the value of a variable or watch expression with a `setVariable` request or, if the
+/// debugger doesn't support it, a `setExpression` request.
+fn set_value(editor: &mut Editor, path: &DebugNodePath, value: String) -> anyhow::Result<()> {
+    let node = editor
+        .debug_panel
+        .node(path)
+        .ok_or_else(|| anyhow!("The variable no longer exists"))?;
+    let debugger = editor
+        .debug_adapters
+        .get_active_client()
+        .ok_or_else(|| anyhow!("Debugger is not running"))?;
+    let caps = debugger.capabilities();
+    if node.container != 0 && caps.supports_set_variable.unwrap_or(false) {
+        block_on(debugger.set_variable(node.container, node.name.clone(), value))?;
+    } else if let (Some(expression), true) = (
+        &node.evaluate_name,
+        caps.supports_set_expression.unwrap_or(false),
+    ) {
+        let frame_id = debugger.current_stack_frame().map(|frame| frame.id);
+        block_on(debugger.set_expression(expression.clone(), value, frame_id))?;
+    } else {
+        bail!("The debugger can't change the value of '{}'", node.name);
+    }
+    block_on(editor.update_debug_panel());
+    editor.update_inline_values();
+    Ok(())
+}
+
+/// Toggles a data breakpoint on the variable or watch expression at `path`.
+fn data_breakpoint(editor: &mut Editor, path: &DebugNodePath) -> anyhow::Result<()> {
+    let node = editor
+        .debug_panel
+        .node(path)
+        .ok_or_else(|| anyhow!("The variable no longer exists"))?;
+    let (variables_reference, name) = match node.container {
+        0 => (
+            None,
+            node.evaluate_name
+                .clone()
+                .ok_or_else(|| anyhow!("Can't set a data breakpoint on a scope"))?,
+        ),
+        container => (Some(container), node.name.clone()),
+    };
+    dap_toggle_data_breakpoint_impl(editor, variables_reference, &name, None)
+}
+
+fn watch_prompt() -> Prompt {
+    Prompt::new(
+        "watch:".into(),
+        None,
+        ui::completers::none,
+        |cx, input: &str, event: PromptEvent| {
+            if event != PromptEvent::Validate || input.is_empty() {
+                return;
+            }
+            cx.editor.debug_panel.add_watch(input.to_owned());
+            block_on(cx.editor.update_debug_panel());
+        },
+    )
+}
+
+fn set_value_prompt(path: DebugNodePath, value: &str, editor: &Editor) -> Prompt {
+    let mut prompt = Prompt::new(
+        "value:".into(),
+        None,
+        ui::completers::none,
+        move |cx, input: &str, event: PromptEvent| {
+            if event != PromptEvent::Validate {
+                return;
+            }
+            if let Err(err) = set_value(cx.editor, &path, input.to_owned()) {
+                cx.editor.set_error(err.to_string());
+            }
+        },
+    );
+    prompt.insert_str(value, editor);
+    prompt
+}
+
+/// Closes the debug panel.
+pub fn close(compositor: &mut Compositor, editor: &mut Editor) {
+    compositor.remove(ID);
+    editor.debug_panel.visible = false;
+}
+
+impl Component for DebugPanel {
+    fn handle_event(&mut self, event: &Event, cx: &mut Context) -> EventResult {
+        let Event::Key(key) = event else {
+            return EventResult::Ignored(None);
+        };
+        if !self.focused {
+            return EventResult::Ignored(None);
+        }
+
+        let rows = rows(cx.editor);
+        self.cursor = self.cursor.min(rows.len() - 1);
+        let row = &rows[self.cursor];
+        let result = match key {
+            key!('j') | key!(Down) | ctrl!('n') => {
+                self.cursor = (self.cursor + 1).min(rows.len() - 1);
+                Ok(None)
+            }
+            key!('k') | key!(Up) | ctrl!('p') => {
+                self.cursor = self.cursor.saturating_sub(1);
+                Ok(None)
+            }
+            key!('g') | key!(Home) => {
+                self.cursor = 0;
+                Ok(None)
+            }
+            key!('G') | key!(End) => {
+                self.cursor = rows.len() - 1;
+                Ok(None)
+            }
+            key!(Enter) | key!(Tab) => self.toggle(cx.editor, row).map(|_| None),
+            key!('l') | key!(Right) => match row {
+                Row::Node { path, .. }
+                    if !cx
+                        .editor
+                        .debug_panel
+                        .node(path)
+                        .is_some_and(|node| node.is_expanded()) =>
+                {
+                    expand(cx.editor, path).map(|_| None)
+                }
+                _ => Ok(None),
+            },
+            key!('h') | key!(Left) => {
+                if let Row::Node { path, .. } = row {
+                    cx.editor.debug_panel.collapse(path);
+                }
+                Ok(None)
+            }
+            key!('a') => {
+                let callback: Callback = Box::new(|compositor: &mut Compositor, _| {
+                    compositor.push(Box::new(watch_prompt()))
+                });
+                Ok(Some(callback))
+            }
+            key!('e') => match row {
+                Row::Node { path, .. } => {
+                    let path = path.clone();
+                    let callback: Callback = Box::new(move |compositor: &mut Compositor, cx| {
+                        let Some(node) = cx.editor.debug_panel.node(&path) else {
+                            return;
+                        };
+                        let prompt = set_value_prompt(path.clone(), &node.value, cx.editor);
+                        compositor.push(Box::new(prompt))
+                    });
+                    Ok(Some(callback))
+                }
+                _ => Ok(None),
+            },
+            key!('d') => self.delete(cx.editor, row).map(|_| None),
+            key!('t') => match row {
+                Row::Breakpoint(id) => id
+                    .change(cx.editor, BreakpointChange::ToggleEnabled)
+                    .map(|_| None),
+                _ => Ok(None),
+            },
+            key!('b') => match row {
+                Row::Node { path, .. } => data_breakpoint(cx.editor, path).map(|_| None),
+                _ => Ok(None),
+            },
+            key!(Esc) => {
+                self.focused = false;
+                Ok(None)
+            }
+            key!('q') => {
+                let callback: Callback =
+                    Box::new(|compositor: &mut Compositor, cx| close(compositor, cx.editor));
+                Ok(Some(callback))
+            }
+            _ => Ok(None),
+        };
+        match result {
+            Ok(callback) => EventResult::Consumed(callback),
+            Err(err) => {
+                cx.editor.set_error(err.to_string());
+                EventResult::Consumed(None)
+            }
+        }
+    }
+
+    fn render(&mut self, area: Rect, surface: &mut Surface, cx: &mut Context) {
+        // Keep the last row free for the status message.
+        let area = area.clip_bottom(1);
+        let height = cx.editor.debug_panel.height(area.height);
+        let area = area.clip_top(area.height - height);
+        let theme = &cx.editor.theme;
+        surface.clear_with(area, theme.get("ui.background"));
+        let block = Block::new()
+            .borders(Borders::TOP)
+            .border_style(theme.get("ui.window"))
+            .title(Span::styled(" Debug ", theme.get("ui.text.focus")));
+        let inner = block.inner(area);
+        block.render(area, surface);
+
+        let rows = rows(cx.editor);
+        self.cursor = self.cursor.min(rows.len() - 1);
+        let visible = inner.height as usize;
+        if self.cursor < self.scroll {
+            self.scroll = self.cursor;
+        } else if self.cursor >= self.scroll + visible {
+            self.scroll = self.cursor + 1 - visible;
+        }
+
+        for (i, row) in rows.iter().enumerate().skip(self.scroll).take(visible) {
+            let y = inner.y + (i - self.scroll) as u16;
+            let spans = self.render_row(cx.editor, row);
+            surface.set_spans(inner.x + 1, y, &spans, inner.width.saturating_sub(1));
+            if self.focused && i == self.cursor {
+                surface.set_style(
+                    Rect::new(inner.x, y, inner.width, 1),
+                    cx.editor.theme.get("ui.menu.selected"),
+                );
+            }
+        }
+    }
+
+    fn id(&self) -> Option<&'static str> {
+        Some(ID)
+    }
+}
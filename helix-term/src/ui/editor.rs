@@ -134,6 +134,16 @@ pub fn render_view(
             &text_annotations,
         ));
 
+        if config.lsp.display_semantic_tokens {
+            Self::doc_semantic_token_highlights_into(
+                doc,
+                view_offset.anchor,
+                inner.height,
+                theme,
+                &mut overlays,
+            );
+        }
+
         if doc
             .language_config()
             .and_then(|config| config.rainbow_brackets)
@@ -150,6 +160,10 @@ pub fn render_view(
             overlays.push(overlay);
         }
 
+        if let Some(overlay) = Self::doc_conflict_highlights(doc, theme) {
+            overlays.push(overlay);
+        }
+
         Self::doc_diagnostics_highlights_into(doc, theme, &mut overlays);
 
         if is_focused {
@@ -217,6 +231,41 @@ pub fn render_view(
             ));
         }
 
+        if is_focused && config.inline_blame.enable {
+            if let Some(blame) = text_decorations::InlineBlame::new(
+                doc,
+                primary_cursor,
+                &config.inline_blame.format,
+                theme,
+            ) {
+                decorations.add_decoration(blame);
+            }
+        }
+
+        if config.lsp.display_code_lenses && !doc.code_lenses.is_empty() {
+            decorations.add_decoration(text_decorations::CodeLenses::new(doc, view.id, theme));
+        }
+
+        if let Some(inline_completion) = doc
+            .inline_completion
+            .as_ref()
+            .filter(|inline_completion| inline_completion.view_id == view.id)
+        {
+            if !inline_completion.ghost_lines.is_empty() {
+                decorations.add_decoration(text_decorations::InlineCompletionLines::new(
+                    inline_completion,
+                    theme,
+                ));
+            }
+        }
+
+        if let Some((hunk, anchor)) = view.hunk_diff(doc) {
+            if let Some(hunk_diff) = text_decorations::HunkDiffLines::new(doc, &hunk, anchor, theme)
+            {
+                decorations.add_decoration(hunk_diff);
+            }
+        }
+
         render_document(
             surface,
             inner,
@@ -359,6 +408,60 @@ pub fn doc_rainbow_highlights(
         Some(syntax.rainbow_highlights(text, theme.rainbow_length(), loader, range))
     }
 
+    /// Get highlight spans for LSP semantic tokens. Token types and each token modifier are
+    /// separate overlays so that the styles of modifiers are patched over the styles of types.
+    pub fn doc_semantic_token_highlights_into(
+        doc: &Document,
+        anchor: usize,
+        height: u16,
+        theme: &Theme,
+        overlay_highlights: &mut Vec<OverlayHighlights>,
+    ) {
+        let Some(semantic_tokens) = &doc.semantic_tokens else {
+            return;
+        };
+        let text = doc.text().slice(..);
+        let row = text.char_to_line(anchor.min(text.len_chars()));
+        let visible_range = Self::viewport_byte_range(text, row, height);
+        let visible_range =
+            text.byte_to_char(visible_range.start)..text.byte_to_char(visible_range.end);
+        let tokens = &semantic_tokens.tokens;
+        let start = tokens.partition_point(|token| token.end <= visible_range.start);
+        let end = tokens.partition_point(|token| token.start < visible_range.end);
+        let tokens = &tokens[start..end.max(start)];
+
+        let type_highlights: Vec<_> = semantic_tokens
+            .type_scopes
+            .iter()
+            .map(|scope| theme.find_highlight(scope))
+            .collect();
+        let highlights: Vec<_> = tokens
+            .iter()
+            .filter(|token| token.start < token.end)
+            .filter_map(|token| {
+                let highlight = (*type_highlights.get(token.token_type as usize)?)?;
+                Some((highlight, token.start..token.end))
+            })
+            .collect();
+        if !highlights.is_empty() {
+            overlay_highlights.push(OverlayHighlights::Heterogenous { highlights });
+        }
+
+        for (bit, scope) in semantic_tokens.modifier_scopes.iter().enumerate().take(32) {
+            let Some(highlight) = theme.find_highlight(scope) else {
+                continue;
+            };
+            let ranges: Vec<_> = tokens
+                .iter()
+                .filter(|token| token.start < token.end && token.modifiers & (1 << bit) != 0)
+                .map(|token| token.start..token.end)
+                .collect();
+            if !ranges.is_empty() {
+                overlay_highlights.push(OverlayHighlights::Homogeneous { highlight, ranges });
+            }
+        }
+    }
+
     /// Get highlight spans for document diagnostics
     pub fn doc_diagnostics_highlights_into(
         doc: &Document,
@@ -507,6 +610,47 @@ pub fn doc_document_highlights(
         })
     }
 
+    /// Highlights the versions of the lines of git merge conflicts and their markers.
+    pub fn doc_conflict_highlights(doc: &Document, theme: &Theme) -> Option<OverlayHighlights> {
+        if doc.conflicts.is_empty() {
+            return None;
+        }
+
+        let get_scope_of = |scope, fallback| {
+            theme
+                .find_highlight_exact(scope)
+                .or_else(|| theme.find_highlight_exact(fallback))
+        };
+        let marker = get_scope_of("ui.conflict.marker", "diff.delta.conflict");
+        let ours = get_scope_of("ui.conflict.ours", "diff.plus");
+        let base = get_scope_of("ui.conflict.base", "diff.minus");
+        let theirs = get_scope_of("ui.conflict.theirs", "diff.delta");
+
+        let text = doc.text().slice(..);
+        let mut highlights = Vec::new();
+        let mut push = |highlight: Option<syntax::Highlight>, lines: ops::Range<usize>| {
+            if let Some(highlight) = highlight.filter(|_| !lines.is_empty()) {
+                highlights.push((
+                    highlight,
+                    text.line_to_char(lines.start)..text.line_to_char(lines.end),
+                ));
+            }
+        };
+        for conflict in &doc.conflicts {
+            push(marker, conflict.start..conflict.start + 1);
+            push(ours, conflict.ours());
+            if let (Some(line), Some(lines)) = (conflict.base, conflict.base_lines()) {
+                push(marker, line..line + 1);
+                push(base, lines);
+            }
+            push(marker, conflict.separator..conflict.separator + 1);
+            push(theirs, conflict.theirs());
+            push(marker, conflict.end..conflict.end + 1);
+        }
+
+        Some(OverlayHighlights::Heterogenous { highlights })
+    }
+
     pub fn doc_document_link_highlights(
         doc: &Document,
         theme: &Theme,
@@ -1064,6 +1208,7 @@ fn handle_keymap_event(
         event: KeyEvent,
     ) -> Option<KeymapResult> {
         let mut last_mode = mode;
+        self.keymaps.set_language(doc!(cxt.editor).language_name());
         self.pseudo_pending.extend(self.keymaps.pending());
         let key_result = self.keymaps.get(mode, event);
         cxt.editor.autoinfo = self.keymaps.sticky().map(|node| node.infobox());
@@ -1290,6 +1435,7 @@ pub fn clear_completion(&mut self, editor: &mut Editor) -> Option<OnKeyCallback>
 
     pub fn handle_idle_timeout(&mut self, cx: &mut commands::Context) -> EventResult {
         commands::compute_inlay_hints_for_all_views(cx.editor, cx.jobs);
+        crate::handlers::code_lens::resolve_visible_code_lenses(cx.editor);
 
         EventResult::Ignored(None)
     }
@@ -1766,6 +1912,13 @@ fn render(&mut self, area: Rect, surface: &mut Surface, cx: &mut Context) {
         if use_bufferline {
             editor_area = editor_area.clip_top(1);
         }
+        // The debug panels dock above the status message row.
+        let docked_height = area.height.saturating_sub(1);
+        let docked = cx.editor.debug_panel.height(docked_height)
+            + cx.editor.debug_console.height(docked_height);
+        if docked > 0 {
+            editor_area = editor_area.clip_bottom(docked + 1);
+        }
 
         // if the terminal size suddenly changed, we need to trigger a resize
         cx.editor.resize(editor_area);
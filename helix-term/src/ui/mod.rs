@@ -1,4 +1,6 @@
 mod completion;
+pub mod debug_console;
+pub mod debug_panel;
 mod document;
 pub(crate) mod editor;
 mod info;
@@ -19,6 +21,8 @@
 use crate::filter_picker_entry;
 use crate::job::{self, Callback};
 pub use completion::Completion;
+pub use debug_console::DebugConsole;
+pub use debug_panel::DebugPanel;
 pub use editor::EditorView;
 use helix_stdx::rope;
 use helix_view::theme::Style;
@@ -34,7 +38,10 @@
 use helix_view::Editor;
 use tui::text::{Span, Spans};
 
+use arc_swap::ArcSwapOption;
+use helix_vcs::{FileStatus, RepoStatus};
 use std::path::Path;
+use std::sync::Arc;
 use std::{error::Error, path::PathBuf};
 
 struct Utf8PathBuf {
@@ -210,10 +217,71 @@ fn get_excluded_types() -> ignore::types::Types {
         .expect("failed to build excluded_types")
 }
 
+/// The git statuses of the files listed by the file picker or file explorer, which are
+/// collected in the background.
+#[derive(Debug)]
+pub struct FileStatuses {
+    statuses: Arc<ArcSwapOption<RepoStatus>>,
+    untracked: Style,
+    modified: Style,
+    conflict: Style,
+    renamed: Style,
+    ignored: Style,
+}
+
+impl FileStatuses {
+    /// Shows the statuses cached for the repository containing `root` until they have been
+    /// collected again. Nothing is shown if `enable` is false.
+    fn new(editor: &Editor, root: &Path, enable: bool) -> Self {
+        let statuses = Arc::new(ArcSwapOption::empty());
+        if enable {
+            statuses.store(editor.diff_providers.cached_file_statuses(root));
+            let trust_full = editor
+                .workspace_trust
+                .query(
+                    &helix_loader::find_workspace_in(root).0,
+                    helix_loader::workspace_trust::TrustQuery::Git,
+                )
+                .is_trusted();
+            let statuses = statuses.clone();
+            editor.diff_providers.refresh_file_statuses(
+                root.to_path_buf(),
+                trust_full,
+                move |new| {
+                    statuses.store(Some(new));
+                    helix_event::request_redraw();
+                },
+            );
+        }
+        let theme = &editor.theme;
+        FileStatuses {
+            statuses,
+            untracked: theme.get("diff.plus"),
+            modified: theme.get("diff.delta"),
+            conflict: theme.get("diff.delta.conflict"),
+            renamed: theme.get("diff.delta.moved"),
+            ignored: theme.get("ui.text.inactive"),
+        }
+    }
+
+    fn style(&self, path: &Path) -> Style {
+        let statuses = self.statuses.load();
+        match statuses.as_ref().and_then(|statuses| statuses.get(path)) {
+            Some(FileStatus::Untracked) => self.untracked,
+            Some(FileStatus::Modified) => self.modified,
+            Some(FileStatus::Conflict) => self.conflict,
+            Some(FileStatus::Renamed) => self.renamed,
+            Some(FileStatus::Ignored) => self.ignored,
+            None => Style::default(),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct FilePickerData {
     root: PathBuf,
     directory_style: Style,
+    statuses: FileStatuses,
 }
 type FilePicker = Picker<PathBuf, FilePickerData>;
 
@@ -225,6 +293,7 @@ pub fn file_picker(editor: &Editor, root: PathBuf) -> FilePicker {
     let data = FilePickerData {
         root: root.clone(),
         directory_style: editor.theme.get("ui.text.directory"),
+        statuses: FileStatuses::new(editor, &root, config.file_picker.git_status),
     };
 
     let now = Instant::now();
@@ -273,7 +342,7 @@ pub fn file_picker(editor: &Editor, root: PathBuf) -> FilePicker {
                 .file_name()
                 .expect("normalized paths can't end in `..`")
                 .to_string_lossy();
-            spans.push(Span::raw(filename));
+            spans.push(Span::styled(filename, data.statuses.style(item)));
             Spans::from(spans).into()
         },
     )];
@@ -313,20 +382,23 @@ pub fn file_picker(editor: &Editor, root: PathBuf) -> FilePicker {
     picker
 }
 
-type FileExplorer = Picker<(PathBuf, bool), (PathBuf, Style)>;
+type FileExplorer = Picker<(PathBuf, bool), (PathBuf, Style, FileStatuses)>;
 
 pub fn file_explorer(root: PathBuf, editor: &Editor) -> Result<FileExplorer, std::io::Error> {
     let directory_style = editor.theme.get("ui.text.directory");
     let directory_content = directory_content(&root, editor)?;
+    let statuses = FileStatuses::new(editor, &root, editor.config().file_explorer.git_status);
 
     let columns = [PickerColumn::new(
         "path",
-        |(path, is_dir): &(PathBuf, bool), (root, directory_style): &(PathBuf, Style)| {
+        |(path, is_dir): &(PathBuf, bool),
+         (root, directory_style, statuses): &(PathBuf, Style, FileStatuses)| {
             let name = path.strip_prefix(root).unwrap_or(path).to_string_lossy();
+            let style = statuses.style(path);
             if *is_dir {
-                Span::styled(format!("{}/", name), *directory_style).into()
+                Span::styled(format!("{}/", name), directory_style.patch(style)).into()
             } else {
-                name.into()
+                Span::styled(name, style).into()
             }
         },
     )];
@@ -334,7 +406,7 @@ pub fn file_explorer(root: PathBuf, editor: &Editor) -> Result<FileExplorer, std
         columns,
         0,
         directory_content,
-        (root, directory_style),
+        (root, directory_style, statuses),
         move |cx, (path, is_dir): &(PathBuf, bool), action| {
             if *is_dir {
                 let new_root = helix_stdx::path::normalize(path);
@@ -470,6 +542,13 @@ pub fn theme(_editor: &Editor, input: &str) -> Vec<Completion> {
             .collect()
     }
 
+    pub fn session(_editor: &Editor, input: &str) -> Vec<Completion> {
+        fuzzy_match(input, helix_view::session::Session::list(), false)
+            .into_iter()
+            .map(|(name, _)| ((0..), name.into()))
+            .collect()
+    }
+
     /// Recursive function to get all keys from this value and add them to vec
     fn get_keys(value: &serde_json::Value, vec: &mut Vec<String>, scope: Option<&str>) {
         if let Some(map) = value.as_object() {
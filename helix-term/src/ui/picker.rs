@@ -64,6 +64,8 @@
 pub enum PathOrId<'a> {
     Id(DocumentId),
     Path(&'a Path),
+    /// Plain text, e.g. the contents of a register.
+    Text(&'a str),
 }
 
 impl<'a> From<&'a Path> for PathOrId<'a> {
@@ -96,6 +98,7 @@ pub enum CachedPreview {
 pub enum Preview<'picker, 'editor> {
     Cached(&'picker CachedPreview),
     EditorDocument(&'editor Document),
+    Text(String),
 }
 
 impl Preview<'_, '_> {
@@ -114,10 +117,18 @@ fn dir_content(&self) -> Option<&Vec<(String, bool)>> {
         }
     }
 
+    fn text(&self) -> Option<&str> {
+        match self {
+            Preview::Text(text) => Some(text),
+            _ => None,
+        }
+    }
+
     /// Alternate text to show for the preview.
     fn placeholder(&self) -> &str {
         match *self {
             Self::EditorDocument(_) => "<Invalid file location>",
+            Self::Text(_) => "<Empty>",
             Self::Cached(preview) => match preview {
                 CachedPreview::Document(_) => "<Invalid file location>",
                 CachedPreview::Directory(_) => "<Invalid directory location>",
@@ -700,6 +711,7 @@ fn get_preview<'picker, 'editor>(
                 let doc = editor.documents.get(&id).unwrap();
                 Some((Preview::EditorDocument(doc), range))
             }
+            PathOrId::Text(text) => Some((Preview::Text(text.to_owned()), range)),
         }
     }
 
@@ -983,6 +995,21 @@ fn render_preview(&mut self, area: Rect, surface: &mut Surface, cx: &mut Context
                         return;
                     }
 
+                    if let Some(preview_text) = preview.text().filter(|text| !text.is_empty()) {
+                        for (i, line) in
+                            preview_text.lines().take(inner.height as usize).enumerate()
+                        {
+                            surface.set_stringn(
+                                inner.x,
+                                inner.y + i as u16,
+                                &line.replace('\t', "    "),
+                                inner.width as usize,
+                                text,
+                            );
+                        }
+                        return;
+                    }
+
                     let alt_text = preview.placeholder();
                     let x = inner.x + inner.width.saturating_sub(alt_text.len() as u16) / 2;
                     let y = inner.y + inner.height / 2;
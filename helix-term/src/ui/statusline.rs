@@ -1,6 +1,7 @@
 use helix_core::indent::IndentStyle;
 use helix_core::{coords_at_pos, encoding, unicode::width::UnicodeWidthStr, Position};
 use helix_lsp::lsp::DiagnosticSeverity;
+use helix_vcs::DiffBase;
 use helix_view::document::DEFAULT_LANGUAGE_NAME;
 use helix_view::{
     document::{Mode, SearchMatch, SearchMatchLimit, SCRATCH_BUFFER_NAME},
@@ -156,6 +157,7 @@ fn get_render_function<'a, F>(element_id: StatusLineElementID) -> impl Fn(&mut R
         helix_view::editor::StatusLineElement::Separator => render_separator,
         helix_view::editor::StatusLineElement::Spacer => render_spacer,
         helix_view::editor::StatusLineElement::VersionControl => render_version_control,
+        helix_view::editor::StatusLineElement::DiffBase => render_diff_base,
         helix_view::editor::StatusLineElement::Register => render_register,
         helix_view::editor::StatusLineElement::CurrentWorkingDirectory => render_cwd,
         helix_view::editor::StatusLineElement::CodeActionHint => render_code_action_hint,
@@ -606,6 +608,23 @@ fn render_version_control<'a, F>(context: &mut RenderContext<'a>, write: F)
     write(context, head.into());
 }
 
+fn render_diff_base<'a, F>(context: &mut RenderContext<'a>, write: F)
+where
+    F: Fn(&mut RenderContext<'a>, Span<'a>) + Copy,
+{
+    if context.doc.diff_handle().is_none() {
+        return;
+    }
+    let base = match &context.doc.diff_base_override {
+        Some(base) => base.to_string(),
+        None => match context.doc.config.load().diff_base {
+            DiffBase::Head => "HEAD".to_string(),
+            DiffBase::Index => "index".to_string(),
+        },
+    };
+    write(context, format!(" diff:{base} ").into());
+}
+
 fn render_register<'a, F>(context: &mut RenderContext<'a>, write: F)
 where
     F: Fn(&mut RenderContext<'a>, Span<'a>) + Copy,
@@ -6,9 +6,17 @@
 
 use crate::ui::document::{LinePos, TextRenderer};
 
+pub use blame::InlineBlame;
+pub use code_lens::CodeLenses;
 pub use diagnostics::InlineDiagnostics;
+pub use hunk_diff::HunkDiffLines;
+pub use inline_completion::InlineCompletionLines;
 
+mod blame;
+mod code_lens;
 mod diagnostics;
+mod hunk_diff;
+mod inline_completion;
 
 /// Decorations are the primary mechanism for extending the text rendering.
 ///
@@ -0,0 +1,97 @@
+use helix_core::doc_formatter::FormattedGrapheme;
+use helix_core::line_ending::line_end_char_index;
+use helix_core::Position;
+use helix_vcs::LineBlame;
+use helix_view::theme::Style;
+use helix_view::{Document, Theme};
+
+use crate::ui::document::{LinePos, TextRenderer};
+use crate::ui::text_decorations::Decoration;
+
+/// The number of columns between the end of the line and the blame.
+const PADDING: usize = 4;
+
+/// Renders the git blame of the cursor line after its end.
+pub struct InlineBlame {
+    /// The char index of the end of the cursor line.
+    anchor: usize,
+    pending: bool,
+    text: String,
+    style: Style,
+}
+
+impl InlineBlame {
+    /// Returns `None` if the blame of the document hasn't been loaded.
+    pub fn new(doc: &Document, cursor: usize, format: &str, theme: &Theme) -> Option<Self> {
+        let text = doc.text().slice(..);
+        let line = text.char_to_line(cursor);
+        let anchor = line_end_char_index(&text, line);
+        let text = match doc.line_blame(line)? {
+            LineBlame::Committed(commit) => format
+                .replace("{author}", &commit.author)
+                .replace("{email}", &commit.email)
+                .replace("{date}", &commit.date)
+                .replace("{commit}", &commit.id)
+                .replace("{summary}", &commit.summary),
+            LineBlame::Uncommitted => "Not committed yet".to_string(),
+        };
+        Some(InlineBlame {
+            anchor,
+            pending: false,
+            text,
+            style: theme.get("ui.virtual.blame"),
+        })
+    }
+}
+
+impl Decoration for InlineBlame {
+    fn reset_pos(&mut self, pos: usize) -> usize {
+        self.pending = false;
+        if pos <= self.anchor {
+            self.anchor
+        } else {
+            usize::MAX
+        }
+    }
+
+    fn decorate_grapheme(
+        &mut self,
+        _renderer: &mut TextRenderer,
+        grapheme: &FormattedGrapheme,
+    ) -> usize {
+        if grapheme.char_idx == self.anchor {
+            self.pending = true;
+        }
+        usize::MAX
+    }
+
+    fn render_virt_lines(
+        &mut self,
+        renderer: &mut TextRenderer,
+        pos: LinePos,
+        virt_off: Position,
+    ) -> Position {
+        if !std::mem::take(&mut self.pending) {
+            return Position::new(0, 0);
+        }
+        let col = virt_off.col + PADDING;
+        if !renderer.column_in_bounds(col, 1) {
+            return Position::new(0, 0);
+        }
+
+        let draw_col = (col - renderer.offset.col) as u16;
+        let (end_col, _) = renderer.set_string_truncated(
+            renderer.viewport.x + draw_col,
+            pos.visual_line,
+            &self.text,
+            renderer.viewport.width.saturating_sub(draw_col) as usize,
+            |_| self.style,
+            true,
+            false,
+        );
+        Position::new(
+            0,
+            (end_col - renderer.viewport.x) as usize + renderer.offset.col - virt_off.col,
+        )
+    }
+}
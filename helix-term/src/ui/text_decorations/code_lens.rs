@@ -0,0 +1,99 @@
+use helix_core::doc_formatter::FormattedGrapheme;
+use helix_core::Position;
+use helix_view::annotations::code_lens::{code_lens_lines, CodeLensLine};
+use helix_view::theme::Style;
+use helix_view::{Document, Theme, ViewId};
+
+use crate::ui::document::{LinePos, TextRenderer};
+use crate::ui::text_decorations::Decoration;
+
+/// Renders the code lenses of a document into the virtual lines reserved by
+/// [`helix_view::annotations::code_lens::CodeLenses`].
+pub struct CodeLenses {
+    /// The code lens lines and the indentation (in columns) of the line they belong to.
+    lines: Vec<(CodeLensLine, usize)>,
+    idx: usize,
+    pending: Vec<usize>,
+    style: Style,
+}
+
+impl CodeLenses {
+    pub fn new(doc: &Document, view_id: ViewId, theme: &Theme) -> Self {
+        let text = doc.text().slice(..);
+        let tab_width = doc.tab_width();
+        let lines = code_lens_lines(doc, view_id)
+            .into_iter()
+            .map(|line| {
+                let indent = text
+                    .line(line.line)
+                    .chars()
+                    .take_while(|&ch| ch == ' ' || ch == '\t')
+                    .map(|ch| if ch == '\t' { tab_width } else { 1 })
+                    .sum();
+                (line, indent)
+            })
+            .collect();
+        CodeLenses {
+            lines,
+            idx: 0,
+            pending: Vec::new(),
+            style: theme.get("ui.virtual.code-lens"),
+        }
+    }
+
+    fn next_anchor(&self) -> usize {
+        self.lines
+            .get(self.idx)
+            .map_or(usize::MAX, |(line, _)| line.anchor)
+    }
+}
+
+impl Decoration for CodeLenses {
+    fn reset_pos(&mut self, pos: usize) -> usize {
+        self.idx = self.lines.partition_point(|(line, _)| line.anchor < pos);
+        self.pending.clear();
+        self.next_anchor()
+    }
+
+    fn decorate_grapheme(
+        &mut self,
+        _renderer: &mut TextRenderer,
+        grapheme: &FormattedGrapheme,
+    ) -> usize {
+        while self.next_anchor() == grapheme.char_idx {
+            self.pending.push(self.idx);
+            self.idx += 1;
+        }
+        self.next_anchor()
+    }
+
+    fn render_virt_lines(
+        &mut self,
+        renderer: &mut TextRenderer,
+        pos: LinePos,
+        virt_off: Position,
+    ) -> Position {
+        let height = self.pending.len();
+        for (i, idx) in self.pending.drain(..).enumerate() {
+            let (line, indent) = &self.lines[idx];
+            let row = pos.visual_line + (virt_off.row + i) as u16;
+            if (row as usize) < renderer.offset.row
+                || row as usize - renderer.offset.row >= renderer.viewport.height as usize
+            {
+                continue;
+            }
+            let col = indent.saturating_sub(renderer.offset.col);
+            let width = (renderer.viewport.width as usize).saturating_sub(col);
+            renderer.set_string_truncated(
+                renderer.viewport.x + col as u16,
+                row,
+                &line.text,
+                width,
+                |_| self.style,
+                true,
+                false,
+            );
+        }
+        Position::new(height, 0)
+    }
+}
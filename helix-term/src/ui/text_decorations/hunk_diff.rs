@@ -0,0 +1,163 @@
+use std::ops::Range;
+
+use helix_core::doc_formatter::FormattedGrapheme;
+use helix_core::Position;
+use helix_vcs::Hunk;
+use helix_view::graphics::Modifier;
+use helix_view::theme::Style;
+use helix_view::{Document, Theme};
+
+use crate::ui::document::{LinePos, TextRenderer};
+use crate::ui::text_decorations::Decoration;
+
+/// Renders the lines of the diff base that a change replaced into the virtual lines reserved by
+/// [`helix_view::annotations::hunk_diff::HunkDiffLines`], with the words that were changed
+/// highlighted.
+pub struct HunkDiffLines {
+    /// The lines of the diff base without their line endings and the byte ranges of their
+    /// changed words.
+    lines: Vec<(String, Vec<Range<usize>>)>,
+    anchor: usize,
+    pending: bool,
+    tab_width: usize,
+    style: Style,
+    word_style: Style,
+}
+
+impl HunkDiffLines {
+    /// Returns `None` if the document isn't diffed.
+    pub fn new(doc: &Document, hunk: &Hunk, anchor: usize, theme: &Theme) -> Option<Self> {
+        let diff = doc.diff_handle()?.load();
+        let base = diff.diff_base();
+        let doc_text = diff.doc();
+        let before = base
+            .slice(base.line_to_char(hunk.before.start as usize)..)
+            .lines()
+            .take(hunk.before.len())
+            .map(String::from)
+            .collect::<Vec<_>>();
+        let after = doc_text
+            .slice(
+                doc_text.line_to_char(hunk.after.start as usize)
+                    ..doc_text.line_to_char(hunk.after.end as usize),
+            )
+            .to_string();
+        let mut words = helix_vcs::removed_words(&before.concat(), &after).into_iter();
+
+        let mut line_start = 0;
+        let mut word = words.next();
+        let lines = before
+            .into_iter()
+            .map(|line| {
+                let line_end = line_start + line.len();
+                let mut ranges = Vec::new();
+                while let Some(range) = word.clone() {
+                    if range.start >= line_end {
+                        break;
+                    }
+                    ranges.push(range.start - line_start..range.end.min(line_end) - line_start);
+                    if range.end > line_end {
+                        // The rest of the word continues on the next line.
+                        word = Some(line_end..range.end);
+                        break;
+                    }
+                    word = words.next();
+                }
+                line_start = line_end;
+                let content_len = line.trim_end_matches(['\n', '\r']).len();
+                let mut line = line;
+                line.truncate(content_len);
+                (line, ranges)
+            })
+            .collect();
+
+        let style = theme.get("diff.minus");
+        Some(HunkDiffLines {
+            lines,
+            anchor,
+            pending: false,
+            tab_width: doc.tab_width(),
+            style,
+            word_style: theme
+                .try_get_exact("diff.minus.word")
+                .unwrap_or_else(|| style.add_modifier(Modifier::REVERSED)),
+        })
+    }
+}
+
+impl Decoration for HunkDiffLines {
+    fn reset_pos(&mut self, pos: usize) -> usize {
+        self.pending = false;
+        if pos <= self.anchor {
+            self.anchor
+        } else {
+            usize::MAX
+        }
+    }
+
+    fn decorate_grapheme(
+        &mut self,
+        _renderer: &mut TextRenderer,
+        grapheme: &FormattedGrapheme,
+    ) -> usize {
+        if grapheme.char_idx == self.anchor {
+            self.pending = true;
+        }
+        usize::MAX
+    }
+
+    fn render_virt_lines(
+        &mut self,
+        renderer: &mut TextRenderer,
+        pos: LinePos,
+        virt_off: Position,
+    ) -> Position {
+        if !std::mem::take(&mut self.pending) {
+            return Position::new(0, 0);
+        }
+        for (i, (line, ranges)) in self.lines.iter().enumerate() {
+            let row = pos.visual_line + (virt_off.row + i) as u16;
+            if (row as usize) < renderer.offset.row
+                || row as usize - renderer.offset.row >= renderer.viewport.height as usize
+            {
+                continue;
+            }
+            // Expand tabs and skip the columns scrolled out of view, keeping track of which bytes
+            // of the rendered text belong to changed words.
+            let mut text = String::new();
+            let mut changed = Vec::new();
+            let mut col = 0;
+            for (byte, ch) in line.char_indices() {
+                let (ch, width) = if ch == '\t' {
+                    (' ', self.tab_width - col % self.tab_width)
+                } else {
+                    (ch, 1)
+                };
+                let is_changed = ranges.iter().any(|range| range.contains(&byte));
+                for _ in 0..width {
+                    if col >= renderer.offset.col {
+                        text.push(ch);
+                        changed.extend(std::iter::repeat_n(is_changed, ch.len_utf8()));
+                    }
+                    col += 1;
+                }
+            }
+            renderer.set_string_truncated(
+                renderer.viewport.x,
+                row,
+                &text,
+                renderer.viewport.width as usize,
+                |byte| {
+                    if changed.get(byte).copied().unwrap_or(false) {
+                        self.word_style
+                    } else {
+                        self.style
+                    }
+                },
+                true,
+                false,
+            );
+        }
+        Position::new(self.lines.len(), 0)
+    }
+}
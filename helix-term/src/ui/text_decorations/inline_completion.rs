@@ -0,0 +1,80 @@
+use helix_core::doc_formatter::FormattedGrapheme;
+use helix_core::Position;
+use helix_view::document::DocumentInlineCompletion;
+use helix_view::theme::Style;
+use helix_view::Theme;
+
+use crate::ui::document::{LinePos, TextRenderer};
+use crate::ui::text_decorations::Decoration;
+
+/// Renders the lines after the first line of the ghost text of an inline completion into the
+/// virtual lines reserved by [`helix_view::annotations::inline_completion::InlineCompletionLines`].
+pub struct InlineCompletionLines<'a> {
+    lines: &'a [String],
+    anchor: usize,
+    pending: bool,
+    style: Style,
+}
+
+impl<'a> InlineCompletionLines<'a> {
+    pub fn new(inline_completion: &'a DocumentInlineCompletion, theme: &Theme) -> Self {
+        InlineCompletionLines {
+            lines: &inline_completion.ghost_lines,
+            anchor: inline_completion.ghost_lines_anchor,
+            pending: false,
+            style: theme.get("ui.virtual.inline-completion"),
+        }
+    }
+}
+
+impl Decoration for InlineCompletionLines<'_> {
+    fn reset_pos(&mut self, pos: usize) -> usize {
+        self.pending = false;
+        if pos <= self.anchor {
+            self.anchor
+        } else {
+            usize::MAX
+        }
+    }
+
+    fn decorate_grapheme(
+        &mut self,
+        _renderer: &mut TextRenderer,
+        grapheme: &FormattedGrapheme,
+    ) -> usize {
+        if grapheme.char_idx == self.anchor {
+            self.pending = true;
+        }
+        usize::MAX
+    }
+
+    fn render_virt_lines(
+        &mut self,
+        renderer: &mut TextRenderer,
+        pos: LinePos,
+        virt_off: Position,
+    ) -> Position {
+        if !std::mem::take(&mut self.pending) {
+            return Position::new(0, 0);
+        }
+        for (i, line) in self.lines.iter().enumerate() {
+            let row = pos.visual_line + (virt_off.row + i) as u16;
+            if (row as usize) < renderer.offset.row
+                || row as usize - renderer.offset.row >= renderer.viewport.height as usize
+            {
+                continue;
+            }
+            let line: String = line.chars().skip(renderer.offset.col).collect();
+            renderer.set_string_truncated(
+                renderer.viewport.x,
+                row,
+                &line,
+                renderer.viewport.width as usize,
+                |_| self.style,
+                true,
+                false,
+            );
+        }
+        Position::new(self.lines.len(), 0)
+    }
+}
@@ -74,6 +74,44 @@ async fn variable_expansion() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn cursor_state_expansion() -> anyhow::Result<()> {
+    // Without a syntax tree the symbol under the cursor is the word under the cursor.
+    test_statusline(
+        "ihello world<esc>b:echo %{symbol_under_cursor}",
+        "world",
+        Severity::Info,
+    )
+    .await?;
+    // Variables without a value expand to an empty string.
+    test_statusline(
+        r#":echo "[%{diagnostic_message}|%{diagnostic_code}|%{current_function}]""#,
+        "[||]",
+        Severity::Info,
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn lsp_expansion() -> anyhow::Result<()> {
+    test_statusline(
+        r#":echo %lsp{workspace.command "arg"}"#,
+        "'echo': `workspace.command` is not supported for any language server",
+        Severity::Error,
+    )
+    .await?;
+    test_statusline(
+        r#":echo %lsp{workspace.command [}"#,
+        "'echo': failed to parse arguments: EOF while parsing a list at line 1 column 1",
+        Severity::Error,
+    )
+    .await?;
+
+    Ok(())
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn unicode_expansion() -> anyhow::Result<()> {
     test_statusline(r#":echo %u{20}"#, " ", Severity::Info).await?;
@@ -906,6 +906,117 @@ async fn macro_play_within_macro_record() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn recursive_macro_stops_at_failure() -> anyhow::Result<()> {
+    // * `/foo<ret>` select the first "foo"
+    // * `"qQcbar<esc>n"qqQ` record a macro to register 'q' which replaces the selection, selects
+    //   the next "foo" and then replays itself (register 'q' is still empty while recording)
+    // * `"qq` replay the macro, which replaces every "foo" until `n` finds no more matches
+    test((
+        indoc! {"\
+            #[x|]# foo foo foo
+        "},
+        r#"/foo<ret>"qQcbar<esc>n"qqQ"qq"#,
+        indoc! {"\
+            x bar bar bar#[
+            |]#"},
+    ))
+    .await?;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn replay_macro_until_no_change() -> anyhow::Result<()> {
+    let mut config = test_config();
+    helix_term::keymap::merge_keys(
+        &mut config.keys,
+        toml::from_str(r#"normal = { A-q = "replay_macro_until" }"#)?,
+    );
+
+    // `"qQxQ` records a macro to register 'q' which extends the selection by a line, `"q<A-q>`
+    // replays it until the selection covers the whole buffer and stops changing.
+    test_with_config(
+        AppBuilder::new().with_config(config),
+        (
+            indoc! {"\
+                #[a|]#
+                b
+                c
+            "},
+            r#""qQxQ"q<A-q>"#,
+            indoc! {"\
+                #[a
+                b
+                c
+                |]#"},
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn static_command_args() -> anyhow::Result<()> {
+    let mut config = test_config();
+    helix_term::keymap::merge_keys(
+        &mut config.keys,
+        toml::from_str(
+            r#"normal = { A-g = "goto_line --count 2", A-w = "select_textobject_inner w", A-s = "surround_add '('" }"#,
+        )?,
+    );
+
+    // The bound arguments answer the pending-key prompts and replace the typed count.
+    test_with_config(
+        AppBuilder::new().with_config(config),
+        (
+            indoc! {"\
+                #[a|]# b c d
+                foo bar
+            "},
+            "<A-g>ww<A-w><A-s>",
+            indoc! {"\
+                a b c d
+                foo #[(bar)|]#
+            "},
+        ),
+    )
+    .await?;
+
+    // The bound count only applies to its own command.
+    let mut config = test_config();
+    helix_term::keymap::merge_keys(
+        &mut config.keys,
+        toml::from_str(r#"normal = { A-m = ["move_char_right --count 2", "move_char_right"] }"#)?,
+    );
+    test_with_config(
+        AppBuilder::new().with_config(config),
+        ("#[a|]#bcdef", "<A-m>", "abc#[d|]#ef"),
+    )
+    .await?;
+
+    // Keys left over once the command stops waiting for keys are reported.
+    let mut config = test_config();
+    helix_term::keymap::merge_keys(
+        &mut config.keys,
+        toml::from_str(r#"normal = { A-x = "move_char_right x" }"#)?,
+    );
+    test_key_sequence(
+        &mut AppBuilder::new().with_config(config).build()?,
+        Some("<A-x>"),
+        Some(&|app| {
+            let (status, &severity) = app.editor.get_status().unwrap();
+            assert_eq!(severity, helix_view::editor::Severity::Error);
+            assert_eq!(status, "move_char_right doesn't wait for the bound key x");
+        }),
+        false,
+    )
+    .await?;
+
+    Ok(())
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn global_search_with_multibyte_chars() -> anyhow::Result<()> {
     // Assert that `helix_term::commands::global_search` handles multibyte characters correctly.
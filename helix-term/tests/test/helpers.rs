@@ -11,7 +11,9 @@
 use helix_term::{application::Application, args::Args, config::Config, keymap::merge_keys};
 use helix_view::{
     current_ref, doc,
-    editor::{ImplicitTrustLevelConfig, LspConfig, WordCompletion, WorkspaceTrustConfig},
+    editor::{
+        ImplicitTrustLevelConfig, LspConfig, SessionConfig, WordCompletion, WorkspaceTrustConfig,
+    },
     input::parse_macro,
     Editor,
 };
@@ -317,6 +319,12 @@ pub fn test_editor_config() -> helix_view::editor::Config {
             enable: false,
             ..Default::default()
         },
+        // Keep the macros recorded by tests out of the cache directory, and the macros saved
+        // there out of the tests.
+        session: SessionConfig {
+            persist_macros: false,
+            ..Default::default()
+        },
         // Trust everything implicitly so tests don't hit popups.
         workspace_trust: WorkspaceTrustConfig {
             level: ImplicitTrustLevelConfig::Insecure,
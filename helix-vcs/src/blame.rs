@@ -0,0 +1,66 @@
+use crate::Diff;
+
+/// A commit that last changed some lines of a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlameCommit {
+    /// The abbreviated commit id.
+    pub id: String,
+    pub author: String,
+    pub email: String,
+    /// The author time in seconds since the Unix epoch.
+    pub time: i64,
+    /// The author date formatted as `YYYY-MM-DD`.
+    pub date: String,
+    /// The first line of the commit message.
+    pub summary: String,
+}
+
+/// The blame of a line of a document that may have changed since `HEAD`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineBlame<'a> {
+    Committed(&'a BlameCommit),
+    /// The line was added or changed since `HEAD`.
+    Uncommitted,
+}
+
+/// The commit that last changed each line of a file as of `HEAD` or in the index.
+#[derive(Debug, Clone, Default)]
+pub struct FileBlame {
+    pub(crate) commits: Vec<BlameCommit>,
+    /// An index into `commits` for every line of the file, or `None` for lines that are not
+    /// committed yet.
+    pub(crate) lines: Vec<Option<u32>>,
+}
+
+impl FileBlame {
+    /// Returns the commit that last changed `line` (0-based) of the blamed file.
+    pub fn line(&self, line: usize) -> Option<&BlameCommit> {
+        let commit = (*self.lines.get(line)?)?;
+        self.commits.get(commit as usize)
+    }
+
+    /// Returns the blame of `line` (0-based) of the document that `diff` compares against the
+    /// blamed file. Without a diff the document is assumed to be unchanged.
+    pub fn line_blame(&self, line: usize, diff: Option<&Diff>) -> LineBlame<'_> {
+        let mut head_line = line;
+        if let Some(diff) = diff {
+            let line = line as u32;
+            for i in 0..diff.len() {
+                let hunk = diff.nth_hunk(i);
+                if line < hunk.after.start {
+                    break;
+                }
+                if line < hunk.after.end {
+                    return LineBlame::Uncommitted;
+                }
+                head_line = (line + hunk.before.end - hunk.after.end) as usize;
+            }
+        }
+        self.line(head_line)
+            .map_or(LineBlame::Uncommitted, LineBlame::Committed)
+    }
+
+    pub fn len_lines(&self) -> usize {
+        self.lines.len()
+    }
+}
@@ -12,8 +12,10 @@
 use crate::diff::worker::DiffWorker;
 
 pub use imara_diff::Hunk;
+pub use words::removed_words;
 
 mod line_cache;
+mod words;
 mod worker;
 
 /// A rendering lock passed to the differ the prevents redraws from occurring
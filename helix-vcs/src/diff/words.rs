@@ -0,0 +1,77 @@
+use std::ops::Range;
+
+use helix_core::chars::{categorize_char, CharCategory};
+use imara_diff::{Diff, InternedInput};
+
+use crate::diff::ALGORITHM;
+
+/// Splits `text` into runs of word characters, runs of whitespace and single other characters.
+fn words(text: &str) -> impl Iterator<Item = &str> {
+    let mut rest = text;
+    std::iter::from_fn(move || {
+        let mut chars = rest.char_indices();
+        let (_, first) = chars.next()?;
+        let category = categorize_char(first);
+        let len = match category {
+            CharCategory::Word | CharCategory::Whitespace => chars
+                .find(|&(_, ch)| categorize_char(ch) != category)
+                .map_or(rest.len(), |(i, _)| i),
+            _ => first.len_utf8(),
+        };
+        let (word, tail) = rest.split_at(len);
+        rest = tail;
+        Some(word)
+    })
+}
+
+/// Compares `before` and `after` word by word and returns the byte ranges of the words of
+/// `before` that were removed or changed. Nothing is returned if `after` is empty since all of
+/// `before` was removed then.
+pub fn removed_words(before: &str, after: &str) -> Vec<Range<usize>> {
+    if after.is_empty() {
+        return Vec::new();
+    }
+    let mut input = InternedInput::default();
+    input.update_before(words(before));
+    input.update_after(words(after));
+    let mut diff = Diff::compute(ALGORITHM, &input);
+    diff.postprocess_no_heuristic(&input);
+
+    let mut ranges: Vec<Range<usize>> = Vec::new();
+    let mut pos = 0;
+    for (i, &token) in input.before.iter().enumerate() {
+        let len = input.interner[token].len();
+        if diff.is_removed(i as u32) {
+            match ranges.last_mut() {
+                Some(range) if range.end == pos => range.end += len,
+                _ => ranges.push(pos..pos + len),
+            }
+        }
+        pos += len;
+    }
+    ranges
+}
+
+#[cfg(test)]
+mod test {
+    use super::removed_words;
+
+    #[test]
+    fn changed_words() {
+        let before = "let foo = bar(baz);\n";
+        let after = "let foo = qux(baz, 1);\n";
+        let ranges = removed_words(before, after);
+        let removed: Vec<_> = ranges.into_iter().map(|range| &before[range]).collect();
+        assert_eq!(removed, ["bar"]);
+    }
+
+    #[test]
+    fn adjacent_words_are_merged() {
+        let before = "a b c\n";
+        let after = "x\n";
+        let ranges = removed_words(before, after);
+        let removed: Vec<_> = ranges.into_iter().map(|range| &before[range]).collect();
+        assert_eq!(removed, ["a b c"]);
+        assert!(removed_words(before, "").is_empty());
+    }
+}
@@ -1,13 +1,15 @@
 use anyhow::{bail, Context, Result};
 use arc_swap::ArcSwap;
 use gix::filter::plumbing::driver::apply::Delay;
+use std::cell::RefCell;
 use std::io::Read;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use gix::bstr::ByteSlice;
 use gix::diff::Rewrites;
 use gix::dir::entry::Status;
+use gix::dir::walk::EmissionMode;
 use gix::objs::tree::EntryKind;
 use gix::sec::trust::DefaultForLevel;
 use gix::status::{
@@ -17,8 +19,10 @@
 };
 use gix::{Commit, ObjectId, Repository, ThreadSafeRepository};
 
-use crate::FileChange;
+use crate::{BlameCommit, FileBlame, FileChange, RepoStatus};
 
+mod blame;
+mod stage;
 #[cfg(test)]
 mod test;
 
@@ -40,8 +44,47 @@ pub fn get_diff_base(file: &Path, trust_full: bool) -> Result<Vec<u8>> {
         .to_thread_local();
     let head = repo.head_commit()?;
     let file_oid = find_file_in_commit(&repo, &head, &file)?;
+    read_file(&repo, &file, file_oid)
+}
+
+/// Like [`get_diff_base`] but returns the staged contents of the file instead.
+pub fn get_index_base(file: &Path, trust_full: bool) -> Result<Vec<u8>> {
+    debug_assert!(!file.exists() || file.is_file());
+    debug_assert!(file.is_absolute());
+    let file = gix::path::realpath(file).context("resolve symlinks")?;
+
+    let repo_dir = get_repo_dir(&file)?;
+    let repo = open_repo(repo_dir, trust_full)
+        .context("failed to open git repo")?
+        .to_thread_local();
+    let index = repo.open_index()?;
+    let entry = find_file_in_index(&repo, &index, &file)?;
+    read_file(&repo, &file, index.entries()[entry].id)
+}
 
-    let file_object = repo.find_object(file_oid)?;
+/// Like [`get_diff_base`] but returns the contents of the file in the commit `rev` resolves to,
+/// for example a branch, a tag or `HEAD~3`.
+pub fn get_revision_base(file: &Path, rev: &str, trust_full: bool) -> Result<Vec<u8>> {
+    debug_assert!(!file.exists() || file.is_file());
+    debug_assert!(file.is_absolute());
+    let file = gix::path::realpath(file).context("resolve symlinks")?;
+
+    let repo_dir = get_repo_dir(&file)?;
+    let repo = open_repo(repo_dir, trust_full)
+        .context("failed to open git repo")?
+        .to_thread_local();
+    let commit = repo
+        .rev_parse_single(rev)
+        .with_context(|| format!("invalid revision '{rev}'"))?
+        .object()?
+        .peel_to_commit()?;
+    let file_oid = find_file_in_commit(&repo, &commit, &file)?;
+    read_file(&repo, &file, file_oid)
+}
+
+/// Reads the contents of a file from the object database.
+fn read_file(repo: &Repository, file: &Path, id: ObjectId) -> Result<Vec<u8>> {
+    let file_object = repo.find_object(id)?;
     let data = file_object.detach().data;
     // Get the actual data that git would make out of the git object.
     // This will apply the user's git config or attributes like crlf conversions.
@@ -85,12 +128,203 @@ pub fn get_current_head_name(file: &Path, trust_full: bool) -> Result<Arc<ArcSwa
     Ok(Arc::new(ArcSwap::from_pointee(name.into_boxed_str())))
 }
 
+pub fn get_repo_root(dir: &Path, trust_full: bool) -> Result<PathBuf> {
+    let repo = open_repo(dir, trust_full)
+        .context("failed to open git repo")?
+        .to_thread_local();
+    Ok(repo
+        .workdir()
+        .context("repo has no worktree")?
+        .to_path_buf())
+}
+
+pub fn blame_file(file: &Path, trust_full: bool) -> Result<FileBlame> {
+    debug_assert!(!file.exists() || file.is_file());
+    debug_assert!(file.is_absolute());
+    let file = gix::path::realpath(file).context("resolve symlinks")?;
+
+    let repo_dir = get_repo_dir(&file)?;
+    let repo = open_repo(repo_dir, trust_full)
+        .context("failed to open git repo")?
+        .to_thread_local();
+    blame_head(&repo, &file)
+}
+
+/// Like [`blame_file`] but blames the staged contents of the file instead. Staged changes are
+/// not committed yet.
+pub fn blame_index(file: &Path, trust_full: bool) -> Result<FileBlame> {
+    debug_assert!(!file.exists() || file.is_file());
+    debug_assert!(file.is_absolute());
+    let file = gix::path::realpath(file).context("resolve symlinks")?;
+
+    let repo_dir = get_repo_dir(&file)?;
+    let repo = open_repo(repo_dir, trust_full)
+        .context("failed to open git repo")?
+        .to_thread_local();
+    let blame = blame_head(&repo, &file)?;
+    let committed = find_file_in_commit(&repo, &repo.head_commit()?, &file)?;
+    let index = repo.open_index()?;
+    let staged = index.entries()[find_file_in_index(&repo, &index, &file)?].id;
+    if staged == committed {
+        return Ok(blame);
+    }
+
+    let committed = repo.find_object(committed)?.detach().data;
+    let staged = repo.find_object(staged)?.detach().data;
+    Ok(blame::blame_staged(blame, &committed, &staged))
+}
+
+fn blame_head(repo: &Repository, file: &Path) -> Result<FileBlame> {
+    let mut commit = repo.head_commit()?;
+    let mut blob = find_file_in_commit(repo, &commit, file)?;
+    let mut data = repo.find_object(blob)?.detach().data;
+    let mut blamer = blame::Blamer::new(&data);
+
+    while !blamer.is_done() {
+        let mut parents = Vec::new();
+        for id in commit.parent_ids() {
+            let parent = id.object()?.try_into_commit()?;
+            let parent_blob = find_file_in_commit(repo, &parent, file).ok();
+            parents.push((parent, parent_blob));
+        }
+        // Like `git blame`, follow a parent with identical contents if there is one so that
+        // merges are not blamed for changes made on the merged branch. Otherwise, changes
+        // are compared against the first parent only.
+        let same = parents
+            .iter()
+            .position(|(_, parent_blob)| *parent_blob == Some(blob));
+        let Some((parent, parent_blob)) = parents.into_iter().nth(same.unwrap_or(0)) else {
+            // The root commit.
+            break;
+        };
+        // The file was added by `commit`.
+        let Some(parent_blob) = parent_blob else {
+            break;
+        };
+        if parent_blob != blob {
+            let parent_data = repo.find_object(parent_blob)?.detach().data;
+            blamer.blame_diff(&parent_data, &data, || blame_commit(&commit))?;
+            blob = parent_blob;
+            data = parent_data;
+        }
+        commit = parent;
+    }
+
+    blamer.finish(|| blame_commit(&commit))
+}
+
+fn blame_commit(commit: &Commit) -> Result<BlameCommit> {
+    let author = commit.author()?;
+    let time = author.time()?;
+    Ok(BlameCommit {
+        id: commit.id.to_hex_with_len(8).to_string(),
+        author: author.name.to_string(),
+        email: author.email.to_string(),
+        time: time.seconds,
+        date: time.format_or_unix(gix::date::time::format::SHORT),
+        summary: commit.message()?.summary().to_string(),
+    })
+}
+
+pub fn stage_hunks(
+    file: &Path,
+    doc: &[u8],
+    lines: &[(usize, usize)],
+    trust_full: bool,
+) -> Result<()> {
+    debug_assert!(file.is_absolute());
+    let file = gix::path::realpath(file).context("resolve symlinks")?;
+
+    let repo_dir = get_repo_dir(&file)?;
+    let repo = open_repo(repo_dir, trust_full)
+        .context("failed to open git repo")?
+        .to_thread_local();
+    let mut index = repo.open_index()?;
+    let entry = find_file_in_index(&repo, &index, &file)?;
+    let staged = read_file(&repo, &file, index.entries()[entry].id)?;
+
+    let contents = stage::apply_hunks(&staged, doc, |hunk| stage::intersects(&hunk.after, lines));
+    write_index_entry(&repo, &mut index, entry, &file, &contents)
+}
+
+pub fn unstage_hunks(
+    file: &Path,
+    doc: &[u8],
+    lines: &[(usize, usize)],
+    trust_full: bool,
+) -> Result<()> {
+    debug_assert!(file.is_absolute());
+    let file = gix::path::realpath(file).context("resolve symlinks")?;
+
+    let repo_dir = get_repo_dir(&file)?;
+    let repo = open_repo(repo_dir, trust_full)
+        .context("failed to open git repo")?
+        .to_thread_local();
+    let committed = find_file_in_commit(&repo, &repo.head_commit()?, &file)?;
+    let committed = read_file(&repo, &file, committed)?;
+    let mut index = repo.open_index()?;
+    let entry = find_file_in_index(&repo, &index, &file)?;
+    let staged = read_file(&repo, &file, index.entries()[entry].id)?;
+
+    // The staged hunks are relative to the staged file, not to the document.
+    let lines = stage::map_lines(&staged, doc, lines);
+    let contents = stage::apply_hunks(&committed, &staged, |hunk| {
+        !stage::intersects(&hunk.after, &lines)
+    });
+    write_index_entry(&repo, &mut index, entry, &file, &contents)
+}
+
+/// Writes `contents` as the new staged contents of the file of the index entry at `entry`.
+fn write_index_entry(
+    repo: &Repository,
+    index: &mut gix::index::File,
+    entry: usize,
+    file: &Path,
+    contents: &[u8],
+) -> Result<()> {
+    let repo_dir = repo.workdir().context("repo has no worktree")?;
+    let rela_path = file.strip_prefix(repo_dir)?;
+    let (mut pipeline, _) = repo.filter_pipeline(None)?;
+    let mut git_outcome = pipeline.convert_to_git(contents, rela_path, index)?;
+    let mut data = Vec::with_capacity(contents.len());
+    git_outcome.read_to_end(&mut data)?;
+    let id = repo.write_blob(&data)?.detach();
+
+    let entry = &mut index.entries_mut()[entry];
+    entry.id = id;
+    // The file in the worktree no longer matches the index. Clearing the stat data makes git
+    // compare the contents instead of assuming that the file is unchanged.
+    entry.stat = Default::default();
+    // The cached tree ids are out of date.
+    index.remove_tree();
+    index.write(Default::default())?;
+    Ok(())
+}
+
 pub fn for_each_changed_file(
     cwd: &Path,
     trust_full: bool,
     f: impl Fn(Result<FileChange>) -> bool,
 ) -> Result<()> {
-    status(&open_repo(cwd, trust_full)?.to_thread_local(), f)
+    status(&open_repo(cwd, trust_full)?.to_thread_local(), None, f)
+}
+
+/// Collects the statuses of all changed and ignored files of the repository containing `dir`.
+pub fn file_statuses(dir: &Path, trust_full: bool) -> Result<RepoStatus> {
+    let repo = open_repo(dir, trust_full)?.to_thread_local();
+    let root = repo
+        .workdir()
+        .context("repo has no worktree")?
+        .to_path_buf();
+    let statuses = RefCell::new(RepoStatus::new(root));
+    let on_ignored = |path| statuses.borrow_mut().insert_ignored(path);
+    status(&repo, Some(&on_ignored), |change| {
+        if let Ok(change) = change {
+            statuses.borrow_mut().insert(change);
+        }
+        true
+    })?;
+    Ok(statuses.into_inner())
 }
 
 fn open_repo(path: &Path, trust_full: bool) -> Result<ThreadSafeRepository> {
@@ -144,8 +378,13 @@ fn open_repo(path: &Path, trust_full: bool) -> Result<ThreadSafeRepository> {
     Ok(ThreadSafeRepository::open_opts(git_dir, options)?)
 }
 
-/// Emulates the result of running `git status` from the command line.
-fn status(repo: &Repository, f: impl Fn(Result<FileChange>) -> bool) -> Result<()> {
+/// Emulates the result of running `git status` from the command line. Ignored files are only
+/// reported, to `on_ignored`, if it is set, ignored directories as a whole.
+fn status(
+    repo: &Repository,
+    on_ignored: Option<&dyn Fn(PathBuf)>,
+    f: impl Fn(Result<FileChange>) -> bool,
+) -> Result<()> {
     let work_dir = repo
         .workdir()
         .ok_or_else(|| anyhow::anyhow!("working tree not found"))?
@@ -164,7 +403,10 @@ fn status(repo: &Repository, f: impl Fn(Result<FileChange>) -> bool) -> Result<(
             percentage: Some(0.5),
             limit: 1000,
             ..Default::default()
-        }));
+        }))
+        .dirwalk_options(|options| {
+            options.emit_ignored(on_ignored.is_some().then_some(EmissionMode::Matching))
+        });
 
     // No filtering based on path
     let empty_patterns = vec![];
@@ -201,6 +443,12 @@ fn status(repo: &Repository, f: impl Fn(Result<FileChange>) -> bool) -> Result<(
                     path: work_dir.join(entry.rela_path.to_path()?),
                 }
             }
+            Item::DirectoryContents { entry, .. } if matches!(entry.status, Status::Ignored(_)) => {
+                if let Some(on_ignored) = on_ignored {
+                    on_ignored(work_dir.join(entry.rela_path.to_path()?));
+                }
+                continue;
+            }
             Item::Rewrite {
                 source,
                 dirwalk_entry,
@@ -219,6 +467,21 @@ fn status(repo: &Repository, f: impl Fn(Result<FileChange>) -> bool) -> Result<(
     Ok(())
 }
 
+/// Finds the position of the unconflicted index entry of a file.
+fn find_file_in_index(repo: &Repository, index: &gix::index::State, file: &Path) -> Result<usize> {
+    let repo_dir = repo.workdir().context("repo has no worktree")?;
+    let rel_path = file.strip_prefix(repo_dir)?;
+    let rel_path = gix::path::to_unix_separators_on_windows(gix::path::try_into_bstr(rel_path)?);
+    let entry = index
+        .entry_index_by_path_and_stage(rel_path.as_ref(), gix::index::entry::Stage::Unconflicted)
+        .context("file is untracked")?;
+    let mode = index.entries()[entry].mode;
+    if mode != gix::index::entry::Mode::FILE && mode != gix::index::entry::Mode::FILE_EXECUTABLE {
+        bail!("entry at {} is not a file but a {mode:?}", file.display())
+    }
+    Ok(entry)
+}
+
 /// Finds the object that contains the contents of a file at a specific commit.
 fn find_file_in_commit(repo: &Repository, commit: &Commit, file: &Path) -> Result<ObjectId> {
     let repo_dir = repo.workdir().context("repo has no worktree")?;
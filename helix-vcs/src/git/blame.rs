@@ -0,0 +1,113 @@
+use anyhow::Result;
+use imara_diff::{sources::byte_lines, Algorithm, Diff, InternedInput};
+
+use crate::blame::{BlameCommit, FileBlame};
+
+/// Attributes lines of a file to the commits that introduced them while walking its history
+/// from `HEAD` backwards.
+pub(super) struct Blamer {
+    /// Lines whose commit is still unknown as `(line in the current revision, line at HEAD)`,
+    /// sorted by the line in the current revision.
+    pending: Vec<(u32, u32)>,
+    lines: Vec<Option<u32>>,
+    commits: Vec<BlameCommit>,
+}
+
+impl Blamer {
+    pub fn new(data: &[u8]) -> Self {
+        let len_lines = byte_lines(data).count() as u32;
+        Self {
+            pending: (0..len_lines).map(|line| (line, line)).collect(),
+            lines: vec![None; len_lines as usize],
+            commits: Vec::new(),
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Attributes the pending lines that differ between `parent` and `current` to the commit
+    /// that turned the former into the latter. The remaining lines are renumbered to their
+    /// position in `parent`.
+    pub fn blame_diff(
+        &mut self,
+        parent: &[u8],
+        current: &[u8],
+        commit: impl FnOnce() -> Result<BlameCommit>,
+    ) -> Result<()> {
+        let input = InternedInput::new(parent, current);
+        let mut diff = Diff::compute(Algorithm::Histogram, &input);
+        diff.postprocess_lines(&input);
+        let mut hunks = diff.hunks().peekable();
+
+        // The difference between the line number in `parent` and in `current` for lines
+        // after the hunks consumed so far.
+        let mut offset = 0i64;
+        let mut changed = Vec::new();
+        self.pending.retain_mut(|(line, blamed_line)| {
+            while let Some(hunk) = hunks.peek() {
+                if *line < hunk.after.start {
+                    break;
+                }
+                if *line < hunk.after.end {
+                    changed.push(*blamed_line);
+                    return false;
+                }
+                offset = hunk.before.end as i64 - hunk.after.end as i64;
+                hunks.next();
+            }
+            *line = (*line as i64 + offset) as u32;
+            true
+        });
+
+        if !changed.is_empty() {
+            let commit = self.push_commit(commit()?);
+            for line in changed {
+                self.lines[line as usize] = Some(commit);
+            }
+        }
+        Ok(())
+    }
+
+    /// Attributes all remaining lines to `commit`, which added the file.
+    pub fn finish(mut self, commit: impl FnOnce() -> Result<BlameCommit>) -> Result<FileBlame> {
+        if !self.pending.is_empty() {
+            let commit = self.push_commit(commit()?);
+            for (_, line) in std::mem::take(&mut self.pending) {
+                self.lines[line as usize] = Some(commit);
+            }
+        }
+        Ok(FileBlame {
+            commits: self.commits,
+            lines: self.lines,
+        })
+    }
+
+    fn push_commit(&mut self, commit: BlameCommit) -> u32 {
+        self.commits.push(commit);
+        self.commits.len() as u32 - 1
+    }
+}
+
+/// Turns the blame of the file at `HEAD` with the contents `committed` into the blame of the
+/// file in the index with the contents `staged`. Staged changes are not committed yet.
+pub(super) fn blame_staged(blame: FileBlame, committed: &[u8], staged: &[u8]) -> FileBlame {
+    let input = InternedInput::new(committed, staged);
+    let mut diff = Diff::compute(Algorithm::Histogram, &input);
+    diff.postprocess_lines(&input);
+
+    let mut lines = Vec::with_capacity(input.after.len());
+    let mut pos = 0;
+    for hunk in diff.hunks() {
+        lines.extend_from_slice(&blame.lines[pos as usize..hunk.before.start as usize]);
+        lines.extend((hunk.after.start..hunk.after.end).map(|_| None));
+        pos = hunk.before.end;
+    }
+    lines.extend_from_slice(&blame.lines[pos as usize..]);
+
+    FileBlame {
+        commits: blame.commits,
+        lines,
+    }
+}
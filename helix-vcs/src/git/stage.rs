@@ -0,0 +1,79 @@
+use std::ops::Range;
+
+use imara_diff::{Algorithm, Diff, Hunk, InternedInput};
+
+fn diff<'a>(before: &'a [u8], after: &'a [u8]) -> (InternedInput<&'a [u8]>, Vec<Hunk>) {
+    let input = InternedInput::new(before, after);
+    let mut diff = Diff::compute(Algorithm::Histogram, &input);
+    diff.postprocess_lines(&input);
+    let hunks = diff.hunks().collect();
+    (input, hunks)
+}
+
+/// Returns whether the lines of a hunk intersect any of the inclusive ranges of `lines`. A
+/// removal, which has no lines, counts as the line after it, where the diff gutter marks it.
+pub(super) fn intersects(hunk: &Range<u32>, lines: &[(usize, usize)]) -> bool {
+    let start = hunk.start as usize;
+    let end = hunk.end.max(hunk.start + 1) as usize;
+    lines
+        .iter()
+        .any(|&(first, last)| start <= last && first < end)
+}
+
+/// Returns `before` with the hunks of its diff to `after` that `apply` returns `true` for
+/// replaced by their contents in `after`.
+pub(super) fn apply_hunks(
+    before: &[u8],
+    after: &[u8],
+    mut apply: impl FnMut(&Hunk) -> bool,
+) -> Vec<u8> {
+    let (input, hunks) = diff(before, after);
+    let mut res = Vec::with_capacity(after.len());
+    let mut pos = 0;
+    for hunk in hunks.iter().filter(|hunk| apply(hunk)) {
+        for &token in &input.before[pos as usize..hunk.before.start as usize] {
+            res.extend_from_slice(input.interner[token]);
+        }
+        for &token in &input.after[hunk.after.start as usize..hunk.after.end as usize] {
+            res.extend_from_slice(input.interner[token]);
+        }
+        pos = hunk.before.end;
+    }
+    for &token in &input.before[pos as usize..] {
+        res.extend_from_slice(input.interner[token]);
+    }
+    res
+}
+
+/// Maps the inclusive ranges of `lines` of `after` to the lines of `before` they correspond to.
+/// Lines that were changed map to all lines of their hunk in `before`.
+pub(super) fn map_lines(
+    before: &[u8],
+    after: &[u8],
+    lines: &[(usize, usize)],
+) -> Vec<(usize, usize)> {
+    let (_, hunks) = diff(before, after);
+    let map_line = |line: usize| {
+        let mut offset = 0i64;
+        for hunk in &hunks {
+            if line < hunk.after.start as usize {
+                break;
+            }
+            if line < hunk.after.end as usize {
+                return (hunk.before.start as usize, hunk.before.end as usize);
+            }
+            offset = hunk.before.end as i64 - hunk.after.end as i64;
+        }
+        let line = (line as i64 + offset) as usize;
+        (line, line + 1)
+    };
+    lines
+        .iter()
+        .filter_map(|&(first, last)| {
+            let (start, _) = map_line(first);
+            let (_, end) = map_line(last);
+            // Lines that were added to the document have no counterpart in `before`.
+            (start < end).then(|| (start, end - 1))
+        })
+        .collect()
+}
@@ -2,7 +2,7 @@
 
 use tempfile::TempDir;
 
-use crate::git;
+use crate::{git, FileStatus};
 
 fn exec_git_cmd(args: &str, git_dir: &Path) {
     let res = Command::new("git")
@@ -156,3 +156,179 @@ fn symlink_to_git_repo() {
     assert_eq!(git::get_diff_base(&file_link, true).unwrap(), contents);
     assert_eq!(git::get_diff_base(&file, true).unwrap(), contents);
 }
+
+#[test]
+fn blame_untracked_file() {
+    let temp_git = empty_git_repo();
+    let file = temp_git.path().join("file.txt");
+    File::create(&file).unwrap().write_all(b"foo").unwrap();
+
+    assert!(git::blame_file(&file, true).is_err());
+}
+
+#[test]
+fn blame_modified_lines() {
+    let temp_git = empty_git_repo();
+    let file = temp_git.path().join("file.txt");
+    File::create(&file)
+        .unwrap()
+        .write_all(b"a\nb\nc\n")
+        .unwrap();
+    exec_git_cmd("add -A", temp_git.path());
+    exec_git_cmd("commit -m first", temp_git.path());
+    File::create(&file)
+        .unwrap()
+        .write_all(b"a\nB\nc\nd\n")
+        .unwrap();
+    exec_git_cmd("add -A", temp_git.path());
+    exec_git_cmd("commit -m second", temp_git.path());
+    // Changes that are not committed yet are not part of the blame.
+    File::create(&file).unwrap().write_all(b"x\n").unwrap();
+
+    let blame = git::blame_file(&file, true).unwrap();
+    assert_eq!(blame.len_lines(), 4);
+    let summaries: Vec<_> = (0..4)
+        .map(|line| blame.line(line).unwrap().summary.as_str())
+        .collect();
+    assert_eq!(summaries, ["first", "second", "first", "second"]);
+    let commit = blame.line(0).unwrap();
+    assert_eq!(commit.author, "author");
+    assert_eq!(commit.date, "2000-01-01");
+    assert_eq!(commit.id.len(), 8);
+}
+
+#[test]
+fn blame_through_unrelated_commits() {
+    let temp_git = empty_git_repo();
+    let file = temp_git.path().join("file.txt");
+    let other = temp_git.path().join("other.txt");
+    File::create(&file).unwrap().write_all(b"a\nb\n").unwrap();
+    exec_git_cmd("add -A", temp_git.path());
+    exec_git_cmd("commit -m first", temp_git.path());
+    File::create(&other).unwrap().write_all(b"foo").unwrap();
+    exec_git_cmd("add -A", temp_git.path());
+    exec_git_cmd("commit -m second", temp_git.path());
+
+    let blame = git::blame_file(&file, true).unwrap();
+    assert_eq!(blame.line(0).unwrap().summary, "first");
+    assert_eq!(blame.line(1).unwrap().summary, "first");
+    assert!(blame.line(2).is_none());
+}
+
+#[test]
+fn staged_file() {
+    let temp_git = empty_git_repo();
+    let file = temp_git.path().join("file.txt");
+    File::create(&file).unwrap().write_all(b"foo").unwrap();
+    create_commit(temp_git.path(), true);
+    File::create(&file).unwrap().write_all(b"bar").unwrap();
+    exec_git_cmd("add -A", temp_git.path());
+    File::create(&file).unwrap().write_all(b"baz").unwrap();
+
+    assert_eq!(git::get_index_base(&file, true).unwrap(), b"bar");
+    assert_eq!(git::get_diff_base(&file, true).unwrap(), b"foo");
+}
+
+#[test]
+fn revision_base() {
+    let temp_git = empty_git_repo();
+    let file = temp_git.path().join("file.txt");
+    File::create(&file).unwrap().write_all(b"foo").unwrap();
+    create_commit(temp_git.path(), true);
+    exec_git_cmd("tag v1", temp_git.path());
+    File::create(&file).unwrap().write_all(b"bar").unwrap();
+    create_commit(temp_git.path(), true);
+    File::create(&file).unwrap().write_all(b"baz").unwrap();
+
+    assert_eq!(git::get_revision_base(&file, "HEAD", true).unwrap(), b"bar");
+    assert_eq!(
+        git::get_revision_base(&file, "HEAD~1", true).unwrap(),
+        b"foo"
+    );
+    assert_eq!(git::get_revision_base(&file, "v1", true).unwrap(), b"foo");
+    assert!(git::get_revision_base(&file, "missing", true).is_err());
+}
+
+#[test]
+fn file_statuses() {
+    let temp_git = empty_git_repo();
+    let root = temp_git.path();
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    std::fs::create_dir_all(root.join("target/debug")).unwrap();
+    File::create(root.join(".gitignore"))
+        .unwrap()
+        .write_all(b"/target\n")
+        .unwrap();
+    File::create(root.join("src/lib.rs")).unwrap();
+    File::create(root.join("unchanged.txt")).unwrap();
+    create_commit(root, true);
+    File::create(root.join("src/lib.rs"))
+        .unwrap()
+        .write_all(b"foo")
+        .unwrap();
+    File::create(root.join("new.txt")).unwrap();
+    File::create(root.join("target/debug/helix")).unwrap();
+
+    let root = gix::path::realpath(root).unwrap();
+    let statuses = git::file_statuses(&root, true).unwrap();
+    let get = |path: &str| statuses.get(&root.join(path));
+    assert_eq!(get("src/lib.rs"), Some(FileStatus::Modified));
+    assert_eq!(get("src"), Some(FileStatus::Modified));
+    assert_eq!(get("new.txt"), Some(FileStatus::Untracked));
+    assert_eq!(get("target/debug/helix"), Some(FileStatus::Ignored));
+    assert_eq!(get("unchanged.txt"), None);
+}
+
+#[test]
+fn stage_and_unstage_hunks() {
+    let temp_git = empty_git_repo();
+    let file = temp_git.path().join("file.txt");
+    File::create(&file)
+        .unwrap()
+        .write_all(b"a\nb\nc\nd\ne\n")
+        .unwrap();
+    create_commit(temp_git.path(), true);
+    let doc = b"A\nb\nc\nd\nE\nf\n";
+    File::create(&file).unwrap().write_all(doc).unwrap();
+
+    git::stage_hunks(&file, doc, &[(0, 0)], true).unwrap();
+    assert_eq!(
+        git::get_index_base(&file, true).unwrap(),
+        b"A\nb\nc\nd\ne\n"
+    );
+    git::stage_hunks(&file, doc, &[(3, 4)], true).unwrap();
+    assert_eq!(git::get_index_base(&file, true).unwrap(), doc);
+
+    git::unstage_hunks(&file, doc, &[(0, 0)], true).unwrap();
+    assert_eq!(
+        git::get_index_base(&file, true).unwrap(),
+        b"a\nb\nc\nd\nE\nf\n"
+    );
+    // Git notices that the worktree still differs from the index.
+    let status = Command::new("git")
+        .arg("-C")
+        .arg(temp_git.path())
+        .args(["diff", "--quiet"])
+        .status()
+        .unwrap();
+    assert_eq!(status.code(), Some(1));
+}
+
+#[test]
+fn blame_staged_lines() {
+    let temp_git = empty_git_repo();
+    let file = temp_git.path().join("file.txt");
+    File::create(&file).unwrap().write_all(b"a\nb\n").unwrap();
+    create_commit(temp_git.path(), true);
+    File::create(&file)
+        .unwrap()
+        .write_all(b"x\na\nb\n")
+        .unwrap();
+    exec_git_cmd("add -A", temp_git.path());
+
+    let blame = git::blame_index(&file, true).unwrap();
+    assert_eq!(blame.len_lines(), 3);
+    assert!(blame.line(0).is_none());
+    assert_eq!(blame.line(1).unwrap().summary, "message");
+    assert_eq!(blame.line(2).unwrap().summary, "message");
+}
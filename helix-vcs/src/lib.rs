@@ -4,7 +4,10 @@
 
 use anyhow::{anyhow, bail, Result};
 use arc_swap::ArcSwap;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
 use std::{
+    collections::HashMap,
     path::{Path, PathBuf},
     sync::Arc,
 };
@@ -14,33 +17,55 @@
 
 mod diff;
 
-pub use diff::{DiffHandle, Hunk};
+pub use diff::{removed_words, Diff, DiffHandle, Hunk};
 
 mod status;
 
-pub use status::FileChange;
+pub use status::{FileChange, FileStatus, RepoStatus};
+
+mod blame;
+
+pub use blame::{BlameCommit, FileBlame, LineBlame};
+
+/// What the contents of a document are compared against.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DiffBase {
+    /// The file in the `HEAD` commit.
+    #[default]
+    Head,
+    /// The file in the index, so that staged changes are not shown as changes.
+    Index,
+}
 
 /// Contains all active diff providers. Diff providers are compiled in via features. Currently
 /// only `git` is supported.
 #[derive(Clone)]
 pub struct DiffProviderRegistry {
     providers: Vec<DiffProvider>,
+    /// The last statuses of the files of repositories by their root.
+    file_statuses: Arc<Mutex<HashMap<PathBuf, Arc<RepoStatus>>>>,
 }
 
 impl DiffProviderRegistry {
     /// Get the given file from the VCS. This provides the unedited document as a "base"
     /// for a diff to be created.
-    pub fn get_diff_base(&self, file: &Path, trust_full: bool) -> Option<Vec<u8>> {
-        self.providers
-            .iter()
-            .find_map(|provider| match provider.get_diff_base(file, trust_full) {
+    pub fn get_diff_base(&self, file: &Path, base: DiffBase, trust_full: bool) -> Option<Vec<u8>> {
+        self.providers.iter().find_map(|provider| {
+            match provider.get_diff_base(file, base, trust_full) {
                 Ok(res) => Some(res),
                 Err(err) => {
                     log::debug!("{err:#?}");
                     log::debug!("failed to open diff base for {}", file.display());
                     None
                 }
-            })
+            }
+        })
+    }
+
+    /// Get the given file as of the revision `rev`, for example a branch, a tag or `HEAD~3`.
+    pub fn get_revision_base(&self, file: &Path, rev: &str, trust_full: bool) -> Result<Vec<u8>> {
+        self.first_success(|provider| provider.get_revision_base(file, rev, trust_full))
     }
 
     /// Get the current name of the current [HEAD](https://stackoverflow.com/questions/2304087/what-is-head-in-git).
@@ -61,6 +86,116 @@ pub fn get_current_head_name(
         })
     }
 
+    /// Get the root of the worktree of the repository containing `dir`.
+    pub fn get_repo_root(&self, dir: &Path, trust_full: bool) -> Option<PathBuf> {
+        self.providers
+            .iter()
+            .find_map(|provider| match provider.get_repo_root(dir, trust_full) {
+                Ok(res) => Some(res),
+                Err(err) => {
+                    log::debug!("{err:#?}");
+                    log::debug!("failed to find the repository of {}", dir.display());
+                    None
+                }
+            })
+    }
+
+    /// Find the commit that last changed each line of `file` in `base`. This walks the history
+    /// of the file, so it should not be called from the main thread.
+    pub fn blame(&self, file: &Path, base: DiffBase, trust_full: bool) -> Option<FileBlame> {
+        self.providers
+            .iter()
+            .find_map(|provider| match provider.blame(file, base, trust_full) {
+                Ok(res) => Some(res),
+                Err(err) => {
+                    log::debug!("{err:#?}");
+                    log::debug!("failed to blame {}", file.display());
+                    None
+                }
+            })
+    }
+
+    /// Stage the changes of the hunks between the index and `doc`, the contents of `file` in
+    /// the worktree, that intersect any of the inclusive ranges of (0-based) `lines` of `doc`.
+    pub fn stage_hunks(
+        &self,
+        file: &Path,
+        doc: &[u8],
+        lines: &[(usize, usize)],
+        trust_full: bool,
+    ) -> Result<()> {
+        self.first_success(|provider| provider.stage_hunks(file, doc, lines, trust_full))
+    }
+
+    /// Revert the staged changes of the hunks between `HEAD` and the index that intersect any
+    /// of the inclusive ranges of (0-based) `lines` of `doc`, the contents of `file` in the
+    /// worktree.
+    pub fn unstage_hunks(
+        &self,
+        file: &Path,
+        doc: &[u8],
+        lines: &[(usize, usize)],
+        trust_full: bool,
+    ) -> Result<()> {
+        self.first_success(|provider| provider.unstage_hunks(file, doc, lines, trust_full))
+    }
+
+    /// Returns the result of the first provider that succeeds or the error of the first one.
+    fn first_success<T>(&self, f: impl Fn(&DiffProvider) -> Result<T>) -> Result<T> {
+        let mut error = None;
+        for provider in &self.providers {
+            match f(provider) {
+                Ok(res) => return Ok(res),
+                Err(err) => {
+                    error.get_or_insert(err);
+                }
+            }
+        }
+        Err(error.unwrap_or_else(|| anyhow!("no diff provider available")))
+    }
+
+    /// Returns the last statuses of the files of the repository containing `dir` collected by
+    /// [`Self::refresh_file_statuses`].
+    pub fn cached_file_statuses(&self, dir: &Path) -> Option<Arc<RepoStatus>> {
+        self.file_statuses
+            .lock()
+            .iter()
+            .filter(|(root, _)| dir.starts_with(root))
+            .max_by_key(|(root, _)| root.as_os_str().len())
+            .map(|(_, statuses)| statuses.clone())
+    }
+
+    /// Collects the statuses of the files of the repository containing `dir` in the background
+    /// and caches them. `on_done` is called with the statuses unless the directory isn't in a
+    /// repository.
+    pub fn refresh_file_statuses(
+        &self,
+        dir: PathBuf,
+        trust_full: bool,
+        on_done: impl FnOnce(Arc<RepoStatus>) + Send + 'static,
+    ) {
+        let registry = self.clone();
+        tokio::task::spawn_blocking(move || {
+            let statuses = registry.providers.iter().find_map(|provider| {
+                match provider.file_statuses(&dir, trust_full) {
+                    Ok(res) => Some(Arc::new(res)),
+                    Err(err) => {
+                        log::debug!("{err:#?}");
+                        log::debug!("failed to collect file statuses in {}", dir.display());
+                        None
+                    }
+                }
+            });
+            if let Some(statuses) = statuses {
+                registry
+                    .file_statuses
+                    .lock()
+                    .insert(statuses.root().to_path_buf(), statuses.clone());
+                on_done(statuses);
+            }
+        });
+    }
+
     /// Fire-and-forget changed file iteration. Runs everything in a background task. Keeps
     /// iteration until `on_change` returns `false`.
     pub fn for_each_changed_file(
@@ -91,7 +226,10 @@ fn default() -> Self {
             DiffProvider::Git,
             DiffProvider::None,
         ];
-        DiffProviderRegistry { providers }
+        DiffProviderRegistry {
+            providers,
+            file_statuses: Arc::default(),
+        }
     }
 }
 
@@ -107,10 +245,29 @@ enum DiffProvider {
 }
 
 impl DiffProvider {
-    fn get_diff_base(&self, file: &Path, trust_full: bool) -> Result<Vec<u8>> {
+    fn get_diff_base(&self, file: &Path, base: DiffBase, trust_full: bool) -> Result<Vec<u8>> {
         match self {
             #[cfg(feature = "git")]
-            Self::Git => git::get_diff_base(file, trust_full),
+            Self::Git => match base {
+                DiffBase::Head => git::get_diff_base(file, trust_full),
+                DiffBase::Index => git::get_index_base(file, trust_full),
+            },
+            Self::None => bail!("No diff support compiled in"),
+        }
+    }
+
+    fn get_revision_base(&self, file: &Path, rev: &str, trust_full: bool) -> Result<Vec<u8>> {
+        match self {
+            #[cfg(feature = "git")]
+            Self::Git => git::get_revision_base(file, rev, trust_full),
+            Self::None => bail!("No diff support compiled in"),
+        }
+    }
+
+    fn file_statuses(&self, dir: &Path, trust_full: bool) -> Result<RepoStatus> {
+        match self {
+            #[cfg(feature = "git")]
+            Self::Git => git::file_statuses(dir, trust_full),
             Self::None => bail!("No diff support compiled in"),
         }
     }
@@ -127,6 +284,53 @@ fn get_current_head_name(
         }
     }
 
+    fn get_repo_root(&self, dir: &Path, trust_full: bool) -> Result<PathBuf> {
+        match self {
+            #[cfg(feature = "git")]
+            Self::Git => git::get_repo_root(dir, trust_full),
+            Self::None => bail!("No diff support compiled in"),
+        }
+    }
+
+    fn blame(&self, file: &Path, base: DiffBase, trust_full: bool) -> Result<FileBlame> {
+        match self {
+            #[cfg(feature = "git")]
+            Self::Git => match base {
+                DiffBase::Head => git::blame_file(file, trust_full),
+                DiffBase::Index => git::blame_index(file, trust_full),
+            },
+            Self::None => bail!("No diff support compiled in"),
+        }
+    }
+
+    fn stage_hunks(
+        &self,
+        file: &Path,
+        doc: &[u8],
+        lines: &[(usize, usize)],
+        trust_full: bool,
+    ) -> Result<()> {
+        match self {
+            #[cfg(feature = "git")]
+            Self::Git => git::stage_hunks(file, doc, lines, trust_full),
+            Self::None => bail!("No diff support compiled in"),
+        }
+    }
+
+    fn unstage_hunks(
+        &self,
+        file: &Path,
+        doc: &[u8],
+        lines: &[(usize, usize)],
+        trust_full: bool,
+    ) -> Result<()> {
+        match self {
+            #[cfg(feature = "git")]
+            Self::Git => git::unstage_hunks(file, doc, lines, trust_full),
+            Self::None => bail!("No diff support compiled in"),
+        }
+    }
+
     fn for_each_changed_file(
         &self,
         cwd: &Path,
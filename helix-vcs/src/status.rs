@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
 /// States for a file having been changed.
@@ -28,3 +29,113 @@ pub fn path(&self) -> &Path {
         }
     }
 }
+
+/// The status of a file or directory shown in the file picker and file explorer, ordered by
+/// precedence for directories containing files of different statuses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FileStatus {
+    Ignored,
+    Untracked,
+    Renamed,
+    Modified,
+    Conflict,
+}
+
+/// The statuses of the files in the worktree of a repository.
+#[derive(Debug, Default)]
+pub struct RepoStatus {
+    root: PathBuf,
+    /// The statuses of changed and ignored files as well as of the directories containing
+    /// changed files. Ignored directories are not walked.
+    statuses: HashMap<PathBuf, FileStatus>,
+}
+
+impl RepoStatus {
+    pub(crate) fn new(root: PathBuf) -> Self {
+        RepoStatus {
+            root,
+            statuses: HashMap::new(),
+        }
+    }
+
+    /// The root of the worktree.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub(crate) fn insert(&mut self, change: FileChange) {
+        let status = match change {
+            FileChange::Untracked { .. } => FileStatus::Untracked,
+            FileChange::Modified { .. } => FileStatus::Modified,
+            FileChange::Conflict { .. } => FileStatus::Conflict,
+            FileChange::Renamed { .. } => FileStatus::Renamed,
+            // Deleted files aren't listed by the pickers but their directories are modified.
+            FileChange::Deleted { .. } => FileStatus::Modified,
+        };
+        let path = change.path();
+        if !matches!(change, FileChange::Deleted { .. }) {
+            self.statuses.insert(path.to_path_buf(), status);
+        }
+        for dir in path.ancestors().skip(1) {
+            if !dir.starts_with(&self.root) || dir == self.root {
+                break;
+            }
+            let dir_status = self
+                .statuses
+                .entry(dir.to_path_buf())
+                .or_insert(FileStatus::Ignored);
+            *dir_status = (*dir_status).max(status);
+        }
+    }
+
+    /// Marks an ignored file or directory. Unlike changes, this doesn't affect the status of the
+    /// directories containing it.
+    pub(crate) fn insert_ignored(&mut self, path: PathBuf) {
+        self.statuses.insert(path, FileStatus::Ignored);
+    }
+
+    /// Returns the status of a file or directory, or `None` if it is unchanged.
+    pub fn get(&self, path: &Path) -> Option<FileStatus> {
+        if let Some(&status) = self.statuses.get(path) {
+            return Some(status);
+        }
+        // Everything in an ignored directory is ignored.
+        path.ancestors()
+            .skip(1)
+            .take_while(|dir| dir.starts_with(&self.root))
+            .find_map(|dir| self.statuses.get(dir))
+            .filter(|&&status| status == FileStatus::Ignored)
+            .copied()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::{Path, PathBuf};
+
+    use super::{FileChange, FileStatus, RepoStatus};
+
+    #[test]
+    fn directory_statuses() {
+        let mut status = RepoStatus::new(PathBuf::from("/repo"));
+        status.insert(FileChange::Untracked {
+            path: "/repo/src/new.rs".into(),
+        });
+        status.insert(FileChange::Modified {
+            path: "/repo/src/lib.rs".into(),
+        });
+        status.insert_ignored("/repo/target".into());
+        status.insert(FileChange::Deleted {
+            path: "/repo/docs/old.md".into(),
+        });
+
+        let get = |path: &str| status.get(Path::new(path));
+        assert_eq!(get("/repo/src/new.rs"), Some(FileStatus::Untracked));
+        assert_eq!(get("/repo/src"), Some(FileStatus::Modified));
+        assert_eq!(get("/repo/docs"), Some(FileStatus::Modified));
+        assert_eq!(get("/repo/docs/old.md"), None);
+        assert_eq!(get("/repo/target/debug/helix"), Some(FileStatus::Ignored));
+        assert_eq!(get("/repo/README.md"), None);
+        assert_eq!(get("/repo"), None);
+    }
+}
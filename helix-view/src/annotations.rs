@@ -1 +1,4 @@
+pub mod code_lens;
 pub mod diagnostics;
+pub mod hunk_diff;
+pub mod inline_completion;
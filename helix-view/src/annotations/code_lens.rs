@@ -0,0 +1,109 @@
+use helix_core::doc_formatter::FormattedGrapheme;
+use helix_core::line_ending::line_end_char_index;
+use helix_core::text_annotations::LineAnnotation;
+use helix_core::Position;
+
+use crate::{Document, ViewId};
+
+/// A virtual line that shows the code lenses of a document line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodeLensLine {
+    /// The char index of the line ending after which the virtual line is inserted.
+    pub anchor: usize,
+    /// The document line the code lenses belong to.
+    pub line: usize,
+    /// The titles of the code lenses, separated by ` | `.
+    pub text: String,
+}
+
+/// Collects the code lenses of `doc` into one virtual line for each document line.
+///
+/// The virtual line is placed above the line the code lenses belong to, except for the first
+/// line of the document where it is placed below as there is no line above. Lenses of lines
+/// hidden by a closed fold are not shown and the lenses of the line following a fold are anchored
+/// at the line ending that closes the fold.
+pub fn code_lens_lines(doc: &Document, view_id: ViewId) -> Vec<CodeLensLine> {
+    let text = doc.text().slice(..);
+    let folds = doc
+        .folds
+        .get(&view_id)
+        .map_or(&[][..], |folds| folds.visible.as_slice());
+    let fold_hiding = |char_idx: usize| folds.iter().find(|fold| fold.hides(char_idx));
+
+    let mut lines: Vec<CodeLensLine> = Vec::new();
+    for code_lens in &doc.code_lenses {
+        let Some(command) = &code_lens.lens.command else {
+            continue;
+        };
+        let line = text.char_to_line(code_lens.pos.min(text.len_chars()));
+        match lines.last_mut() {
+            Some(last) if last.line == line => {
+                last.text.push_str(" | ");
+                last.text.push_str(&command.title);
+                continue;
+            }
+            _ => (),
+        }
+        if fold_hiding(text.line_to_char(line)).is_some() {
+            continue;
+        }
+
+        let mut anchor = line_end_char_index(&text, line.saturating_sub(1));
+        if let Some(fold) = fold_hiding(anchor) {
+            anchor = fold.start;
+        }
+        lines.push(CodeLensLine {
+            anchor,
+            line,
+            text: command.title.clone(),
+        });
+    }
+    lines
+}
+
+/// Reserves the virtual lines for the code lenses of a document, see [`code_lens_lines`].
+pub(crate) struct CodeLenses {
+    anchors: Vec<usize>,
+    idx: usize,
+    pending: usize,
+}
+
+impl CodeLenses {
+    #[allow(clippy::new_ret_no_self)]
+    pub(crate) fn new(lines: &[CodeLensLine]) -> Box<dyn LineAnnotation> {
+        Box::new(CodeLenses {
+            anchors: lines.iter().map(|line| line.anchor).collect(),
+            idx: 0,
+            pending: 0,
+        })
+    }
+
+    fn next_anchor(&self) -> usize {
+        self.anchors.get(self.idx).copied().unwrap_or(usize::MAX)
+    }
+}
+
+impl LineAnnotation for CodeLenses {
+    fn reset_pos(&mut self, char_idx: usize) -> usize {
+        self.idx = self.anchors.partition_point(|&anchor| anchor < char_idx);
+        self.pending = 0;
+        self.next_anchor()
+    }
+
+    fn process_anchor(&mut self, grapheme: &FormattedGrapheme) -> usize {
+        while self.next_anchor() == grapheme.char_idx {
+            self.pending += 1;
+            self.idx += 1;
+        }
+        self.next_anchor()
+    }
+
+    fn insert_virtual_lines(
+        &mut self,
+        _line_end_char_idx: usize,
+        _line_end_visual_pos: Position,
+        _doc_line: usize,
+    ) -> Position {
+        Position::new(std::mem::take(&mut self.pending), 0)
+    }
+}
@@ -0,0 +1,49 @@
+use helix_core::doc_formatter::FormattedGrapheme;
+use helix_core::text_annotations::LineAnnotation;
+use helix_core::Position;
+
+/// Reserves the virtual lines for the ghost text of an inline completion that spans multiple
+/// lines, see [`crate::document::DocumentInlineCompletion::ghost_lines`].
+pub(crate) struct InlineCompletionLines {
+    anchor: usize,
+    height: usize,
+    pending: usize,
+}
+
+impl InlineCompletionLines {
+    #[allow(clippy::new_ret_no_self)]
+    pub(crate) fn new(anchor: usize, height: usize) -> Box<dyn LineAnnotation> {
+        Box::new(InlineCompletionLines {
+            anchor,
+            height,
+            pending: 0,
+        })
+    }
+}
+
+impl LineAnnotation for InlineCompletionLines {
+    fn reset_pos(&mut self, char_idx: usize) -> usize {
+        self.pending = 0;
+        if char_idx <= self.anchor {
+            self.anchor
+        } else {
+            usize::MAX
+        }
+    }
+
+    fn process_anchor(&mut self, grapheme: &FormattedGrapheme) -> usize {
+        if grapheme.char_idx == self.anchor {
+            self.pending = self.height;
+        }
+        usize::MAX
+    }
+
+    fn insert_virtual_lines(
+        &mut self,
+        _line_end_char_idx: usize,
+        _line_end_visual_pos: Position,
+        _doc_line: usize,
+    ) -> Position {
+        Position::new(std::mem::take(&mut self.pending), 0)
+    }
+}
@@ -0,0 +1,175 @@
+//! The scrollback of the debug console: the expressions evaluated in it, their results and the
+//! output of the debugged program.
+
+use std::collections::VecDeque;
+
+use crate::Editor;
+
+/// The maximum number of lines kept in the scrollback.
+const MAX_LINES: usize = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConsoleLineKind {
+    /// An expression entered in the console.
+    Input,
+    /// The result of evaluating an expression.
+    Result,
+    /// An expression that failed to evaluate.
+    Error,
+    Stdout,
+    Stderr,
+    /// Output of the debugger itself, e.g. the `console` and `important` output categories.
+    Console,
+}
+
+impl ConsoleLineKind {
+    /// The kind of the lines of an output event with the given category.
+    pub fn from_category(category: Option<&str>) -> Self {
+        match category {
+            Some("stdout") => Self::Stdout,
+            Some("stderr") => Self::Stderr,
+            _ => Self::Console,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConsoleLine {
+    pub kind: ConsoleLineKind,
+    pub text: String,
+}
+
+#[derive(Debug, Default)]
+pub struct DebugConsole {
+    /// Whether the console is docked below the editor views.
+    pub visible: bool,
+    pub lines: VecDeque<ConsoleLine>,
+    /// Whether the last line wasn't terminated by a newline yet, so the next output of the same
+    /// kind continues it.
+    partial: bool,
+}
+
+impl DebugConsole {
+    /// The number of rows taken by the console below the editor views.
+    pub fn height(&self, area_height: u16) -> u16 {
+        if self.visible {
+            area_height / 3
+        } else {
+            0
+        }
+    }
+
+    /// Appends `text`, which may span several lines, as complete lines.
+    pub fn push_line(&mut self, kind: ConsoleLineKind, text: &str) {
+        self.partial = false;
+        self.push(kind, text.strip_suffix('\n').unwrap_or(text));
+        self.partial = false;
+    }
+
+    /// Appends the output of the debugged program or the debugger. Output doesn't have to end
+    /// with a newline, in which case the next output of the same kind continues the last line.
+    pub fn push_output(&mut self, kind: ConsoleLineKind, output: &str) {
+        self.push(kind, output);
+    }
+
+    fn push(&mut self, kind: ConsoleLineKind, text: &str) {
+        let text = text.replace("\r\n", "\n");
+        let mut lines = text.split('\n').peekable();
+        if self.partial && self.lines.back().is_some_and(|line| line.kind == kind) {
+            if let (Some(line), Some(first)) = (self.lines.back_mut(), lines.next()) {
+                line.text.push_str(first);
+            }
+        }
+        self.partial = true;
+        while let Some(line) = lines.next() {
+            if lines.peek().is_none() && line.is_empty() {
+                // The text ended with a newline.
+                self.partial = false;
+                break;
+            }
+            self.lines.push_back(ConsoleLine {
+                kind,
+                text: line.to_owned(),
+            });
+        }
+        while self.lines.len() > MAX_LINES {
+            self.lines.pop_front();
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.lines.clear();
+        self.partial = false;
+    }
+}
+
+impl Editor {
+    /// Evaluates `expression` in the `repl` context of the active stack frame, or globally if the
+    /// debugger isn't stopped, and appends it and its result to the debug console.
+    pub async fn eval_in_debug_console(&mut self, expression: &str) {
+        self.debug_console
+            .push_line(ConsoleLineKind::Input, expression);
+        let Some(debugger) = self.debug_adapters.get_active_client() else {
+            self.debug_console
+                .push_line(ConsoleLineKind::Error, "Debugger is not running");
+            return;
+        };
+        let frame_id = debugger.current_stack_frame().map(|frame| frame.id);
+        match debugger
+            .eval(expression.to_owned(), frame_id, Some("repl"))
+            .await
+        {
+            Ok(response) => {
+                if !response.result.is_empty() {
+                    self.debug_console
+                        .push_line(ConsoleLineKind::Result, &response.result);
+                }
+            }
+            Err(err) => self
+                .debug_console
+                .push_line(ConsoleLineKind::Error, &err.to_string()),
+        }
+        // The expression may have changed the values of variables.
+        self.update_debug_panel().await;
+        self.update_inline_values();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn texts(console: &DebugConsole) -> Vec<(ConsoleLineKind, &str)> {
+        console
+            .lines
+            .iter()
+            .map(|line| (line.kind, line.text.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn output() {
+        use ConsoleLineKind::*;
+
+        let mut console = DebugConsole::default();
+        console.push_output(Stdout, "hello ");
+        console.push_output(Stdout, "world\nfoo");
+        console.push_output(Stderr, "error\r\n");
+        console.push_output(Stdout, "bar\n");
+        console.push_line(Input, "1 + 1");
+        console.push_line(Result, "2\n");
+        console.push_output(Stdout, "\n");
+        assert_eq!(
+            texts(&console),
+            [
+                (Stdout, "hello world"),
+                (Stdout, "foo"),
+                (Stderr, "error"),
+                (Stdout, "bar"),
+                (Input, "1 + 1"),
+                (Result, "2"),
+                (Stdout, ""),
+            ]
+        );
+    }
+}
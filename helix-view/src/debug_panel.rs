@@ -0,0 +1,356 @@
+//! The state of the debug panel: the watch expressions, the variables of the active stack frame
+//! and which of them are expanded. The call stack and the breakpoints are shown straight from the
+//! debugger and [`Editor::breakpoints`].
+
+use std::collections::HashSet;
+
+use futures_util::future::BoxFuture;
+use helix_dap::Client;
+
+use crate::Editor;
+
+/// A variable, the result of a watch expression or a scope, shown as a node of a tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DebugVariable {
+    pub name: String,
+    pub value: String,
+    pub ty: Option<String>,
+    /// The expression that evaluates to the variable, used to change its value with a
+    /// `setExpression` request.
+    pub evaluate_name: Option<String>,
+    /// The `variablesReference` of the scope or variable containing the variable, used to change
+    /// its value with a `setVariable` request. `0` for watch expressions and scopes.
+    pub container: usize,
+    /// The `variablesReference` of the children of the variable, `0` if it has none.
+    pub variables_reference: usize,
+    /// The children of the variable, `None` while collapsed.
+    pub children: Option<Vec<DebugVariable>>,
+}
+
+impl DebugVariable {
+    pub fn is_expandable(&self) -> bool {
+        self.variables_reference != 0
+    }
+
+    pub fn is_expanded(&self) -> bool {
+        self.children.is_some()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Watch {
+    pub expression: String,
+    /// The result of the last evaluation or the error it failed with. `None` if the expression
+    /// wasn't evaluated yet.
+    pub result: Option<Result<DebugVariable, String>>,
+}
+
+/// The tree a node of the debug panel belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DebugRoot {
+    Watch(usize),
+    Scope(usize),
+}
+
+/// Identifies a node of the debug panel by its tree and the indices of the children leading to
+/// it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DebugNodePath {
+    pub root: DebugRoot,
+    pub children: Vec<usize>,
+}
+
+#[derive(Debug, Default)]
+pub struct DebugPanel {
+    /// Whether the panel is docked below the editor views.
+    pub visible: bool,
+    pub watches: Vec<Watch>,
+    /// The scopes of the active stack frame, with their variables as children.
+    pub scopes: Vec<DebugVariable>,
+    /// The names leading to the nodes that were expanded (starting with the watch expression or
+    /// scope name), so they are expanded again when the values are refreshed.
+    expanded: HashSet<Vec<String>>,
+    /// The names of the scopes that were collapsed. Scopes which aren't expensive to fetch are
+    /// expanded otherwise.
+    collapsed_scopes: HashSet<String>,
+}
+
+impl DebugPanel {
+    /// The number of rows taken by the panel below the editor views.
+    pub fn height(&self, area_height: u16) -> u16 {
+        if self.visible {
+            area_height / 3
+        } else {
+            0
+        }
+    }
+
+    pub fn node(&self, path: &DebugNodePath) -> Option<&DebugVariable> {
+        let mut node = match path.root {
+            DebugRoot::Watch(idx) => self.watches.get(idx)?.result.as_ref()?.as_ref().ok()?,
+            DebugRoot::Scope(idx) => self.scopes.get(idx)?,
+        };
+        for &idx in &path.children {
+            node = node.children.as_ref()?.get(idx)?;
+        }
+        Some(node)
+    }
+
+    fn node_mut(&mut self, path: &DebugNodePath) -> Option<&mut DebugVariable> {
+        let mut node = match path.root {
+            DebugRoot::Watch(idx) => self.watches.get_mut(idx)?.result.as_mut()?.as_mut().ok()?,
+            DebugRoot::Scope(idx) => self.scopes.get_mut(idx)?,
+        };
+        for &idx in &path.children {
+            node = node.children.as_mut()?.get_mut(idx)?;
+        }
+        Some(node)
+    }
+
+    /// The names of the nodes leading to the node at `path`, including its own.
+    fn names(&self, path: &DebugNodePath) -> Option<Vec<String>> {
+        let mut node = match path.root {
+            DebugRoot::Watch(idx) => {
+                let watch = self.watches.get(idx)?;
+                let mut names = vec![watch.expression.clone()];
+                let mut node = watch.result.as_ref()?.as_ref().ok()?;
+                for &idx in &path.children {
+                    node = node.children.as_ref()?.get(idx)?;
+                    names.push(node.name.clone());
+                }
+                return Some(names);
+            }
+            DebugRoot::Scope(idx) => self.scopes.get(idx)?,
+        };
+        let mut names = vec![node.name.clone()];
+        for &idx in &path.children {
+            node = node.children.as_ref()?.get(idx)?;
+            names.push(node.name.clone());
+        }
+        Some(names)
+    }
+
+    /// Fetches the children of the node at `path`.
+    pub async fn expand(&mut self, debugger: &Client, path: &DebugNodePath) -> anyhow::Result<()> {
+        let Some(names) = self.names(path) else {
+            return Ok(());
+        };
+        let Some(node) = self.node_mut(path) else {
+            return Ok(());
+        };
+        if !node.is_expandable() {
+            return Ok(());
+        }
+        node.children = Some(variables(debugger, node.variables_reference).await?);
+        if path.children.is_empty() && matches!(path.root, DebugRoot::Scope(_)) {
+            self.collapsed_scopes.remove(&names[0]);
+        }
+        self.expanded.insert(names);
+        Ok(())
+    }
+
+    pub fn collapse(&mut self, path: &DebugNodePath) {
+        let Some(names) = self.names(path) else {
+            return;
+        };
+        if let Some(node) = self.node_mut(path) {
+            node.children = None;
+        }
+        if path.children.is_empty() && matches!(path.root, DebugRoot::Scope(_)) {
+            self.collapsed_scopes.insert(names[0].clone());
+        }
+        self.expanded.remove(&names);
+    }
+
+    pub fn add_watch(&mut self, expression: String) {
+        self.watches.push(Watch {
+            expression,
+            result: None,
+        });
+    }
+
+    pub fn remove_watch(&mut self, idx: usize) {
+        if idx >= self.watches.len() {
+            return;
+        }
+        let watch = self.watches.remove(idx);
+        self.expanded
+            .retain(|names| names.first() != Some(&watch.expression));
+    }
+
+    /// Forgets the values, e.g. when the debug session ended. The watch expressions are kept.
+    pub fn clear(&mut self) {
+        self.scopes.clear();
+        for watch in &mut self.watches {
+            watch.result = None;
+        }
+    }
+
+    /// Evaluates the watch expressions and fetches the scopes of the stack frame `frame_id`,
+    /// expanding the nodes that were expanded before.
+    pub async fn refresh(&mut self, debugger: &Client, frame_id: usize) {
+        for watch in &mut self.watches {
+            let mut result = debugger
+                .eval(watch.expression.clone(), Some(frame_id), Some("watch"))
+                .await
+                .map(|response| DebugVariable {
+                    name: watch.expression.clone(),
+                    value: response.result,
+                    ty: response.ty,
+                    evaluate_name: Some(watch.expression.clone()),
+                    container: 0,
+                    variables_reference: response.variables_reference,
+                    children: None,
+                })
+                .map_err(|err| err.to_string());
+            if let Ok(node) = &mut result {
+                let mut names = vec![watch.expression.clone()];
+                restore_expanded(debugger, node, &mut names, &self.expanded).await;
+            }
+            watch.result = Some(result);
+        }
+
+        let scopes = debugger.scopes(frame_id).await.unwrap_or_default();
+        self.scopes = Vec::with_capacity(scopes.len());
+        for scope in scopes {
+            let mut node = DebugVariable {
+                name: scope.name,
+                value: String::new(),
+                ty: None,
+                evaluate_name: None,
+                container: 0,
+                variables_reference: scope.variables_reference,
+                children: None,
+            };
+            let mut names = vec![node.name.clone()];
+            if !scope.expensive && !self.collapsed_scopes.contains(&node.name) {
+                self.expanded.insert(names.clone());
+            }
+            restore_expanded(debugger, &mut node, &mut names, &self.expanded).await;
+            self.scopes.push(node);
+        }
+    }
+}
+
+async fn variables(debugger: &Client, reference: usize) -> helix_dap::Result<Vec<DebugVariable>> {
+    let variables = debugger.variables(reference).await?;
+    Ok(variables
+        .into_iter()
+        .map(|variable| DebugVariable {
+            name: variable.name,
+            value: variable.value,
+            ty: variable.ty,
+            evaluate_name: variable.evaluate_name,
+            container: reference,
+            variables_reference: variable.variables_reference,
+            children: None,
+        })
+        .collect())
+}
+
+/// Fetches the children of `node` and its descendants if their `names` are in `expanded`.
+fn restore_expanded<'a>(
+    debugger: &'a Client,
+    node: &'a mut DebugVariable,
+    names: &'a mut Vec<String>,
+    expanded: &'a HashSet<Vec<String>>,
+) -> BoxFuture<'a, ()> {
+    Box::pin(async move {
+        if !node.is_expandable() || !expanded.contains(names) {
+            return;
+        }
+        let Ok(children) = variables(debugger, node.variables_reference).await else {
+            return;
+        };
+        let children = node.children.insert(children);
+        for child in children {
+            names.push(child.name.clone());
+            restore_expanded(debugger, child, names, expanded).await;
+            names.pop();
+        }
+    })
+}
+
+impl Editor {
+    /// Refreshes the debug panel with the values of the active stack frame, if the panel is
+    /// visible.
+    pub async fn update_debug_panel(&mut self) {
+        if !self.debug_panel.visible {
+            return;
+        }
+        let Some(debugger) = self.debug_adapters.get_active_client() else {
+            self.debug_panel.clear();
+            return;
+        };
+        match debugger.current_stack_frame() {
+            Some(frame) => {
+                let frame_id = frame.id;
+                self.debug_panel.refresh(debugger, frame_id).await
+            }
+            None => self.debug_panel.clear(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn variable(name: &str, variables_reference: usize) -> DebugVariable {
+        DebugVariable {
+            name: name.to_owned(),
+            value: String::new(),
+            ty: None,
+            evaluate_name: None,
+            container: 0,
+            variables_reference,
+            children: None,
+        }
+    }
+
+    #[test]
+    fn nodes() {
+        let mut panel = DebugPanel::default();
+        panel.add_watch("foo".to_owned());
+        let mut foo = variable("foo", 1);
+        foo.children = Some(vec![variable("bar", 0)]);
+        panel.watches[0].result = Some(Ok(foo));
+        let mut locals = variable("Locals", 2);
+        locals.children = Some(vec![variable("x", 0), variable("y", 3)]);
+        panel.scopes.push(locals);
+
+        let bar = DebugNodePath {
+            root: DebugRoot::Watch(0),
+            children: vec![0],
+        };
+        assert_eq!(panel.node(&bar).map(|node| node.name.as_str()), Some("bar"));
+        assert_eq!(panel.names(&bar), Some(vec!["foo".into(), "bar".into()]));
+        let y = DebugNodePath {
+            root: DebugRoot::Scope(0),
+            children: vec![1],
+        };
+        assert_eq!(panel.names(&y), Some(vec!["Locals".into(), "y".into()]));
+        assert_eq!(
+            panel.node(&DebugNodePath {
+                root: DebugRoot::Scope(0),
+                children: vec![2],
+            }),
+            None
+        );
+
+        let locals = DebugNodePath {
+            root: DebugRoot::Scope(0),
+            children: Vec::new(),
+        };
+        panel.expanded.insert(vec!["Locals".into()]);
+        panel.collapse(&locals);
+        assert!(!panel.node(&locals).unwrap().is_expanded());
+        assert!(panel.collapsed_scopes.contains("Locals"));
+        assert!(panel.expanded.is_empty());
+
+        panel.expanded.insert(vec!["foo".into()]);
+        panel.remove_watch(0);
+        assert!(panel.watches.is_empty());
+        assert!(panel.expanded.is_empty());
+    }
+}
@@ -6,16 +6,18 @@
 use helix_core::auto_pairs::AutoPairs;
 use helix_core::chars::char_is_word;
 use helix_core::command_line::Token;
+use helix_core::conflict::Conflict;
 use helix_core::diagnostic::DiagnosticProvider;
 use helix_core::doc_formatter::TextFormat;
 use helix_core::encoding::Encoding;
+use helix_core::fold::{self, Fold};
 use helix_core::snippets::{ActiveSnippet, SnippetRenderCtx};
 use helix_core::syntax::config::LanguageServerFeature;
 use helix_core::text_annotations::{InlineAnnotation, Overlay};
 use helix_event::TaskController;
 use helix_lsp::util::lsp_pos_to_pos;
 use helix_stdx::faccess::{copy_metadata, readonly};
-use helix_vcs::{DiffHandle, DiffProviderRegistry};
+use helix_vcs::{DiffBase, DiffHandle, DiffProviderRegistry, FileBlame, LineBlame};
 use once_cell::sync::OnceCell;
 use thiserror;
 
@@ -28,6 +30,7 @@
 use std::fmt::Display;
 use std::future::Future;
 use std::io;
+use std::ops;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::sync::{Arc, Weak};
@@ -38,9 +41,10 @@
     encoding,
     history::{History, State, UndoKind},
     indent::{auto_detect_indent_style, IndentStyle},
-    line_ending::auto_detect_line_ending,
+    line_ending::{auto_detect_line_ending, line_end_char_index},
     syntax::{self, config::LanguageConfiguration},
-    ChangeSet, Diagnostic, LineEnding, Range, Rope, RopeBuilder, Selection, Syntax, Transaction,
+    Assoc, ChangeSet, Diagnostic, LineEnding, Range, Rope, RopeBuilder, RopeSlice, Selection,
+    Syntax, Transaction,
 };
 
 use crate::{
@@ -173,6 +177,8 @@ pub struct Document {
     pub(crate) document_highlights: HashMap<ViewId, DocumentHighlights>,
     /// LSP code action hints for each view.
     pub(crate) code_action_hints: HashSet<ViewId>,
+    /// Closed folds for each view.
+    pub(crate) folds: HashMap<ViewId, DocumentFolds>,
     /// Set to `true` when the document is updated, reset to `false` on the next inlay hints
     /// update from the LSP
     pub inlay_hints_oudated: bool,
@@ -225,6 +231,9 @@ pub struct Document {
     pub(crate) language_servers: HashMap<LanguageServerName, Arc<Client>>,
 
     diff_handle: Option<DiffHandle>,
+    /// The diff against the base selected by the `diff-base` option while
+    /// [`Document::diff_base_override`] is set, used to map the blame to the current text.
+    blame_diff_handle: Option<DiffHandle>,
     version_control_head: Option<Arc<ArcSwap<Box<str>>>>,
 
     // when document was used for most-recent-used buffer picker
@@ -247,6 +256,34 @@ pub struct Document {
     pub code_action_controllers: HashMap<ViewId, TaskController>,
     pub pull_diagnostic_controller: TaskController,
     pub document_link_controller: TaskController,
+    /// Cached LSP folding ranges, sorted with `helix_core::fold::sort`.
+    pub folding_ranges: Vec<Fold>,
+    pub folding_range_controller: TaskController,
+    /// LSP semantic tokens, overlaid over the tree-sitter highlights.
+    pub semantic_tokens: Option<DocumentSemanticTokens>,
+    pub semantic_tokens_controller: TaskController,
+    /// Cached LSP code lenses, sorted by position.
+    pub code_lenses: Vec<DocumentCodeLens>,
+    pub code_lens_controller: TaskController,
+    /// The linked ranges the cursors of a view are placed in while in insert mode.
+    pub linked_editing: Option<LinkedEditing>,
+    pub linked_editing_controller: TaskController,
+    /// LSP inline completions shown as ghost text at the cursor while in insert mode.
+    pub inline_completion: Option<DocumentInlineCompletion>,
+    pub inline_completion_controller: TaskController,
+    /// The values of variables shown at the end of their lines while a debugger is stopped.
+    pub inline_values: Vec<InlineAnnotation>,
+    /// The git blame of the file as of `HEAD`, loaded on demand.
+    pub blame: Option<FileBlame>,
+    pub blame_controller: TaskController,
+    /// Whether the blame gutter is shown for this document.
+    pub blame_gutter: bool,
+    /// The view and index of the hunk whose base lines are shown with `show_hunk_diff`.
+    pub hunk_diff: Option<(ViewId, u32)>,
+    /// Overrides the `diff-base` option for this document.
+    pub diff_base_override: Option<DocumentDiffBase>,
+    /// The git merge conflicts in the document, in ascending order.
+    pub conflicts: Vec<Conflict>,
 
     // NOTE: this field should eventually go away - we should use the Editor's syn_loader instead
     // of storing a copy on every doc. Then we can remove the surrounding `Arc` and use the
@@ -261,12 +298,79 @@ pub struct DocumentColorSwatches {
     pub color_swatches_padding: Vec<InlineAnnotation>,
 }
 
+/// Semantic tokens returned by LSP `textDocument/semanticTokens/full` and
+/// `textDocument/semanticTokens/full/delta`.
+#[derive(Debug, Clone)]
+pub struct DocumentSemanticTokens {
+    pub language_server_id: LanguageServerId,
+    /// The id of the response the tokens were decoded from, used to request deltas.
+    pub result_id: Option<String>,
+    /// The tokens of the last response in the relative LSP encoding. Deltas are applied to these.
+    pub data: Vec<lsp::SemanticToken>,
+    /// The theme scope of each token type in the server's legend, e.g. `lsp.type.parameter`.
+    pub type_scopes: Vec<String>,
+    /// The theme scope of each token modifier in the server's legend, e.g. `lsp.mod.mutable`.
+    pub modifier_scopes: Vec<String>,
+    /// The decoded tokens, sorted by position and non-overlapping.
+    pub tokens: Vec<SemanticToken>,
+}
+
+/// A single decoded semantic token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SemanticToken {
+    pub start: usize,
+    pub end: usize,
+    /// Index into [`DocumentSemanticTokens::type_scopes`].
+    pub token_type: u32,
+    /// Bitset of indices into [`DocumentSemanticTokens::modifier_scopes`].
+    pub modifiers: u32,
+}
+
 /// Highlight ranges returned by LSP `textDocument/documentHighlight` for a view.
 #[derive(Debug, Clone, Default)]
 pub struct DocumentHighlights {
     pub ranges: Vec<std::ops::Range<usize>>,
 }
 
+/// Closed folds for a single `(Document, View)` combo.
+#[derive(Debug, Clone, Default)]
+pub struct DocumentFolds {
+    /// All closed folds, sorted with `helix_core::fold::sort`. Folds nested inside another
+    /// closed fold stay closed when the outer fold is opened.
+    pub(crate) closed: Vec<Fold>,
+    /// The closed folds that are not hidden by another closed fold. These are the folds
+    /// that are actually skipped when rendering.
+    pub(crate) visible: Vec<Fold>,
+    /// The `… N lines` placeholder shown at the end of the first line of each visible fold.
+    pub(crate) placeholders: Vec<InlineAnnotation>,
+    pub(crate) placeholders_padding: Vec<InlineAnnotation>,
+}
+
+impl DocumentFolds {
+    fn new(mut closed: Vec<Fold>, text: RopeSlice) -> Self {
+        fold::sort(&mut closed);
+        let visible = fold::outermost(&closed);
+        let placeholders = visible
+            .iter()
+            .map(|fold| {
+                let lines = fold.hidden_lines(text);
+                let text = format!("… {lines} line{}", if lines == 1 { "" } else { "s" });
+                InlineAnnotation::new(fold.start, text)
+            })
+            .collect();
+        let placeholders_padding = visible
+            .iter()
+            .map(|fold| InlineAnnotation::new(fold.start, " "))
+            .collect();
+        Self {
+            closed,
+            visible,
+            placeholders,
+            placeholders_padding,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct DocumentLink {
     /// Character offsets in the document for the link range.
@@ -276,6 +380,172 @@ pub struct DocumentLink {
     pub language_server_id: LanguageServerId,
 }
 
+#[derive(Debug, Clone)]
+pub struct DocumentCodeLens {
+    /// Character offset in the document of the start of the lens range.
+    pub pos: usize,
+    pub lens: lsp::CodeLens,
+    pub language_server_id: LanguageServerId,
+    /// Whether a `codeLens/resolve` request was sent for this lens. Lenses are resolved once
+    /// they come into view.
+    pub resolve_requested: bool,
+}
+
+/// Ranges that are edited together, see [`helix_core::linked_editing`].
+#[derive(Debug, Clone)]
+pub struct LinkedEditing {
+    pub view_id: ViewId,
+    /// Character ranges in the document that always contain the same text.
+    pub ranges: Vec<ops::Range<usize>>,
+}
+
+/// What a document is compared against instead of the base selected by the `diff-base` option,
+/// set with `:diff-base` and `:diff-base-file`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DocumentDiffBase {
+    /// The staged file in the git index.
+    Index,
+    /// The file as of a git revision, for example a branch, a tag or `HEAD~3`.
+    Revision(String),
+    /// The contents of another file.
+    File(PathBuf),
+}
+
+impl DocumentDiffBase {
+    /// Reads the contents of the base of the document at `path`.
+    pub fn read(
+        &self,
+        path: &Path,
+        provider_registry: &DiffProviderRegistry,
+        trust_full: bool,
+    ) -> anyhow::Result<Vec<u8>> {
+        match self {
+            Self::Index => provider_registry
+                .get_diff_base(path, DiffBase::Index, trust_full)
+                .ok_or_else(|| anyhow!("{} is not tracked by git", path.display())),
+            Self::Revision(rev) => provider_registry.get_revision_base(path, rev, trust_full),
+            Self::File(file) => std::fs::read(file)
+                .map_err(|err| anyhow!("failed to read {}: {err}", file.display())),
+        }
+    }
+}
+
+impl Display for DocumentDiffBase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Index => f.write_str("index"),
+            Self::Revision(rev) => f.write_str(rev),
+            Self::File(file) => {
+                write!(f, "{}", helix_stdx::path::get_relative_path(file).display())
+            }
+        }
+    }
+}
+
+/// Inline completions returned by LSP `textDocument/inlineCompletion` for the cursor of a view.
+#[derive(Debug, Clone)]
+pub struct DocumentInlineCompletion {
+    pub view_id: ViewId,
+    pub language_server_id: LanguageServerId,
+    /// The candidates in the order returned by the language server, never empty.
+    pub candidates: Vec<InlineCompletionCandidate>,
+    /// Index of the displayed candidate.
+    pub index: usize,
+    /// The first line of the untyped part of the displayed candidate, shown at the cursor.
+    pub(crate) ghost_text: Vec<InlineAnnotation>,
+    /// The remaining lines of the untyped part, shown as virtual lines below the cursor line.
+    pub ghost_lines: Vec<String>,
+    /// The char index of the line ending after which the `ghost_lines` are inserted.
+    pub ghost_lines_anchor: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct InlineCompletionCandidate {
+    /// Character range of the document that is replaced when accepting the candidate.
+    pub range: ops::Range<usize>,
+    pub insert_text: String,
+    /// Command to execute after the candidate was accepted.
+    pub command: Option<lsp::Command>,
+}
+
+impl DocumentInlineCompletion {
+    pub fn new(
+        view_id: ViewId,
+        language_server_id: LanguageServerId,
+        candidates: Vec<InlineCompletionCandidate>,
+    ) -> Option<Self> {
+        if candidates.is_empty() {
+            return None;
+        }
+        Some(Self {
+            view_id,
+            language_server_id,
+            candidates,
+            index: 0,
+            ghost_text: Vec::new(),
+            ghost_lines: Vec::new(),
+            ghost_lines_anchor: 0,
+        })
+    }
+
+    pub fn candidate(&self) -> &InlineCompletionCandidate {
+        &self.candidates[self.index]
+    }
+
+    /// Returns the part of the displayed candidate that has not been typed yet at `cursor`, or
+    /// `None` if the text before the cursor does not match the candidate.
+    pub fn remainder(&self, text: RopeSlice, cursor: usize) -> Option<&str> {
+        let candidate = self.candidate();
+        if cursor < candidate.range.start || cursor > text.len_chars() {
+            return None;
+        }
+        let typed: Cow<str> = text.slice(candidate.range.start..cursor).into();
+        candidate
+            .insert_text
+            .strip_prefix(&*typed)
+            .filter(|remainder| !remainder.is_empty())
+    }
+
+    /// Maps the ranges of all candidates through `changes`. Text typed at the start of a range
+    /// becomes part of it so that the candidates can be typed through.
+    pub fn map(&mut self, changes: &ChangeSet) {
+        changes.update_positions(self.candidates.iter_mut().flat_map(|candidate| {
+            std::iter::once((&mut candidate.range.start, Assoc::Before))
+                .chain(std::iter::once((&mut candidate.range.end, Assoc::After)))
+        }));
+    }
+
+    fn update_ghost_text(
+        &mut self,
+        text: RopeSlice,
+        selection: Option<&Selection>,
+        tab_width: usize,
+    ) {
+        let remainder = selection
+            .filter(|selection| selection.len() == 1)
+            .and_then(|selection| {
+                let cursor = selection.primary().cursor(text);
+                Some((cursor, self.remainder(text, cursor)?.to_owned()))
+            });
+        self.ghost_text.clear();
+        self.ghost_lines.clear();
+        let Some((cursor, remainder)) = remainder else {
+            return;
+        };
+
+        let mut lines = remainder.lines();
+        let first_line = lines.next().unwrap_or_default();
+        if !first_line.is_empty() {
+            self.ghost_text
+                .push(InlineAnnotation::new(cursor, first_line.to_owned()));
+        }
+        let tab = " ".repeat(tab_width);
+        self.ghost_lines
+            .extend(lines.map(|line| line.replace('\t', &tab)));
+        self.ghost_lines_anchor = line_end_char_index(&text, text.char_to_line(cursor));
+    }
+}
+
 /// Inlay hints for a single `(Document, View)` combo.
 ///
 /// There are `*_inlay_hints` field for each kind of hints an LSP can send since we offer the
@@ -781,6 +1051,7 @@ pub fn from(
             modified_since_accessed: false,
             language_servers: HashMap::new(),
             diff_handle: None,
+            blame_diff_handle: None,
             config,
             version_control_head: None,
             focused_at: std::time::Instant::now(),
@@ -788,6 +1059,7 @@ pub fn from(
             jump_labels: HashMap::new(),
             document_highlights: HashMap::new(),
             code_action_hints: HashSet::new(),
+            folds: HashMap::new(),
             color_swatches: None,
             document_links: Vec::new(),
             color_swatch_controller: TaskController::new(),
@@ -797,6 +1069,23 @@ pub fn from(
             previous_diagnostic_ids: HashMap::new(),
             pull_diagnostic_controller: TaskController::new(),
             document_link_controller: TaskController::new(),
+            folding_ranges: Vec::new(),
+            folding_range_controller: TaskController::new(),
+            semantic_tokens: None,
+            semantic_tokens_controller: TaskController::new(),
+            code_lenses: Vec::new(),
+            code_lens_controller: TaskController::new(),
+            linked_editing: None,
+            linked_editing_controller: TaskController::new(),
+            inline_completion: None,
+            inline_completion_controller: TaskController::new(),
+            inline_values: Vec::new(),
+            blame: None,
+            blame_controller: TaskController::new(),
+            blame_gutter: false,
+            hunk_diff: None,
+            diff_base_override: None,
+            conflicts: Vec::new(),
         }
     }
 
@@ -1331,11 +1620,7 @@ pub fn reload(
         self.pickup_last_saved_time();
         self.detect_indent_and_line_ending();
 
-        match provider_registry.get_diff_base(&path, trust_full) {
-            Some(diff_base) => self.set_diff_base(diff_base),
-            None => self.diff_handle = None,
-        }
-
+        self.update_diff_base(provider_registry, trust_full);
         self.version_control_head = provider_registry.get_current_head_name(&path, trust_full);
 
         Ok(())
@@ -1413,6 +1698,35 @@ pub fn set_language_by_language_id(
         Ok(())
     }
 
+    /// Updates the ghost text of the inline completion to the cursor of its view.
+    pub fn update_inline_completion_ghost_text(&mut self) {
+        let tab_width = self.tab_width();
+        if let Some(inline_completion) = &mut self.inline_completion {
+            let selection = self.selections.get(&inline_completion.view_id);
+            inline_completion.update_ghost_text(self.text.slice(..), selection, tab_width);
+        }
+    }
+
+    /// Cancels pending inline completion requests and discards the displayed candidates.
+    pub fn clear_inline_completion(&mut self) {
+        self.inline_completion_controller.cancel();
+        self.inline_completion = None;
+    }
+
+    /// Ends linked editing, removing the cursors that were added to the linked ranges.
+    pub fn stop_linked_editing(&mut self) {
+        self.linked_editing_controller.cancel();
+        let Some(linked_editing) = self.linked_editing.take() else {
+            return;
+        };
+        if let Some(selection) = self.selections.get(&linked_editing.view_id) {
+            if selection.len() > 1 {
+                let selection = selection.clone().into_single();
+                self.set_selection(linked_editing.view_id, selection);
+            }
+        }
+    }
+
     /// Select text within the [`Document`].
     pub fn set_selection(&mut self, view_id: ViewId, selection: Selection) {
         self.last_search_match.remove(&view_id);
@@ -1477,6 +1791,10 @@ pub fn remove_view(&mut self, view_id: ViewId) {
         self.document_highlight_controllers.remove(&view_id);
         self.code_action_hints.remove(&view_id);
         self.code_action_controllers.remove(&view_id);
+        self.folds.remove(&view_id);
+        if self.hunk_diff.is_some_and(|(id, _)| id == view_id) {
+            self.hunk_diff = None;
+        }
     }
 
     /// Apply a [`Transaction`] to the [`Document`] to change its text.
@@ -1560,6 +1878,9 @@ fn apply_impl(
         if let Some(diff_handle) = &self.diff_handle {
             diff_handle.update_document(self.text.clone(), false);
         }
+        if let Some(diff_handle) = &self.blame_diff_handle {
+            diff_handle.update_document(self.text.clone(), false);
+        }
 
         // map diagnostics over changes too
         changes.update_positions(self.diagnostics.iter_mut().map(|diagnostic| {
@@ -1627,6 +1948,7 @@ fn apply_impl(
             apply_inlay_hint_changes(other_inlay_hints);
             apply_inlay_hint_changes(padding_after_inlay_hints);
         }
+        apply_inlay_hint_changes(&mut self.inline_values);
 
         for highlights in self.document_highlights.values_mut() {
             let text_len = self.text.len_chars();
@@ -1650,6 +1972,22 @@ fn apply_impl(
             highlights.ranges = updated;
         }
 
+        let text = self.text.slice(..);
+        self.folding_ranges = self
+            .folding_ranges
+            .iter()
+            .filter_map(|fold| fold.map(text, changes))
+            .collect();
+        fold::sort(&mut self.folding_ranges);
+        for folds in self.folds.values_mut() {
+            let closed = folds
+                .closed
+                .iter()
+                .filter_map(|fold| fold.map(text, changes))
+                .collect();
+            *folds = DocumentFolds::new(closed, text);
+        }
+
         helix_event::dispatch(DocumentDidChange {
             doc: self,
             view: view_id,
@@ -2020,16 +2358,93 @@ pub fn diff_handle(&self) -> Option<&DiffHandle> {
         self.diff_handle.as_ref()
     }
 
+    /// Returns the git blame of `line`, accounting for changes since `HEAD`, if the blame
+    /// has been loaded.
+    pub fn line_blame(&self, line: usize) -> Option<LineBlame<'_>> {
+        let blame = self.blame.as_ref()?;
+        let diff = self.blame_diff_handle().map(DiffHandle::load);
+        Some(blame.line_blame(line, diff.as_ref()))
+    }
+
+    /// Returns the diff against the base selected by the `diff-base` option, which the blame
+    /// is mapped through. Unlike [`Document::diff_handle`] this ignores
+    /// [`Document::diff_base_override`].
+    pub fn blame_diff_handle(&self) -> Option<&DiffHandle> {
+        if self.diff_base_override.is_some() {
+            self.blame_diff_handle.as_ref()
+        } else {
+            self.diff_handle.as_ref()
+        }
+    }
+
+    /// Reads the diff base selected by the `diff-base` option, or by
+    /// [`Document::diff_base_override`] if set.
+    pub fn update_diff_base(&mut self, provider_registry: &DiffProviderRegistry, trust_full: bool) {
+        let Some(path) = self.path() else {
+            return;
+        };
+        let diff_base = match &self.diff_base_override {
+            Some(base) => base
+                .read(path, provider_registry, trust_full)
+                .map_err(|err| log::debug!("failed to read diff base {base}: {err:#}"))
+                .ok(),
+            None => provider_registry.get_diff_base(path, self.config.load().diff_base, trust_full),
+        };
+        match diff_base {
+            Some(diff_base) => self.set_diff_base(diff_base),
+            None => self.diff_handle = None,
+        }
+        self.update_blame_diff_base(provider_registry, trust_full);
+    }
+
+    /// Reads the diff base selected by the `diff-base` option for
+    /// [`Document::blame_diff_handle`] if [`Document::diff_base_override`] is set, and drops it
+    /// otherwise.
+    pub fn update_blame_diff_base(
+        &mut self,
+        provider_registry: &DiffProviderRegistry,
+        trust_full: bool,
+    ) {
+        let diff_base = self
+            .path()
+            .filter(|_| self.diff_base_override.is_some())
+            .and_then(|path| {
+                provider_registry.get_diff_base(path, self.config.load().diff_base, trust_full)
+            });
+        self.blame_diff_handle = match diff_base {
+            Some(diff_base) => Self::diff_handle_with_base(
+                self.blame_diff_handle.take(),
+                diff_base,
+                self.encoding,
+                &self.text,
+            ),
+            None => None,
+        };
+    }
+
     /// Intialize/updates the differ for this document with a new base.
     pub fn set_diff_base(&mut self, diff_base: Vec<u8>) {
-        if let Ok((diff_base, ..)) = from_reader(&mut diff_base.as_slice(), Some(self.encoding)) {
-            if let Some(differ) = &self.diff_handle {
+        self.diff_handle = Self::diff_handle_with_base(
+            self.diff_handle.take(),
+            diff_base,
+            self.encoding,
+            &self.text,
+        );
+    }
+
+    fn diff_handle_with_base(
+        differ: Option<DiffHandle>,
+        diff_base: Vec<u8>,
+        encoding: &'static encoding::Encoding,
+        text: &Rope,
+    ) -> Option<DiffHandle> {
+        let (diff_base, ..) = from_reader(&mut diff_base.as_slice(), Some(encoding)).ok()?;
+        match differ {
+            Some(differ) => {
                 differ.update_diff_base(diff_base);
-                return;
+                Some(differ)
             }
-            self.diff_handle = Some(DiffHandle::new(diff_base, self.text.clone()))
-        } else {
-            self.diff_handle = None;
+            None => Some(DiffHandle::new(diff_base, text.clone())),
         }
     }
 
@@ -2508,6 +2923,39 @@ pub fn code_action_controller(&mut self, view_id: ViewId) -> &mut TaskController
         self.code_action_controllers.entry(view_id).or_default()
     }
 
+    /// The closed folds of this document in `view_id`.
+    pub fn folds(&self, view_id: ViewId) -> &[Fold] {
+        self.folds
+            .get(&view_id)
+            .map_or(&[], |folds| folds.closed.as_slice())
+    }
+
+    /// Replace the closed folds of this document in `view_id`.
+    pub fn set_folds(&mut self, view_id: ViewId, folds: Vec<Fold>) {
+        if folds.is_empty() {
+            self.folds.remove(&view_id);
+        } else {
+            let folds = DocumentFolds::new(folds, self.text.slice(..));
+            self.folds.insert(view_id, folds);
+        }
+    }
+
+    /// The regions of this document that can be folded.
+    ///
+    /// These are the folding ranges of the language servers or, if no language server provides
+    /// folding ranges, the `@fold` captures of the `folds.scm` query.
+    pub fn foldable_ranges(&self) -> Vec<Fold> {
+        if !self.folding_ranges.is_empty() {
+            return self.folding_ranges.clone();
+        }
+        match &self.syntax {
+            Some(syntax) => {
+                fold::treesitter_folds(syntax, self.text.slice(..), &self.syn_loader.load())
+            }
+            None => Vec::new(),
+        }
+    }
+
     /// Get the inlay hints for this document and `view_id`.
     pub fn inlay_hints(&self, view_id: ViewId) -> Option<&DocumentInlayHints> {
         self.inlay_hints.get(&view_id)
@@ -2737,6 +3185,49 @@ fn test_line_ending() {
         );
     }
 
+    #[test]
+    fn inline_completion_ghost_text() {
+        let mut doc = Document::from(
+            Rope::from("let x = fo\n"),
+            None,
+            Arc::new(ArcSwap::new(Arc::new(Config::default()))),
+            Arc::new(ArcSwap::from_pointee(syntax::Loader::default())),
+        );
+        let view = ViewId::default();
+        doc.set_selection(view, Selection::point(10));
+        doc.inline_completion = DocumentInlineCompletion::new(
+            view,
+            LanguageServerId::default(),
+            vec![InlineCompletionCandidate {
+                range: 8..10,
+                insert_text: "foo(\n\tbar,\n)".to_string(),
+                command: None,
+            }],
+        );
+
+        doc.update_inline_completion_ghost_text();
+        let inline_completion = doc.inline_completion.as_ref().unwrap();
+        assert_eq!(inline_completion.ghost_text.len(), 1);
+        assert_eq!(inline_completion.ghost_text[0].char_idx, 10);
+        assert_eq!(inline_completion.ghost_text[0].text, "o(");
+        let indent = " ".repeat(doc.tab_width());
+        assert_eq!(
+            inline_completion.ghost_lines,
+            [format!("{indent}bar,"), ")".into()]
+        );
+        assert_eq!(inline_completion.ghost_lines_anchor, 10);
+
+        // Text before the cursor that does not match the candidate hides the ghost text.
+        doc.set_selection(view, Selection::point(4));
+        doc.update_inline_completion_ghost_text();
+        let inline_completion = doc.inline_completion.as_ref().unwrap();
+        assert!(inline_completion.ghost_text.is_empty());
+        assert!(inline_completion.ghost_lines.is_empty());
+
+        doc.clear_inline_completion();
+        assert!(doc.inline_completion.is_none());
+    }
+
     macro_rules! decode {
         ($name:ident, $label:expr, $label_override:expr) => {
             #[test]
@@ -1,6 +1,8 @@
 use crate::{
     annotations::diagnostics::{DiagnosticFilter, InlineDiagnosticsConfig},
     clipboard::ClipboardProvider,
+    debug_console::DebugConsole,
+    debug_panel::DebugPanel,
     document::{
         DocumentOpenError, DocumentSavedEventFuture, DocumentSavedEventResult, Mode, SavePoint,
     },
@@ -10,13 +12,15 @@
     info::Info,
     input::KeyEvent,
     register::Registers,
+    session::{Session, SessionJump, SessionLayout, SessionRange, SessionView},
     theme::{self, Theme},
-    tree::{self, Tree},
+    tree::{self, Layout, Tree},
+    view::JumpList,
     Document, DocumentId, View, ViewId,
 };
 use helix_event::dispatch;
 use helix_loader::workspace_trust::{ImplicitTrustLevel, TrustQuery, WorkspaceTrust};
-use helix_vcs::DiffProviderRegistry;
+use helix_vcs::{DiffBase, DiffProviderRegistry};
 
 use futures_util::stream::select_all::SelectAll;
 use futures_util::StreamExt;
@@ -51,7 +55,7 @@
         config::{AutoPairConfig, IndentationHeuristic, LanguageServerFeature, SoftWrap},
     },
     time::now_timestamp,
-    Change, LineEnding, Position, Range, Selection, Uri, NATIVE_LINE_ENDING,
+    Change, LineEnding, Position, Range, RopeSlice, Selection, Uri, NATIVE_LINE_ENDING,
 };
 use helix_dap::{self as dap, registry::DebugAdapterId};
 use helix_lsp::lsp;
@@ -220,6 +224,8 @@ pub struct FilePickerConfig {
     /// WalkBuilder options
     /// Maximum Depth to recurse directories in file picker and global search. Defaults to `None`.
     pub max_depth: Option<usize>,
+    /// Whether to colour files by their git status. Defaults to true.
+    pub git_status: bool,
 }
 
 impl Default for FilePickerConfig {
@@ -234,6 +240,7 @@ fn default() -> Self {
             git_global: true,
             git_exclude: true,
             max_depth: None,
+            git_status: true,
         }
     }
 }
@@ -264,6 +271,8 @@ pub struct FileExplorerConfig {
     pub git_exclude: bool,
     /// Whether to flatten single-child directories in file explorer. Defaults to true.
     pub flatten_dirs: bool,
+    /// Whether to colour files and directories by their git status. Defaults to true.
+    pub git_status: bool,
 }
 
 impl Default for FileExplorerConfig {
@@ -277,6 +286,7 @@ fn default() -> Self {
             git_global: false,
             git_exclude: false,
             flatten_dirs: true,
+            git_status: true,
         }
     }
 }
@@ -334,6 +344,9 @@ pub struct Config {
     /// etc. Optionally, this can be a list of 2-tuples to specify a
     /// global list of characters to pair. Defaults to true.
     pub auto_pairs: AutoPairConfig,
+    /// Edit linked ranges, like the names of an opening and closing tag, together in insert
+    /// mode. Defaults to true.
+    pub linked_editing: bool,
     /// Automatic auto-completion, automatically pop up without user trigger. Defaults to true.
     pub auto_completion: bool,
     /// Enable filepath completion.
@@ -348,6 +361,9 @@ pub struct Config {
     pub auto_format: bool,
     /// Default register used for yank/paste. Defaults to '"'
     pub default_yank_register: char,
+    /// Number of yanks and deletions kept in the yank history. Defaults to 30, 0 disables the
+    /// history.
+    pub yank_history_size: usize,
     /// Automatic save on focus lost and/or after delay.
     /// Time delay in milliseconds since last edit after which auto save timer triggers.
     /// Time delay defaults to false with 3000ms delay. Focus lost defaults to false.
@@ -440,6 +456,13 @@ pub struct Config {
     /// Display diagnostic below the line they occur.
     pub inline_diagnostics: InlineDiagnosticsConfig,
     pub end_of_line_diagnostics: DiagnosticFilter,
+    /// What the diff gutter compares documents against. Defaults to `head`.
+    pub diff_base: DiffBase,
+    /// Whether the lines a change replaced are always shown below the change under the cursor.
+    /// Defaults to `false`.
+    pub inline_hunk_diff: bool,
+    /// Git blame of the cursor line displayed at its end.
+    pub inline_blame: InlineBlameConfig,
     // Set to override the default clipboard provider
     pub clipboard_provider: ClipboardProvider,
     /// Whether to read settings from [EditorConfig](https://editorconfig.org) files. Defaults to
@@ -535,6 +558,25 @@ pub fn code_action_hint(&self) -> bool {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
+pub struct InlineBlameConfig {
+    /// Whether to show the blame of the cursor line. Defaults to false.
+    pub enable: bool,
+    /// The text shown for committed lines. `{author}`, `{email}`, `{date}`, `{commit}` and
+    /// `{summary}` are replaced with the details of the commit that last changed the line.
+    pub format: String,
+}
+
+impl Default for InlineBlameConfig {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            format: "{author}, {date} • {summary}".to_string(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
 pub struct SessionConfig {
@@ -543,6 +585,21 @@ pub struct SessionConfig {
     /// Maximum age in days for session entries before garbage collection removes them.
     /// Set to 0 to disable GC. Defaults to 90.
     pub gc_max_age: u64,
+    /// Registers whose contents are stored in named sessions. Defaults to `"`, `/` and `:`.
+    pub registers: Vec<char>,
+    /// Save the breakpoints of the workspace when exiting and restore them on startup.
+    /// Defaults to true.
+    pub persist_breakpoints: bool,
+    /// Save recorded macros when exiting and restore them to their registers on startup.
+    /// Defaults to true.
+    pub persist_macros: bool,
+    /// Registers whose contents are saved when exiting and restored on startup. Defaults to none.
+    ///
+    /// This is independent of `registers`: loading a named session, including with `--session`
+    /// on startup, replaces the contents restored here for the registers saved in the session.
+    pub persist_registers: Vec<char>,
+    /// Save the yank history when exiting and restore it on startup. Defaults to false.
+    pub persist_yank_history: bool,
 }
 
 impl Default for SessionConfig {
@@ -550,6 +607,11 @@ fn default() -> Self {
         Self {
             restore_cursor: false,
             gc_max_age: 90,
+            registers: vec!['"', '/', ':'],
+            persist_breakpoints: true,
+            persist_macros: true,
+            persist_registers: Vec::new(),
+            persist_yank_history: false,
         }
     }
 }
@@ -751,6 +813,12 @@ pub struct LspConfig {
     pub inlay_hints_length_limit: Option<NonZeroU8>,
     /// Display document color swatches
     pub display_color_swatches: bool,
+    /// Highlight LSP semantic tokens on top of the tree-sitter highlights
+    pub display_semantic_tokens: bool,
+    /// Display code lenses above the lines they refer to
+    pub display_code_lenses: bool,
+    /// Request inline completions while typing in insert mode and display them as ghost text
+    pub inline_completion: bool,
     /// Whether to enable snippet support
     pub snippets: bool,
     /// Whether to include declaration in the goto reference query
@@ -771,6 +839,9 @@ fn default() -> Self {
             snippets: true,
             goto_reference_include_declaration: true,
             display_color_swatches: true,
+            display_semantic_tokens: true,
+            display_code_lenses: false,
+            inline_completion: true,
         }
     }
 }
@@ -920,6 +991,9 @@ pub enum StatusLineElement {
     /// Current version control information
     VersionControl,
 
+    /// What the diff gutter compares the file against
+    DiffBase,
+
     /// Indicator for selected register
     Register,
 
@@ -1035,6 +1109,8 @@ pub enum GutterType {
     Diff,
     /// Indicator for when code actions are available
     CodeActionHint,
+    /// Show the git commit that last changed each line, when toggled with `:blame-gutter`
+    Blame,
 }
 
 impl std::str::FromStr for GutterType {
@@ -1047,8 +1123,9 @@ fn from_str(s: &str) -> Result<Self, Self::Err> {
             "line-numbers" => Ok(Self::LineNumbers),
             "diff" => Ok(Self::Diff),
             "code-action-hint" => Ok(Self::CodeActionHint),
+            "blame" => Ok(Self::Blame),
             _ => anyhow::bail!(
-                "Gutter type can only be `diagnostics`, `spacer`, `line-numbers` or `diff`."
+                "Gutter type can only be `diagnostics`, `spacer`, `line-numbers`, `diff`, `code-action-hint` or `blame`."
             ),
         }
     }
@@ -1317,11 +1394,13 @@ fn default() -> Self {
             gutters: GutterConfig::default(),
             middle_click_paste: true,
             auto_pairs: AutoPairConfig::default(),
+            linked_editing: true,
             auto_completion: true,
             path_completion: true,
             word_completion: WordCompletion::default(),
             auto_format: true,
             default_yank_register: '"',
+            yank_history_size: 30,
             auto_save: AutoSave::default(),
             idle_timeout: Duration::from_millis(250),
             completion_timeout: Duration::from_millis(250),
@@ -1363,6 +1442,9 @@ fn default() -> Self {
             enable_diagnostics: true,
             inline_diagnostics: InlineDiagnosticsConfig::default(),
             end_of_line_diagnostics: DiagnosticFilter::Enable(Severity::Hint),
+            diff_base: DiffBase::default(),
+            inline_hunk_diff: false,
+            inline_blame: InlineBlameConfig::default(),
             clipboard_provider: ClipboardProvider::default(),
             editor_config: true,
             rainbow_brackets: false,
@@ -1386,23 +1468,122 @@ fn default() -> Self {
     }
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Breakpoint {
+    #[serde(skip)]
     pub id: Option<usize>,
+    #[serde(skip)]
     pub verified: bool,
+    #[serde(skip)]
     pub message: Option<String>,
 
     pub line: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub column: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub condition: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub hit_condition: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub log_message: Option<String>,
+    /// Disabled breakpoints are kept but not sent to the debugger.
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+impl Default for Breakpoint {
+    fn default() -> Self {
+        Self {
+            id: None,
+            verified: false,
+            message: None,
+            line: 0,
+            column: None,
+            condition: None,
+            hit_condition: None,
+            log_message: None,
+            enabled: true,
+        }
+    }
+}
+
+/// A breakpoint that is hit when a function with the given name is called.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FunctionBreakpoint {
+    #[serde(skip)]
+    pub verified: bool,
+    #[serde(skip)]
+    pub message: Option<String>,
+
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub condition: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hit_condition: Option<String>,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+/// A breakpoint that is hit when the value of a variable is accessed, as determined by the
+/// debugger's `dataBreakpointInfo` response.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DataBreakpoint {
+    #[serde(skip)]
+    pub verified: bool,
+    #[serde(skip)]
+    pub message: Option<String>,
+
+    /// The debugger's id of the data. Only persisted if the debugger allows it.
+    pub data_id: String,
+    pub description: String,
+    /// `read`, `write` or `readWrite`, the debugger's default if `None`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub access_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub condition: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hit_condition: Option<String>,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Whether the data id stays valid across debug sessions. Only such breakpoints are saved.
+    #[serde(skip_serializing, default = "default_true")]
+    pub can_persist: bool,
+}
+
+/// A breakpoint on a memory address of the debuggee, which is only valid during the debug
+/// session it was set in.
+#[derive(Debug, Clone)]
+pub struct InstructionBreakpoint {
+    pub verified: bool,
+    pub message: Option<String>,
+
+    pub instruction_reference: String,
+    pub offset: Option<isize>,
+    pub condition: Option<String>,
+    pub hit_condition: Option<String>,
+    pub enabled: bool,
+}
+
+fn default_true() -> bool {
+    true
 }
 
 use futures_util::stream::{Flatten, Once};
 
 type Diagnostics = BTreeMap<Uri, Vec<(lsp::Diagnostic, DiagnosticProvider)>>;
 
+/// The state of the LSP `workspace/diagnostic` requests of a language server.
+#[derive(Debug, Default)]
+pub struct WorkspaceDiagnostics {
+    /// The result ids of the last reports for each document. They are sent with the next request
+    /// so that the server can report documents whose diagnostics did not change as unchanged.
+    pub result_ids: HashMap<lsp::Url, String>,
+    /// The token that partial results of the pending request are reported with.
+    pub partial_result_token: Option<lsp::ProgressToken>,
+    /// Whether to send another request once the pending request finished.
+    pub refresh: bool,
+}
+
 pub struct Editor {
     /// Current editing mode.
     pub mode: Mode,
@@ -1421,12 +1602,27 @@ pub struct Editor {
     pub registers: Registers,
     pub macro_recording: Option<(char, Vec<KeyEvent>)>,
     pub macro_replaying: Vec<char>,
+    /// Keys of the macros replayed by the macro under replay, which are replayed before its
+    /// remaining keys.
+    pub macro_queue: VecDeque<KeyEvent>,
+    /// The macros recorded or edited, by register. They are saved on exit if
+    /// `session.persist-macros` is enabled.
+    pub macros: BTreeMap<char, String>,
+    /// The scratch buffers opened with `:macro-edit` and the registers they are written to.
+    pub macro_buffers: HashMap<DocumentId, char>,
     pub language_servers: helix_lsp::Registry,
     pub diagnostics: Diagnostics,
+    /// The state of the `workspace/diagnostic` requests of each language server.
+    pub workspace_diagnostics: HashMap<LanguageServerId, WorkspaceDiagnostics>,
     pub diff_providers: DiffProviderRegistry,
 
     pub debug_adapters: dap::registry::Registry,
     pub breakpoints: HashMap<PathBuf, Vec<Breakpoint>>,
+    pub function_breakpoints: Vec<FunctionBreakpoint>,
+    pub data_breakpoints: Vec<DataBreakpoint>,
+    pub instruction_breakpoints: Vec<InstructionBreakpoint>,
+    pub debug_panel: DebugPanel,
+    pub debug_console: DebugConsole,
 
     pub syn_loader: Arc<ArcSwap<syntax::Loader>>,
     pub theme_loader: Arc<theme::Loader>,
@@ -1571,20 +1767,35 @@ pub fn new(
             selected_register: None,
             macro_recording: None,
             macro_replaying: Vec::new(),
+            macro_queue: VecDeque::new(),
+            macros: BTreeMap::new(),
+            macro_buffers: HashMap::new(),
             theme: theme_loader.default(),
             language_servers,
             diagnostics: Diagnostics::new(),
+            workspace_diagnostics: HashMap::new(),
             diff_providers: DiffProviderRegistry::default(),
             debug_adapters: dap::registry::Registry::new(),
             breakpoints: HashMap::new(),
+            function_breakpoints: Vec::new(),
+            data_breakpoints: Vec::new(),
+            instruction_breakpoints: Vec::new(),
+            debug_panel: DebugPanel::default(),
+            debug_console: DebugConsole::default(),
             syn_loader,
             theme_loader,
             last_theme: None,
             last_selection: None,
-            registers: Registers::new(Box::new(arc_swap::access::Map::new(
-                Arc::clone(&config),
-                |config: &Config| &config.clipboard_provider,
-            ))),
+            registers: Registers::new(
+                Box::new(arc_swap::access::Map::new(
+                    Arc::clone(&config),
+                    |config: &Config| &config.clipboard_provider,
+                )),
+                Box::new(arc_swap::access::Map::new(
+                    Arc::clone(&config),
+                    |config: &Config| &config.yank_history_size,
+                )),
+            ),
             status_msg: None,
             autoinfo: None,
             idle_timer: Box::pin(sleep(conf.idle_timeout)),
@@ -1647,6 +1858,15 @@ pub fn config(&self) -> DynGuard<Config> {
     pub fn refresh_config(&mut self, old_config: &Config) {
         let config = self.config();
         self.auto_pairs = (&config.auto_pairs).into();
+        if config.diff_base != old_config.diff_base {
+            for doc in self.documents.values_mut() {
+                let trust_full = self
+                    .workspace_trust
+                    .query(doc.workspace_root(), TrustQuery::Git)
+                    .is_trusted();
+                doc.update_diff_base(&self.diff_providers, trust_full);
+            }
+        }
         self.reset_idle_timer();
         self._refresh();
         helix_event::dispatch(crate::events::ConfigDidChange {
@@ -2074,8 +2294,6 @@ fn replace_document_in_view(&mut self, current_view: ViewId, doc_id: DocumentId)
     }
 
     pub fn switch(&mut self, id: DocumentId, action: Action) {
-        use crate::tree::Layout;
-
         if !self.documents.contains_key(&id) {
             log::error!("cannot switch to document that does not exist (anymore)");
             return;
@@ -2262,9 +2480,7 @@ pub fn open(&mut self, path: &Path, action: Action) -> Result<DocumentId, Docume
                 .workspace_trust
                 .query(doc.workspace_root(), TrustQuery::Git)
                 .is_trusted();
-            if let Some(diff_base) = self.diff_providers.get_diff_base(&path, trust_full) {
-                doc.set_diff_base(diff_base);
-            }
+            doc.update_diff_base(&self.diff_providers, trust_full);
             doc.set_version_control_head(
                 self.diff_providers.get_current_head_name(&path, trust_full),
             );
@@ -2431,6 +2647,215 @@ fn restore_doc_cursor_position(&mut self, doc_id: DocumentId, path: &Path) {
         );
     }
 
+    /// Captures the open buffers, split layout, jumplists, configured registers and working
+    /// directory as a [`Session`].
+    pub fn capture_session(&self) -> Session {
+        let buffers = self
+            .documents()
+            .filter_map(|doc| doc.path().map(Path::to_path_buf))
+            .collect();
+        let registers = self
+            .config()
+            .session
+            .registers
+            .iter()
+            .filter(|name| !matches!(name, '_' | '#' | '.' | '%' | '*' | '+'))
+            .filter_map(|&name| {
+                let values: Vec<_> = self
+                    .registers
+                    .read(name, self)?
+                    .map(|value| value.into_owned())
+                    .collect();
+                Some((name, values))
+            })
+            .collect();
+
+        Session {
+            cwd: helix_stdx::env::current_working_dir(),
+            buffers,
+            layout: self.capture_session_layout(self.tree.root()),
+            registers,
+            timestamp: now_timestamp(),
+        }
+    }
+
+    fn capture_session_layout(&self, node: ViewId) -> SessionLayout {
+        if let Some((layout, children)) = self.tree.container(node) {
+            return SessionLayout::Container {
+                layout,
+                children: children
+                    .iter()
+                    .map(|&child| self.capture_session_layout(child))
+                    .collect(),
+            };
+        }
+
+        let view = self.tree.get(node);
+        let doc = &self.documents[&view.doc];
+        let text = doc.text().slice(..);
+        let view_offset = doc.view_offset(view.id);
+        let view_anchor = helix_core::coords_at_pos(text, view_offset.anchor.min(text.len_chars()));
+        let jumps = view
+            .jumps
+            .iter()
+            .filter_map(|(doc_id, selection)| {
+                let doc = self.documents.get(doc_id)?;
+                Some(SessionJump {
+                    path: doc.path()?.to_path_buf(),
+                    range: session_range(doc.text().slice(..), selection.primary()),
+                })
+            })
+            .collect();
+
+        SessionLayout::View(SessionView {
+            path: doc.path().map(Path::to_path_buf),
+            range: session_range(text, doc.selection(view.id).primary()),
+            view_anchor_row: view_anchor.row,
+            view_anchor_col: view_anchor.col,
+            horizontal_offset: view_offset.horizontal_offset,
+            jumps,
+            focused: view.id == self.tree.focus,
+        })
+    }
+
+    /// Replaces all buffers and splits with the contents of `session`. Fails without changing
+    /// anything if a buffer has unsaved changes.
+    pub fn restore_session(&mut self, session: &Session) -> anyhow::Result<()> {
+        if let Some(doc) = self.documents().find(|doc| doc.is_modified()) {
+            bail!("{} has unsaved changes", doc.display_name());
+        }
+        self.set_cwd(&session.cwd).map_err(|err| {
+            anyhow!(
+                "Could not change working directory to '{}': {err}",
+                session.cwd.display()
+            )
+        })?;
+
+        // Start over from a single view showing an empty scratch buffer.
+        let views: Vec<_> = self
+            .tree
+            .views()
+            .filter(|(_, focused)| !focused)
+            .map(|(view, _)| view.id)
+            .collect();
+        for view_id in views {
+            self.close(view_id);
+        }
+        let old_docs: Vec<_> = self.documents.keys().copied().collect();
+        self.new_file(Action::Replace);
+        for doc_id in old_docs {
+            // Unmodified buffers can always be closed.
+            let _ = self.close_document(doc_id, false);
+        }
+
+        for path in &session.buffers {
+            if let Err(err) = self.open(path, Action::Load) {
+                log::warn!("Failed to open {} from session: {err}", path.display());
+            }
+        }
+        let root_view = self.tree.focus;
+        if let Some(focused) = self.restore_session_layout(&session.layout, root_view) {
+            self.focus(focused);
+        }
+
+        for (&name, values) in &session.registers {
+            if let Err(err) = self.registers.write(name, values.clone()) {
+                log::warn!("Failed to restore register {name} from session: {err}");
+            }
+        }
+        Ok(())
+    }
+
+    /// Rebuilds `layout` in place of `view_id`, returning the view that was focused when the
+    /// session was saved.
+    fn restore_session_layout(
+        &mut self,
+        layout: &SessionLayout,
+        view_id: ViewId,
+    ) -> Option<ViewId> {
+        self.focus(view_id);
+        let (layout, children) = match layout {
+            SessionLayout::View(session_view) => {
+                self.restore_session_view(session_view);
+                return session_view.focused.then_some(view_id);
+            }
+            SessionLayout::Container { layout, children } => (layout, children),
+        };
+
+        // Splitting the last view of the container appends the new view right after it.
+        let action = match layout {
+            Layout::Horizontal => Action::HorizontalSplit,
+            Layout::Vertical => Action::VerticalSplit,
+        };
+        let mut view_ids = vec![view_id];
+        for _ in children.iter().skip(1) {
+            let doc_id = view!(self).doc;
+            self.switch(doc_id, action);
+            view_ids.push(self.tree.focus);
+        }
+
+        let mut focused = None;
+        for (child, view_id) in children.iter().zip(view_ids) {
+            focused = self.restore_session_layout(child, view_id).or(focused);
+        }
+        focused
+    }
+
+    fn restore_session_view(&mut self, session_view: &SessionView) {
+        let view_id = self.tree.focus;
+        let doc_id = match &session_view.path {
+            Some(path) => match self.open(path, Action::Replace) {
+                Ok(doc_id) => doc_id,
+                Err(err) => {
+                    log::warn!("Failed to open {} from session: {err}", path.display());
+                    return;
+                }
+            },
+            None => self.new_file(Action::Replace),
+        };
+
+        let jumps: Vec<_> = session_view
+            .jumps
+            .iter()
+            .filter_map(|jump| {
+                let doc = self.document_by_path(&jump.path)?;
+                let range = restore_session_range(doc.text().slice(..), jump.range);
+                Some((doc.id(), Selection::from(range)))
+            })
+            .collect();
+
+        let doc = doc_mut!(self, &doc_id);
+        let text = doc.text().slice(..);
+        let max_line = text.len_lines().saturating_sub(1);
+        let view_anchor = helix_core::pos_at_coords(
+            text,
+            Position::new(
+                session_view.view_anchor_row.min(max_line),
+                session_view.view_anchor_col,
+            ),
+            true,
+        );
+        let range = restore_session_range(text, session_view.range);
+        doc.set_selection(view_id, range.into());
+        doc.set_view_offset(
+            view_id,
+            crate::view::ViewPosition {
+                anchor: view_anchor,
+                horizontal_offset: session_view.horizontal_offset,
+                vertical_offset: 0,
+            },
+        );
+
+        let mut jumps = jumps.into_iter();
+        if let Some(first) = jumps.next() {
+            let view = self.tree.get_mut(view_id);
+            view.jumps = JumpList::new(first);
+            for jump in jumps {
+                view.jumps.push(jump);
+            }
+        }
+    }
+
     pub fn close_document(&mut self, doc_id: DocumentId, force: bool) -> Result<(), CloseError> {
         let doc = match self.documents.get(&doc_id) {
             Some(doc) => doc,
@@ -2446,6 +2871,7 @@ pub fn close_document(&mut self, doc_id: DocumentId, force: bool) -> Result<(),
 
         // This will also disallow any follow-up writes
         self.saves.remove(&doc_id);
+        self.macro_buffers.remove(&doc_id);
 
         enum Action {
             Close(ViewId),
@@ -2924,6 +3350,28 @@ fn jump_to(&mut self, view_id: ViewId, dest_doc_id: DocumentId, mut selection: S
     }
 }
 
+fn session_range(text: RopeSlice, range: Range) -> SessionRange {
+    let anchor = helix_core::coords_at_pos(text, range.anchor.min(text.len_chars()));
+    let head = helix_core::coords_at_pos(text, range.head.min(text.len_chars()));
+    SessionRange {
+        anchor_row: anchor.row,
+        anchor_col: anchor.col,
+        head_row: head.row,
+        head_col: head.col,
+    }
+}
+
+fn restore_session_range(text: RopeSlice, range: SessionRange) -> Range {
+    let max_line = text.len_lines().saturating_sub(1);
+    let anchor = Position::new(range.anchor_row.min(max_line), range.anchor_col);
+    let head = Position::new(range.head_row.min(max_line), range.head_col);
+    // Allow positions on line endings: the head of a forward range is one past the cursor.
+    Range::new(
+        helix_core::pos_at_coords(text, anchor, false),
+        helix_core::pos_at_coords(text, head, false),
+    )
+}
+
 fn try_restore_indent(doc: &mut Document, view: &mut View) {
     use helix_core::{
         chars::char_is_whitespace,
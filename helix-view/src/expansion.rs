@@ -1,11 +1,21 @@
 use std::borrow::Cow;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-use helix_core::command_line::{ExpansionKind, Token, TokenKind, Tokenizer};
+use helix_core::{
+    command_line::{ExpansionKind, Token, TokenKind, Tokenizer},
+    diagnostic::NumberOrString,
+    graphemes::next_grapheme_boundary,
+    syntax::{config::LanguageServerFeature, QueryMatchIterEvent},
+    textobject::{self, TextObject},
+    Diagnostic,
+};
+use helix_loader::workspace_trust::TrustQuery;
+use helix_lsp::lsp;
+use serde_json::Value;
 
 use anyhow::{anyhow, bail, ensure, Result};
 
-use crate::Editor;
+use crate::{Document, Editor, View};
 
 /// Variables that can be expanded in the command mode (`:`) via the expansion syntax.
 ///
@@ -54,6 +64,24 @@ pub enum Variable {
     BufferStem,
     Indentation,
     SelectionLineStartIndentation,
+    /// The message of the most severe diagnostic under the primary cursor, or else on the line
+    /// of the primary cursor.
+    DiagnosticMessage,
+    /// The code of the diagnostic of `DiagnosticMessage`.
+    DiagnosticCode,
+    /// The source of the diagnostic of `DiagnosticMessage`, e.g. `rustc`.
+    DiagnosticSource,
+    /// The syntax node under the primary cursor, e.g. an identifier, or the word under the
+    /// primary cursor when there is no syntax tree.
+    SymbolUnderCursor,
+    /// The name of the innermost function or method containing the primary cursor, found with
+    /// the tags query of the language.
+    CurrentFunction,
+    /// The name of the checked out branch of the repository of the currently focused document.
+    GitBranch,
+    /// The root of the repository of the currently focused document, or of the current working
+    /// directory for scratch buffers.
+    GitRoot,
 }
 
 impl Variable {
@@ -74,6 +102,13 @@ impl Variable {
         Self::LineEnding,
         Self::Indentation,
         Self::SelectionLineStartIndentation,
+        Self::DiagnosticMessage,
+        Self::DiagnosticCode,
+        Self::DiagnosticSource,
+        Self::SymbolUnderCursor,
+        Self::CurrentFunction,
+        Self::GitBranch,
+        Self::GitRoot,
     ];
 
     pub const fn as_str(&self) -> &'static str {
@@ -94,6 +129,13 @@ pub const fn as_str(&self) -> &'static str {
             Self::BufferStem => "buffer_stem",
             Self::Indentation => "indentation",
             Self::SelectionLineStartIndentation => "selection_line_start_indentation",
+            Self::DiagnosticMessage => "diagnostic_message",
+            Self::DiagnosticCode => "diagnostic_code",
+            Self::DiagnosticSource => "diagnostic_source",
+            Self::SymbolUnderCursor => "symbol_under_cursor",
+            Self::CurrentFunction => "current_function",
+            Self::GitBranch => "git_branch",
+            Self::GitRoot => "git_root",
         }
     }
 
@@ -115,6 +157,13 @@ pub fn from_name(s: &str) -> Option<Self> {
             "buffer_stem" => Some(Self::BufferStem),
             "indentation" => Some(Self::Indentation),
             "selection_line_start_indentation" => Some(Self::SelectionLineStartIndentation),
+            "diagnostic_message" => Some(Self::DiagnosticMessage),
+            "diagnostic_code" => Some(Self::DiagnosticCode),
+            "diagnostic_source" => Some(Self::DiagnosticSource),
+            "symbol_under_cursor" => Some(Self::SymbolUnderCursor),
+            "current_function" => Some(Self::CurrentFunction),
+            "git_branch" => Some(Self::GitBranch),
+            "git_root" => Some(Self::GitRoot),
             _ => None,
         }
     }
@@ -150,6 +199,7 @@ pub fn expand<'a>(editor: &Editor, token: Token<'a>) -> Result<Cow<'a, str>> {
         TokenKind::Expand => expand_inner(editor, token.content),
         TokenKind::Expansion(ExpansionKind::Shell) => expand_shell(editor, token.content),
         TokenKind::Expansion(ExpansionKind::Register) => expand_register(editor, token.content),
+        TokenKind::Expansion(ExpansionKind::Lsp) => expand_lsp(editor, token.content),
         // Note: see the docs for this variant.
         TokenKind::ExpansionKind => unreachable!(
             "expansion name tokens cannot be emitted when command line validation is enabled"
@@ -235,6 +285,50 @@ pub fn expand_register<'a>(editor: &Editor, content: Cow<'a, str>) -> Result<Cow
     }
 }
 
+/// Execute a language server command and expand to its result.
+///
+/// The contents are the command followed by its arguments as JSON values, like the arguments of
+/// `:lsp-workspace-command`. String results are inserted as-is and other values as JSON.
+pub fn expand_lsp<'a>(editor: &Editor, content: Cow<'a, str>) -> Result<Cow<'a, str>> {
+    // Recursively expand the expansion's content before executing the command.
+    let content = expand_inner(editor, content)?;
+    let content = content.trim();
+    let (command, arguments) = content
+        .split_once(char::is_whitespace)
+        .unwrap_or((content, ""));
+    ensure!(!command.is_empty(), "Empty language server command");
+    let arguments = serde_json::Deserializer::from_str(arguments)
+        .into_iter()
+        .collect::<Result<Vec<Value>, _>>()
+        .map_err(|err| anyhow!("failed to parse arguments: {err}"))?;
+
+    let doc = doc!(editor);
+    let future = doc
+        .language_servers_with_feature(LanguageServerFeature::WorkspaceCommand)
+        .find(|ls| {
+            ls.capabilities()
+                .execute_command_provider
+                .as_ref()
+                .is_some_and(|options| options.commands.iter().any(|c| c == command))
+        })
+        .and_then(|ls| {
+            ls.command(lsp::Command {
+                title: command.to_owned(),
+                command: command.to_owned(),
+                arguments: (!arguments.is_empty()).then_some(arguments),
+            })
+        })
+        .ok_or_else(|| anyhow!("`{command}` is not supported for any language server"))?;
+
+    let result = tokio::task::block_in_place(|| helix_lsp::block_on(future))
+        .map_err(|err| anyhow!("Failed to execute `{command}`: {err}"))?;
+    Ok(match result {
+        None | Some(Value::Null) => Cow::Borrowed(""),
+        Some(Value::String(text)) => Cow::Owned(text),
+        Some(value) => Cow::Owned(value.to_string()),
+    })
+}
+
 /// Expand a token's contents recursively.
 fn expand_inner<'a>(editor: &Editor, content: Cow<'a, str>) -> Result<Cow<'a, str>> {
     let mut escaped = String::new();
@@ -369,5 +463,189 @@ fn expand_variable(editor: &Editor, variable: Variable) -> Result<Cow<'static, s
         } else {
             Cow::Borrowed("")
         }),
+        Variable::DiagnosticMessage => Ok(cursor_diagnostic(doc, view)
+            .map_or(Cow::Borrowed(""), |diagnostic| {
+                Cow::Owned(diagnostic.message.clone())
+            })),
+        Variable::DiagnosticCode => Ok(
+            match cursor_diagnostic(doc, view).and_then(|diagnostic| diagnostic.code.as_ref()) {
+                Some(NumberOrString::Number(code)) => Cow::Owned(code.to_string()),
+                Some(NumberOrString::String(code)) => Cow::Owned(code.clone()),
+                None => Cow::Borrowed(""),
+            },
+        ),
+        Variable::DiagnosticSource => Ok(cursor_diagnostic(doc, view)
+            .and_then(|diagnostic| diagnostic.source.clone())
+            .map_or(Cow::Borrowed(""), Cow::Owned)),
+        Variable::SymbolUnderCursor => {
+            let range = doc.selection(view.id).primary();
+            let cursor = range.cursor(text);
+            let Some(syntax) = doc.syntax() else {
+                let word = textobject::textobject_word(text, range, TextObject::Inside, 1, false);
+                return Ok(Cow::Owned(word.fragment(text).into_owned()));
+            };
+            let start = text.char_to_byte(cursor) as u32;
+            let end = text.char_to_byte(next_grapheme_boundary(text, cursor)) as u32;
+            Ok(syntax
+                .named_descendant_for_byte_range(start, end)
+                .filter(|node| node.named_child_count() == 0)
+                .map_or(Cow::Borrowed(""), |node| {
+                    let range = node.byte_range();
+                    Cow::Owned(
+                        text.byte_slice(range.start as usize..range.end as usize)
+                            .to_string(),
+                    )
+                }))
+        }
+        Variable::CurrentFunction => {
+            Ok(current_function(editor, doc, view).map_or(Cow::Borrowed(""), Cow::Owned))
+        }
+        Variable::GitBranch => Ok(doc
+            .version_control_head()
+            .map_or(Cow::Borrowed(""), |head| Cow::Owned(head.to_string()))),
+        Variable::GitRoot => {
+            let dir = match doc.path().and_then(|path| path.parent()) {
+                Some(dir) => dir.to_path_buf(),
+                None => helix_stdx::env::current_working_dir(),
+            };
+            let trust_full = editor
+                .workspace_trust
+                .query(&helix_loader::find_workspace_in(&dir).0, TrustQuery::Git)
+                .is_trusted();
+            Ok(editor
+                .diff_providers
+                .get_repo_root(&dir, trust_full)
+                .map_or(Cow::Borrowed(""), |root| {
+                    Cow::Owned(root.to_string_lossy().into_owned())
+                }))
+        }
+    }
+}
+
+/// The most severe diagnostic under the primary cursor, or else on the line of the primary
+/// cursor.
+fn cursor_diagnostic<'a>(doc: &'a Document, view: &View) -> Option<&'a Diagnostic> {
+    let text = doc.text().slice(..);
+    let cursor = doc.selection(view.id).primary().cursor(text);
+    let line = text.char_to_line(cursor);
+    let most_severe = |diagnostics: &mut dyn Iterator<Item = &'a Diagnostic>| {
+        diagnostics.max_by_key(|diagnostic| diagnostic.severity)
+    };
+    most_severe(
+        &mut doc.diagnostics().iter().filter(|diagnostic| {
+            diagnostic.range.start <= cursor && diagnostic.range.end >= cursor
+        }),
+    )
+    .or_else(|| {
+        most_severe(
+            &mut doc
+                .diagnostics()
+                .iter()
+                .filter(|diagnostic| diagnostic.line == line),
+        )
+    })
+}
+
+/// The name of the innermost function or method definition of the tags query containing the
+/// primary cursor.
+fn current_function(editor: &Editor, doc: &Document, view: &View) -> Option<String> {
+    let syntax = doc.syntax()?;
+    let text = doc.text().slice(..);
+    let cursor = text.char_to_byte(doc.selection(view.id).primary().cursor(text)) as u32;
+    let loader = editor.syn_loader.load();
+    let mut tags = syntax.tags(text, &loader, cursor..cursor + 1);
+
+    let mut innermost: Option<(u32, std::ops::Range<u32>)> = None;
+    while let Some(event) = tags.next() {
+        let QueryMatchIterEvent::Match(mat) = event else {
+            continue;
+        };
+        let Some(tag_query) = loader.tag_query(tags.current_language()) else {
+            continue;
+        };
+        let query = &tag_query.query;
+        let name_capture = query.get_capture("name");
+        let mut definition = None;
+        let mut name = None;
+        for node in mat.nodes.iter() {
+            if name_capture == Some(node.capture) {
+                name = Some(node.node.byte_range());
+            } else if matches!(
+                query.capture_name(node.capture),
+                "definition.function" | "definition.method"
+            ) {
+                definition = Some(node.node.byte_range());
+            }
+        }
+        let (Some(definition), Some(name)) = (definition, name) else {
+            continue;
+        };
+        let len = definition.end - definition.start;
+        if definition.contains(&cursor) && innermost.as_ref().is_none_or(|(min, _)| len < *min) {
+            innermost = Some((len, name));
+        }
+    }
+
+    let (_, name) = innermost?;
+    Some(
+        text.byte_slice(name.start as usize..name.end as usize)
+            .to_string(),
+    )
+}
+
+/// Expands the VS Code style variables, e.g. `${file}` or `${workspaceFolder}`, in a string of a
+/// debug launch configuration.
+///
+/// Unlike the command line variables the paths are always absolute, without the home directory
+/// folded to `~`, since they are passed to the debug adapter.
+pub fn expand_launch_variables(editor: &Editor, content: &str) -> Result<String> {
+    let mut expanded = String::with_capacity(content.len());
+    let mut rest = content;
+    while let Some(start) = rest.find("${") {
+        expanded.push_str(&rest[..start]);
+        let end = rest[start..]
+            .find('}')
+            .ok_or_else(|| anyhow!("Unterminated variable in '{content}'"))?
+            + start;
+        expanded.push_str(&expand_launch_variable(editor, &rest[start + 2..end])?);
+        rest = &rest[end + 1..];
     }
+    expanded.push_str(rest);
+    Ok(expanded)
+}
+
+fn expand_launch_variable(editor: &Editor, name: &str) -> Result<Cow<'static, str>> {
+    let doc = doc!(editor);
+    let workspace = helix_loader::find_workspace().0;
+    let path_to_string =
+        |path: Option<&Path>| path.map_or(String::new(), |path| path.to_string_lossy().into());
+
+    let value = match name {
+        "file" => path_to_string(doc.path()),
+        "fileDirname" => path_to_string(doc.path().and_then(|path| path.parent())),
+        "fileExtname" => doc
+            .path()
+            .and_then(|path| path.extension())
+            .map_or(String::new(), |ext| format!(".{}", ext.to_string_lossy())),
+        "fileBasename" => return expand_variable(editor, Variable::BufferName),
+        "fileBasenameNoExtension" => return expand_variable(editor, Variable::BufferStem),
+        "relativeFile" => path_to_string(
+            doc.path()
+                .map(|path| path.strip_prefix(&workspace).unwrap_or(path)),
+        ),
+        "lineNumber" => return expand_variable(editor, Variable::CursorLine),
+        "selectedText" => return expand_variable(editor, Variable::Selection),
+        "workspaceFolder" | "workspaceRoot" | "fileWorkspaceFolder" => {
+            path_to_string(Some(&workspace))
+        }
+        "workspaceFolderBasename" => path_to_string(workspace.file_name().map(Path::new)),
+        "cwd" => path_to_string(Some(&helix_stdx::env::current_working_dir())),
+        "userHome" => path_to_string(helix_stdx::path::home_dir().ok().as_deref()),
+        "pathSeparator" | "/" => std::path::MAIN_SEPARATOR_STR.to_owned(),
+        _ => match name.strip_prefix("env:") {
+            Some(var) => std::env::var(var).unwrap_or_default(),
+            None => bail!("Unsupported variable '${{{name}}}'"),
+        },
+    };
+    Ok(Cow::Owned(value))
 }
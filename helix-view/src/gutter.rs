@@ -1,6 +1,7 @@
 use std::fmt::Write;
 
 use helix_core::syntax::config::LanguageServerFeature;
+use helix_vcs::{DiffHandle, LineBlame};
 
 use crate::{
     editor::GutterType,
@@ -33,6 +34,7 @@ pub fn style<'doc>(
             GutterType::Spacer => padding(editor, doc, view, theme, is_focused),
             GutterType::Diff => diff(editor, doc, view, theme, is_focused),
             GutterType::CodeActionHint => code_action_hint(editor, doc, view, theme, is_focused),
+            GutterType::Blame => blame(editor, doc, view, theme, is_focused),
         }
     }
 
@@ -43,6 +45,7 @@ pub fn width(self, view: &View, doc: &Document) -> usize {
             GutterType::Spacer => 1,
             GutterType::Diff => 1,
             GutterType::CodeActionHint => 1,
+            GutterType::Blame => blame_width(doc),
         }
     }
 }
@@ -141,6 +144,55 @@ pub fn diff<'doc>(
     }
 }
 
+/// The width of a commit id, an author name truncated to 12 columns and a date, separated and
+/// followed by a space.
+const BLAME_WIDTH: usize = 8 + 1 + 12 + 1 + 10 + 1;
+
+fn blame_width(doc: &Document) -> usize {
+    if doc.blame_gutter && doc.blame.is_some() {
+        BLAME_WIDTH
+    } else {
+        0
+    }
+}
+
+pub fn blame<'doc>(
+    _editor: &'doc Editor,
+    doc: &'doc Document,
+    _view: &View,
+    theme: &Theme,
+    _is_focused: bool,
+) -> GutterFn<'doc> {
+    let style = theme.get("ui.virtual.blame");
+    let Some(blame) = doc.blame.as_ref().filter(|_| doc.blame_gutter) else {
+        return Box::new(move |_, _, _, _| None);
+    };
+    let diff = doc.blame_diff_handle().map(DiffHandle::load);
+
+    Box::new(
+        move |line: usize, _selected: bool, first_visual_line: bool, out: &mut String| {
+            if !first_visual_line {
+                return None;
+            }
+            let line_blame = blame.line_blame(line, diff.as_ref());
+            // Only the first line of a run of lines from the same commit is annotated.
+            if line > 0 && blame.line_blame(line - 1, diff.as_ref()) == line_blame {
+                return None;
+            }
+            match line_blame {
+                LineBlame::Committed(commit) => {
+                    let author: String = commit.author.chars().take(12).collect();
+                    write!(out, "{} {author:<12} {}", commit.id, commit.date).ok();
+                }
+                LineBlame::Uncommitted => {
+                    write!(out, "{:<1$}", "Not committed yet", BLAME_WIDTH - 1).ok();
+                }
+            }
+            Some(style)
+        },
+    )
+}
+
 pub fn line_numbers<'doc>(
     editor: &'doc Editor,
     doc: &'doc Document,
@@ -239,6 +291,9 @@ pub fn breakpoints<'doc>(
     let error = theme.get("error");
     let info = theme.get("info");
     let breakpoint_style = theme.get("ui.debug.breakpoint");
+    let disabled_style = theme
+        .try_get_exact("ui.debug.breakpoint.disabled")
+        .unwrap_or_else(|| theme.get("ui.text.inactive"));
 
     let breakpoints = doc.path().and_then(|path| editor.breakpoints.get(path));
 
@@ -256,7 +311,9 @@ pub fn breakpoints<'doc>(
                 .iter()
                 .find(|breakpoint| breakpoint.line == line)?;
 
-            let style = if breakpoint.condition.is_some() && breakpoint.log_message.is_some() {
+            let style = if !breakpoint.enabled {
+                disabled_style
+            } else if breakpoint.condition.is_some() && breakpoint.log_message.is_some() {
                 error.underline_style(UnderlineStyle::Line)
             } else if breakpoint.condition.is_some() {
                 error
@@ -24,6 +24,12 @@ pub struct Handlers {
     pub auto_save: Sender<AutoSaveEvent>,
     pub document_colors: Sender<lsp::DocumentColorsEvent>,
     pub document_links: Sender<lsp::DocumentLinksEvent>,
+    pub folding_ranges: Sender<lsp::FoldingRangesEvent>,
+    pub semantic_tokens: Sender<lsp::SemanticTokensEvent>,
+    pub code_lenses: Sender<lsp::CodeLensEvent>,
+    pub inline_values: Sender<lsp::InlineValuesEvent>,
+    pub linked_editing: Sender<lsp::LinkedEditingEvent>,
+    pub inline_completion: Sender<lsp::InlineCompletionEvent>,
     pub word_index: word_index::Handler,
     pub pull_diagnostics: Sender<lsp::PullDiagnosticsEvent>,
     pub pull_all_documents_diagnostics: Sender<lsp::PullAllDocumentsDiagnosticsEvent>,
@@ -1,16 +1,31 @@
-use crate::editor::{Action, Breakpoint};
-use crate::{align_view, Align, Editor};
-use anyhow::bail;
+use crate::debug_console::ConsoleLineKind;
+use crate::editor::{
+    Action, Breakpoint, DataBreakpoint, FunctionBreakpoint, InstructionBreakpoint,
+};
+use crate::handlers::lsp::InlineValuesEvent;
+use crate::session::SessionBreakpoints;
+use crate::{align_view, Align, DocumentId, Editor};
+use anyhow::{anyhow, bail};
 use dap::requests::DisconnectArguments;
 use dap::requests::ThreadsArguments;
-use helix_core::Selection;
+use futures_util::future::BoxFuture;
+use futures_util::FutureExt;
+use helix_core::line_ending::line_end_char_index;
+use helix_core::syntax::config::LanguageServerFeature;
+use helix_core::text_annotations::InlineAnnotation;
+use helix_core::{Range, Rope, Selection};
 use helix_dap::{
     self as dap, registry::DebugAdapterId, Client, ConnectionType, Payload, Request, ThreadId,
 };
+use helix_event::send_blocking;
 use helix_lsp::block_on;
+use helix_lsp::util::{lsp_range_to_range, range_to_lsp_range};
+use helix_lsp::{lsp, OffsetEncoding};
 use log::{error, warn};
 use serde_json::{json, Value};
+use std::collections::BTreeMap;
 use std::fmt::Write;
+use std::future::Future;
 use std::path::PathBuf;
 
 #[macro_export]
@@ -87,32 +102,114 @@ pub fn jump_to_stack_frame(editor: &mut Editor, frame: &helix_dap::StackFrame) {
     align_view(doc, view, Align::Center);
 }
 
+/// The maximum displayed length of an inline value (excluding the added trailing `…`).
+const INLINE_VALUE_MAX_LEN: usize = 40;
+
+/// The inline values requested for the active stack frame, see
+/// [`Editor::request_inline_values`].
+pub struct InlineValuesRequest {
+    doc_id: DocumentId,
+    text: Rope,
+    frame: dap::StackFrame,
+    /// The char range of the document the values are shown in.
+    start: usize,
+    end: usize,
+}
+
+/// Resolves to the inline values returned by the language server.
+pub type InlineValuesFuture = BoxFuture<'static, Option<(Vec<lsp::InlineValue>, OffsetEncoding)>>;
+
+/// A value to show at the end of a line of the source of the active stack frame.
+enum InlineValue {
+    Text(String),
+    Variable { name: String, case_sensitive: bool },
+    Expression(String),
+}
+
+/// Collects the identifiers of the syntax tree between `start` and `end` (byte offsets), used
+/// to look up variables when no language server provides inline values.
+fn collect_identifiers(
+    node: helix_core::tree_sitter::Node,
+    start: u32,
+    end: u32,
+    identifiers: &mut Vec<(u32, u32)>,
+) {
+    if node.end_byte() <= start || node.start_byte() >= end {
+        return;
+    }
+    if node.kind() == "identifier" {
+        identifiers.push((node.start_byte(), node.end_byte()));
+        return;
+    }
+    for child in node.children() {
+        collect_identifiers(child, start, end, identifiers);
+    }
+}
+
+fn format_inline_value(value: &str) -> String {
+    let value = value.split_whitespace().collect::<Vec<_>>().join(" ");
+    if value.chars().count() > INLINE_VALUE_MAX_LEN {
+        let mut value: String = value.chars().take(INLINE_VALUE_MAX_LEN).collect();
+        value.push('…');
+        value
+    } else {
+        value
+    }
+}
+
+/// Checks that the debugger supports conditional and hit conditional breakpoints, if any are
+/// used.
+fn check_conditions(
+    debugger: &dap::Client,
+    has_condition: bool,
+    has_hit_condition: bool,
+) -> Result<(), anyhow::Error> {
+    let Some(caps) = debugger.caps.as_ref() else {
+        return Ok(());
+    };
+    if has_condition && !caps.supports_conditional_breakpoints.unwrap_or_default() {
+        bail!("Can't edit breakpoint: debugger does not support conditional breakpoints")
+    }
+    if has_hit_condition
+        && !caps
+            .supports_hit_conditional_breakpoints
+            .unwrap_or_default()
+    {
+        bail!("Can't edit breakpoint: debugger does not support hit conditional breakpoints")
+    }
+    Ok(())
+}
+
 pub fn breakpoints_changed(
     debugger: &mut dap::Client,
     path: PathBuf,
     breakpoints: &mut [Breakpoint],
 ) -> Result<(), anyhow::Error> {
+    check_conditions(
+        debugger,
+        breakpoints
+            .iter()
+            .any(|b| b.enabled && b.condition.is_some()),
+        breakpoints
+            .iter()
+            .any(|b| b.enabled && b.hit_condition.is_some()),
+    )?;
     if let Some(caps) = debugger.caps.as_ref() {
-        if breakpoints.iter().any(|b| b.condition.is_some())
-            && !caps.supports_conditional_breakpoints.unwrap_or_default()
-        {
-            bail!("Can't edit breakpoint: debugger does not support conditional breakpoints")
-        }
-        if breakpoints.iter().any(|b| b.hit_condition.is_some())
-            && !caps
-                .supports_hit_conditional_breakpoints
-                .unwrap_or_default()
-        {
-            bail!("Can't edit breakpoint: debugger does not support hit conditional breakpoints")
-        }
-        if breakpoints.iter().any(|b| b.log_message.is_some())
+        if breakpoints
+            .iter()
+            .any(|b| b.enabled && b.log_message.is_some())
             && !caps.supports_log_points.unwrap_or_default()
         {
             bail!("Can't edit breakpoint: debugger does not support logpoints")
         }
     }
+    for breakpoint in breakpoints.iter_mut().filter(|b| !b.enabled) {
+        breakpoint.id = None;
+        breakpoint.verified = false;
+    }
     let source_breakpoints = breakpoints
         .iter()
+        .filter(|breakpoint| breakpoint.enabled)
         .map(|breakpoint| helix_dap::SourceBreakpoint {
             line: breakpoint.line + 1, // convert from 0-indexing to 1-indexing (TODO: could set debugger to 0-indexing on init)
             column: breakpoint.column,
@@ -125,7 +222,8 @@ pub fn breakpoints_changed(
     let request = debugger.set_breakpoints(path, source_breakpoints);
     match block_on(request) {
         Ok(Some(dap_breakpoints)) => {
-            for (breakpoint, dap_breakpoint) in breakpoints.iter_mut().zip(dap_breakpoints) {
+            let enabled = breakpoints.iter_mut().filter(|b| b.enabled);
+            for (breakpoint, dap_breakpoint) in enabled.zip(dap_breakpoints) {
                 breakpoint.id = dap_breakpoint.id;
                 breakpoint.verified = dap_breakpoint.verified;
                 breakpoint.message = dap_breakpoint.message;
@@ -143,7 +241,430 @@ pub fn breakpoints_changed(
     Ok(())
 }
 
+/// The fields shared by the breakpoint kinds besides source breakpoints, which are all set with
+/// one request per kind.
+trait KindBreakpoint {
+    fn enabled(&self) -> bool;
+    fn has_condition(&self) -> bool;
+    fn has_hit_condition(&self) -> bool;
+    fn set_verified(&mut self, verified: bool, message: Option<String>);
+}
+
+macro_rules! impl_kind_breakpoint {
+    ($($breakpoint:ty),*) => {$(
+        impl KindBreakpoint for $breakpoint {
+            fn enabled(&self) -> bool {
+                self.enabled
+            }
+
+            fn has_condition(&self) -> bool {
+                self.condition.is_some()
+            }
+
+            fn has_hit_condition(&self) -> bool {
+                self.hit_condition.is_some()
+            }
+
+            fn set_verified(&mut self, verified: bool, message: Option<String>) {
+                self.verified = verified;
+                self.message = message;
+            }
+        }
+    )*};
+}
+
+impl_kind_breakpoint!(FunctionBreakpoint, DataBreakpoint, InstructionBreakpoint);
+
+/// Sends all enabled `breakpoints` of one kind, converted with `to_dap`, to the debugger with
+/// `set_breakpoints` and updates their verification state from the response.
+fn kind_breakpoints_changed<'a, B: KindBreakpoint, D, F>(
+    debugger: &'a dap::Client,
+    breakpoints: &mut [B],
+    kind: &str,
+    supported: Option<bool>,
+    to_dap: impl Fn(&B) -> D,
+    set_breakpoints: impl FnOnce(&'a dap::Client, Vec<D>) -> F,
+) -> Result<(), anyhow::Error>
+where
+    F: Future<Output = dap::Result<Vec<dap::Breakpoint>>>,
+{
+    if !supported.unwrap_or_default() {
+        bail!("Debugger does not support {kind} breakpoints")
+    }
+    check_conditions(
+        debugger,
+        breakpoints.iter().any(|b| b.enabled() && b.has_condition()),
+        breakpoints
+            .iter()
+            .any(|b| b.enabled() && b.has_hit_condition()),
+    )?;
+    let dap_breakpoints = breakpoints
+        .iter()
+        .filter(|breakpoint| breakpoint.enabled())
+        .map(to_dap)
+        .collect();
+    let response = block_on(set_breakpoints(debugger, dap_breakpoints))
+        .map_err(|err| anyhow!("Failed to set {kind} breakpoints: {err}"))?;
+    for breakpoint in breakpoints.iter_mut() {
+        breakpoint.set_verified(false, None);
+    }
+    let enabled = breakpoints.iter_mut().filter(|b| b.enabled());
+    for (breakpoint, dap_breakpoint) in enabled.zip(response) {
+        breakpoint.set_verified(dap_breakpoint.verified, dap_breakpoint.message);
+    }
+    Ok(())
+}
+
+pub fn function_breakpoints_changed(
+    debugger: &mut dap::Client,
+    breakpoints: &mut [FunctionBreakpoint],
+) -> Result<(), anyhow::Error> {
+    let supported = debugger.capabilities().supports_function_breakpoints;
+    kind_breakpoints_changed(
+        debugger,
+        breakpoints,
+        "function",
+        supported,
+        |breakpoint| dap::FunctionBreakpoint {
+            name: breakpoint.name.clone(),
+            condition: breakpoint.condition.clone(),
+            hit_condition: breakpoint.hit_condition.clone(),
+        },
+        dap::Client::set_function_breakpoints,
+    )
+}
+
+pub fn data_breakpoints_changed(
+    debugger: &mut dap::Client,
+    breakpoints: &mut [DataBreakpoint],
+) -> Result<(), anyhow::Error> {
+    let supported = debugger.capabilities().supports_data_breakpoints;
+    kind_breakpoints_changed(
+        debugger,
+        breakpoints,
+        "data",
+        supported,
+        |breakpoint| dap::DataBreakpoint {
+            data_id: breakpoint.data_id.clone(),
+            access_type: breakpoint.access_type.clone(),
+            condition: breakpoint.condition.clone(),
+            hit_condition: breakpoint.hit_condition.clone(),
+        },
+        dap::Client::set_data_breakpoints,
+    )
+}
+
+pub fn instruction_breakpoints_changed(
+    debugger: &mut dap::Client,
+    breakpoints: &mut [InstructionBreakpoint],
+) -> Result<(), anyhow::Error> {
+    let supported = debugger.capabilities().supports_instruction_breakpoints;
+    kind_breakpoints_changed(
+        debugger,
+        breakpoints,
+        "instruction",
+        supported,
+        |breakpoint| dap::InstructionBreakpoint {
+            instruction_reference: breakpoint.instruction_reference.clone(),
+            offset: breakpoint.offset,
+            condition: breakpoint.condition.clone(),
+            hit_condition: breakpoint.hit_condition.clone(),
+        },
+        dap::Client::set_instruction_breakpoints,
+    )
+}
+
 impl Editor {
+    /// Restores the breakpoints saved for the current workspace.
+    pub fn restore_breakpoints(&mut self) {
+        let workspace = helix_loader::find_workspace().0;
+        match SessionBreakpoints::load(&workspace) {
+            Ok(breakpoints) => {
+                self.breakpoints.extend(breakpoints.source);
+                self.function_breakpoints = breakpoints.function;
+                self.data_breakpoints = breakpoints.data;
+            }
+            Err(err) => log::warn!("Failed to restore breakpoints: {err:#}"),
+        }
+    }
+
+    /// Saves the breakpoints of the current workspace so they can be restored on the next start.
+    /// Breakpoints only valid during a debug session are left out.
+    pub fn save_breakpoints(&self) {
+        let workspace = helix_loader::find_workspace().0;
+        let breakpoints = SessionBreakpoints {
+            source: self
+                .breakpoints
+                .iter()
+                .filter(|(_, breakpoints)| !breakpoints.is_empty())
+                .map(|(path, breakpoints)| (path.clone(), breakpoints.clone()))
+                .collect(),
+            function: self.function_breakpoints.clone(),
+            data: self
+                .data_breakpoints
+                .iter()
+                .filter(|breakpoint| breakpoint.can_persist)
+                .cloned()
+                .collect(),
+        };
+        if let Err(err) = breakpoints.save(&workspace) {
+            log::warn!("Failed to save breakpoints: {err:#}");
+        }
+    }
+
+    /// Removes the inline values of all documents, e.g. when the debuggee resumes.
+    pub fn clear_inline_values(&mut self) {
+        for doc in self.documents_mut() {
+            doc.inline_values.clear();
+        }
+    }
+
+    /// Shows the values of the variables used in the source of the active stack frame at the end
+    /// of their lines, from the first visible line up to the line where execution stopped.
+    ///
+    /// The variables are determined by the language server's `textDocument/inlineValue` or, if no
+    /// language server supports it, by looking up the identifiers of the syntax tree in the
+    /// variables of the stack frame. The values are requested in the background, see
+    /// [`Editor::request_inline_values`].
+    pub fn update_inline_values(&mut self) {
+        self.clear_inline_values();
+        send_blocking(&self.handlers.inline_values, InlineValuesEvent);
+    }
+
+    /// Prepares the inline values of the active stack frame. The returned future resolves to
+    /// the language server's inline values, it is `None` if no language server supports them.
+    /// The values are shown with [`Editor::apply_inline_values`].
+    pub fn request_inline_values(
+        &self,
+    ) -> Option<(InlineValuesRequest, Option<InlineValuesFuture>)> {
+        let debugger = self.debug_adapters.get_active_client()?;
+        let frame = debugger.current_stack_frame()?.clone();
+        let path = frame.source.as_ref()?.path.as_ref()?;
+        let doc = self.document_by_path(path)?;
+        let doc_id = doc.id();
+        let text = doc.text().clone();
+        let slice = text.slice(..);
+        let stopped_line = frame.line.saturating_sub(1);
+        if stopped_line >= text.len_lines() {
+            return None;
+        }
+        let first_line = self
+            .tree
+            .views()
+            .find(|(view, _)| view.doc == doc_id)
+            .map_or(0, |(view, _)| {
+                slice.char_to_line(doc.view_offset(view.id).anchor.min(text.len_chars()))
+            })
+            .min(stopped_line);
+        let start = text.line_to_char(first_line);
+        let end = line_end_char_index(&slice, stopped_line);
+
+        let future = doc
+            .language_servers_with_feature(LanguageServerFeature::InlineValue)
+            .next()
+            .and_then(|language_server| {
+                let offset_encoding = language_server.offset_encoding();
+                let stopped = dap_pos_to_pos(&text, frame.line, frame.column)
+                    .unwrap_or(end)
+                    .min(end);
+                let context = lsp::InlineValueContext {
+                    frame_id: frame.id as i32,
+                    stopped_location: range_to_lsp_range(
+                        &text,
+                        Range::point(stopped),
+                        offset_encoding,
+                    ),
+                };
+                let future = language_server.text_document_inline_value(
+                    doc.identifier(),
+                    range_to_lsp_range(&text, Range::new(start, end), offset_encoding),
+                    context,
+                    None,
+                )?;
+                Some(
+                    async move {
+                        match future.await {
+                            Ok(values) => values.map(|values| (values, offset_encoding)),
+                            Err(err) => {
+                                log::error!("inline value request failed: {err}");
+                                None
+                            }
+                        }
+                    }
+                    .boxed(),
+                )
+            });
+        let request = InlineValuesRequest {
+            doc_id,
+            text,
+            frame,
+            start,
+            end,
+        };
+        Some((request, future))
+    }
+
+    /// Shows the inline values requested with [`Editor::request_inline_values`], unless the
+    /// active stack frame or the document changed in the meantime. `lsp_values` are the values
+    /// returned by the language server, the syntax tree is used if there are none.
+    pub async fn apply_inline_values(
+        &mut self,
+        request: InlineValuesRequest,
+        lsp_values: Option<(Vec<lsp::InlineValue>, OffsetEncoding)>,
+    ) {
+        let InlineValuesRequest {
+            doc_id,
+            text,
+            frame,
+            start,
+            end,
+        } = request;
+        let Some(debugger) = self.debug_adapters.get_active_client() else {
+            return;
+        };
+        if debugger.current_stack_frame().map(|frame| frame.id) != Some(frame.id) {
+            return;
+        }
+        let Some(doc) = self.documents.get(&doc_id) else {
+            return;
+        };
+        if doc.text() != &text {
+            return;
+        }
+        let slice = text.slice(..);
+
+        let mut values: Vec<(usize, InlineValue)> = Vec::new();
+        if let Some((lsp_values, offset_encoding)) = lsp_values {
+            let text_at = |range: lsp::Range| {
+                let range = lsp_range_to_range(&text, range, offset_encoding)?;
+                Some((
+                    slice.char_to_line(range.from()),
+                    String::from(slice.slice(range.from()..range.to())),
+                ))
+            };
+            for value in lsp_values {
+                let value = match value {
+                    lsp::InlineValue::Text(value) => {
+                        text_at(value.range).map(|(line, _)| (line, InlineValue::Text(value.text)))
+                    }
+                    lsp::InlineValue::VariableLookup(lookup) => {
+                        text_at(lookup.range).map(|(line, name)| {
+                            (
+                                line,
+                                InlineValue::Variable {
+                                    name: lookup.variable_name.unwrap_or(name),
+                                    case_sensitive: lookup.case_sensitive_lookup,
+                                },
+                            )
+                        })
+                    }
+                    lsp::InlineValue::EvaluatableExpression(expression) => {
+                        text_at(expression.range).map(|(line, text)| {
+                            (
+                                line,
+                                InlineValue::Expression(expression.expression.unwrap_or(text)),
+                            )
+                        })
+                    }
+                };
+                values.extend(value);
+            }
+        } else if let Some(syntax) = doc.syntax() {
+            let start_byte = slice.char_to_byte(start) as u32;
+            let end_byte = slice.char_to_byte(end) as u32;
+            let mut identifiers = Vec::new();
+            collect_identifiers(
+                syntax.tree_for_byte_range(start_byte, end_byte).root_node(),
+                start_byte,
+                end_byte,
+                &mut identifiers,
+            );
+            values.extend(identifiers.into_iter().map(|(start, end)| {
+                let line = slice.byte_to_line(start as usize);
+                let name = slice.byte_slice(start as usize..end as usize).into();
+                (
+                    line,
+                    InlineValue::Variable {
+                        name,
+                        case_sensitive: true,
+                    },
+                )
+            }));
+        }
+        if values.is_empty() {
+            return;
+        }
+
+        // Fetch the variables of the frame only if they are needed for a lookup.
+        let mut variables = Vec::new();
+        if values
+            .iter()
+            .any(|(_, value)| matches!(value, InlineValue::Variable { .. }))
+        {
+            let scopes = debugger.scopes(frame.id).await.unwrap_or_default();
+            for scope in scopes.iter().filter(|scope| !scope.expensive) {
+                match debugger.variables(scope.variables_reference).await {
+                    Ok(scope_variables) => variables.extend(scope_variables),
+                    Err(err) => log::error!("Failed to get variables: {err}"),
+                }
+            }
+        }
+
+        let mut lines: BTreeMap<usize, Vec<String>> = BTreeMap::new();
+        for (line, value) in values {
+            let label = match value {
+                InlineValue::Text(text) => format_inline_value(&text),
+                InlineValue::Variable {
+                    name,
+                    case_sensitive,
+                } => {
+                    let Some(variable) = variables.iter().find(|variable| {
+                        if case_sensitive {
+                            variable.name == name
+                        } else {
+                            variable.name.eq_ignore_ascii_case(&name)
+                        }
+                    }) else {
+                        continue;
+                    };
+                    format!("{name} = {}", format_inline_value(&variable.value))
+                }
+                InlineValue::Expression(expression) => {
+                    match debugger
+                        .eval(expression.clone(), Some(frame.id), None)
+                        .await
+                    {
+                        Ok(response) => {
+                            format!("{expression} = {}", format_inline_value(&response.result))
+                        }
+                        Err(_) => continue,
+                    }
+                }
+            };
+            let labels = lines.entry(line).or_default();
+            if !labels.contains(&label) {
+                labels.push(label);
+            }
+        }
+
+        let Some(doc) = self.documents.get_mut(&doc_id) else {
+            return;
+        };
+        // The document may have changed while waiting for the responses.
+        if doc.text() != &text {
+            return;
+        }
+        doc.inline_values = lines
+            .into_iter()
+            .map(|(line, labels)| {
+                InlineAnnotation::new(
+                    line_end_char_index(&slice, line),
+                    format!("  {}", labels.join(", ")),
+                )
+            })
+            .collect();
+    }
+
     pub async fn handle_debugger_message(
         &mut self,
         id: DebugAdapterId,
@@ -215,6 +736,8 @@ pub async fn handle_debugger_message(
                         }
 
                         self.set_status(status);
+                        self.update_inline_values();
+                        self.update_debug_panel().await;
                     }
                     Event::Continued(events::ContinuedBody { thread_id, .. }) => {
                         let debugger = match self.debug_adapters.get_client_mut(id) {
@@ -227,6 +750,7 @@ pub async fn handle_debugger_message(
                             .insert(thread_id, "running".to_owned());
                         if debugger.thread_id == Some(thread_id) {
                             debugger.resume_application();
+                            self.clear_inline_values();
                         }
                     }
                     Event::Thread(thread) => {
@@ -319,18 +843,20 @@ pub async fn handle_debugger_message(
                     Event::Output(events::OutputBody {
                         category, output, ..
                     }) => {
-                        let prefix = match category {
-                            Some(category) => {
-                                if &category == "telemetry" {
-                                    return false;
-                                }
-                                format!("Debug ({}):", category)
-                            }
+                        let prefix = match category.as_deref() {
+                            Some("telemetry") => return false,
+                            Some(category) => format!("Debug ({}):", category),
                             None => "Debug:".to_owned(),
                         };
 
                         log::info!("{}", output);
-                        self.set_status(format!("{} {}", prefix, output));
+                        let kind = ConsoleLineKind::from_category(category.as_deref());
+                        self.debug_console.push_output(kind, &output);
+                        // The output is only shown in the statusline if it would go unnoticed
+                        // otherwise.
+                        if !self.debug_console.visible || category.as_deref() == Some("important") {
+                            self.set_status(format!("{} {}", prefix, output));
+                        }
                     }
                     Event::ProgressStart(body) => {
                         let status = {
@@ -384,6 +910,15 @@ pub async fn handle_debugger_message(
                             // TODO: call futures in parallel, await all
                             let _ = breakpoints_changed(debugger, path.clone(), breakpoints);
                         }
+                        if !self.function_breakpoints.is_empty() {
+                            let _ = function_breakpoints_changed(
+                                debugger,
+                                &mut self.function_breakpoints,
+                            );
+                        }
+                        if !self.data_breakpoints.is_empty() {
+                            let _ = data_breakpoints_changed(debugger, &mut self.data_breakpoints);
+                        }
                         // TODO: fetch breakpoints (in case we're attaching)
 
                         if let Err(err) = debugger.configuration_done().await {
@@ -395,6 +930,8 @@ pub async fn handle_debugger_message(
                         self.debug_adapters.set_active_client(id);
                     }
                     Event::Terminated(terminated) => {
+                        self.clear_inline_values();
+                        self.debug_panel.clear();
                         let debugger = match self.debug_adapters.get_client_mut(id) {
                             Some(debugger) => debugger,
                             None => return false,
@@ -439,6 +976,16 @@ pub async fn handle_debugger_message(
                                         breakpoint.verified = false;
                                     }
                                 }
+                                for breakpoint in &mut self.function_breakpoints {
+                                    breakpoint.verified = false;
+                                }
+                                for breakpoint in &mut self.data_breakpoints {
+                                    breakpoint.verified = false;
+                                }
+                                // Instruction references and most data ids are only valid in
+                                // the session they were set in.
+                                self.instruction_breakpoints.clear();
+                                self.data_breakpoints.retain(|b| b.can_persist);
                             }
                             Some(val) => {
                                 log::info!("Attempting to restart debug session.");
@@ -17,6 +17,11 @@
 
 pub struct DocumentColorsEvent(pub DocumentId);
 pub struct DocumentLinksEvent(pub DocumentId);
+pub struct FoldingRangesEvent(pub DocumentId);
+pub struct SemanticTokensEvent(pub DocumentId);
+pub struct CodeLensEvent(pub DocumentId);
+/// Requests the inline values of the active stack frame of the debugger.
+pub struct InlineValuesEvent;
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum SignatureHelpInvoked {
@@ -40,6 +45,24 @@ pub struct PullAllDocumentsDiagnosticsEvent {
     pub language_servers: HashSet<LanguageServerId>,
 }
 
+pub struct LinkedEditingEvent {
+    pub document_id: DocumentId,
+    pub view_id: ViewId,
+}
+
+pub enum InlineCompletionEvent {
+    /// Request inline completions after the cursor or document changed in insert mode.
+    Trigger {
+        document_id: DocumentId,
+        view_id: ViewId,
+    },
+    /// Request inline completions right away, e.g. to look for more candidates.
+    Invoked {
+        document_id: DocumentId,
+        view_id: ViewId,
+    },
+}
+
 pub struct CodeActionHintEvent {
     pub document_id: DocumentId,
     pub view_id: ViewId,
@@ -0,0 +1,307 @@
+//! Debug launch configurations of the workspace, read from `.helix/debug.toml` and VS Code's
+//! `.vscode/launch.json`. They are offered next to the templates of the `debugger` sections in
+//! `languages.toml`.
+
+use std::collections::HashMap;
+
+use anyhow::{bail, Context as _};
+use helix_core::syntax::config::DebugTemplate;
+use helix_loader::workspace_trust::{TrustQuery, WorkspaceTrust};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+use crate::{expansion, Editor};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LaunchConfigSource {
+    #[default]
+    DebugToml,
+    LaunchJson,
+}
+
+impl LaunchConfigSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::DebugToml => ".helix/debug.toml",
+            Self::LaunchJson => ".vscode/launch.json",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct LaunchConfig {
+    pub name: String,
+    /// The debug adapter, matched against the names of the `debugger` sections in
+    /// `languages.toml` and the names of the languages. Defaults to the debug adapter of the
+    /// language of the current document.
+    #[serde(rename = "type", default)]
+    pub adapter: Option<String>,
+    /// `launch` or `attach`.
+    pub request: String,
+    /// The remaining keys, which are passed to the debug adapter.
+    #[serde(flatten)]
+    pub args: Map<String, Value>,
+    #[serde(skip)]
+    pub source: LaunchConfigSource,
+}
+
+#[derive(Debug, Deserialize)]
+struct LaunchConfigs {
+    #[serde(default)]
+    configurations: Vec<LaunchConfig>,
+}
+
+/// Keys of VS Code launch configurations that are meant for the editor rather than the debug
+/// adapter.
+const EDITOR_KEYS: &[&str] = &[
+    "preLaunchTask",
+    "postDebugTask",
+    "presentation",
+    "internalConsoleOptions",
+    "debugServer",
+    "serverReadyAction",
+];
+
+/// The key of the platform specific overrides of a VS Code launch configuration.
+const PLATFORM_KEY: &str = if cfg!(windows) {
+    "windows"
+} else if cfg!(target_os = "macos") {
+    "osx"
+} else {
+    "linux"
+};
+
+impl LaunchConfig {
+    /// Applies the overrides for the current platform and drops the keys not meant for the debug
+    /// adapter.
+    fn normalize(mut self, source: LaunchConfigSource) -> Self {
+        if let Some(Value::Object(overrides)) = self.args.remove(PLATFORM_KEY) {
+            self.args.extend(overrides);
+        }
+        for key in ["windows", "osx", "linux"].iter().chain(EDITOR_KEYS) {
+            self.args.remove(*key);
+        }
+        self.source = source;
+        self
+    }
+
+    /// Converts the configuration to a debug template, expanding the variables (e.g. `${file}`)
+    /// in its values.
+    pub fn template(&self, editor: &Editor) -> anyhow::Result<DebugTemplate> {
+        let args = self
+            .args
+            .iter()
+            .map(|(key, value)| Ok((key.clone(), expand_value(editor, value)?)))
+            .collect::<anyhow::Result<HashMap<_, _>>>()?;
+        Ok(DebugTemplate {
+            name: self.name.clone(),
+            request: self.request.clone(),
+            completion: Vec::new(),
+            args,
+        })
+    }
+}
+
+fn expand_value(editor: &Editor, value: &Value) -> anyhow::Result<Value> {
+    Ok(match value {
+        Value::String(string) => Value::String(expansion::expand_launch_variables(editor, string)?),
+        Value::Array(values) => Value::Array(
+            values
+                .iter()
+                .map(|value| expand_value(editor, value))
+                .collect::<anyhow::Result<_>>()?,
+        ),
+        Value::Object(object) => Value::Object(
+            object
+                .iter()
+                .map(|(key, value)| Ok((key.clone(), expand_value(editor, value)?)))
+                .collect::<anyhow::Result<_>>()?,
+        ),
+        value => value.clone(),
+    })
+}
+
+/// Parses a `.vscode/launch.json` file, which may contain comments and trailing commas.
+pub fn parse_launch_json(text: &str) -> anyhow::Result<Vec<LaunchConfig>> {
+    let configs: LaunchConfigs = serde_json::from_str(&strip_jsonc(text))?;
+    Ok(configs
+        .configurations
+        .into_iter()
+        .map(|config| config.normalize(LaunchConfigSource::LaunchJson))
+        .collect())
+}
+
+/// Parses a `.helix/debug.toml` file, which has the same structure as a `launch.json` file:
+///
+/// ```toml
+/// [[configurations]]
+/// name = "Debug tests"
+/// type = "lldb-dap"
+/// request = "launch"
+/// program = "${workspaceFolder}/target/debug/deps/foo"
+/// ```
+pub fn parse_debug_toml(text: &str) -> anyhow::Result<Vec<LaunchConfig>> {
+    let configs: LaunchConfigs = toml::from_str(text)?;
+    Ok(configs
+        .configurations
+        .into_iter()
+        .map(|config| config.normalize(LaunchConfigSource::DebugToml))
+        .collect())
+}
+
+/// Reads the launch configurations of the current workspace. Fails if the workspace has launch
+/// configurations but isn't trusted to load its local configuration.
+pub fn load(trust: &WorkspaceTrust) -> anyhow::Result<Vec<LaunchConfig>> {
+    let files: Vec<_> = [
+        (
+            LaunchConfigSource::DebugToml,
+            helix_loader::workspace_debug_config_file(),
+        ),
+        (
+            LaunchConfigSource::LaunchJson,
+            helix_loader::workspace_launch_json_file(),
+        ),
+    ]
+    .into_iter()
+    .filter(|(_, path)| path.exists())
+    .collect();
+    if files.is_empty() {
+        return Ok(Vec::new());
+    }
+    if !trust.query_current(TrustQuery::LocalConfig).is_trusted() {
+        bail!("Workspace is not trusted, its launch configurations are ignored. Run `:workspace-trust` to load them.");
+    }
+
+    let mut configs = Vec::new();
+    for (source, path) in files {
+        let text = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let parsed = match source {
+            LaunchConfigSource::DebugToml => parse_debug_toml(&text),
+            LaunchConfigSource::LaunchJson => parse_launch_json(&text),
+        }
+        .with_context(|| format!("Failed to parse {}", path.display()))?;
+        configs.extend(parsed);
+    }
+    Ok(configs)
+}
+
+/// Strips the comments and trailing commas allowed in VS Code's JSON files.
+fn strip_jsonc(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    // The length of `stripped` at the last comma outside of a string, which is removed if only
+    // whitespace and comments follow it before the end of the object or array.
+    let mut comma = None;
+    while let Some(ch) = chars.next() {
+        match ch {
+            '"' => {
+                comma = None;
+                stripped.push(ch);
+                while let Some(ch) = chars.next() {
+                    stripped.push(ch);
+                    match ch {
+                        '\\' => stripped.extend(chars.next()),
+                        '"' => break,
+                        _ => (),
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'/') => {
+                for ch in chars.by_ref() {
+                    if ch == '\n' {
+                        stripped.push(ch);
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut prev = None;
+                for ch in chars.by_ref() {
+                    if prev == Some('*') && ch == '/' {
+                        break;
+                    }
+                    prev = Some(ch);
+                }
+            }
+            ',' => {
+                comma = Some(stripped.len());
+                stripped.push(ch);
+            }
+            '}' | ']' => {
+                if let Some(comma) = comma.take() {
+                    stripped.remove(comma);
+                }
+                stripped.push(ch);
+            }
+            ch if ch.is_whitespace() => stripped.push(ch),
+            ch => {
+                comma = None;
+                stripped.push(ch);
+            }
+        }
+    }
+    stripped
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn launch_json() {
+        let text = r#"{
+            // Use IntelliSense to learn about possible attributes.
+            "version": "0.2.0",
+            "configurations": [
+                {
+                    "type": "lldb",
+                    "request": "launch",
+                    "name": "Debug // not a comment",
+                    "program": "${workspaceFolder}/target/debug/foo",
+                    "args": ["--flag", /* inline */ "\"quoted\""],
+                    "preLaunchTask": "cargo build",
+                    "linux": { "program": "${workspaceFolder}/foo" },
+                    "windows": { "program": "foo.exe" },
+                },
+            ],
+        }"#;
+        let configs = parse_launch_json(text).unwrap();
+        assert_eq!(configs.len(), 1);
+        let config = &configs[0];
+        assert_eq!(config.name, "Debug // not a comment");
+        assert_eq!(config.adapter.as_deref(), Some("lldb"));
+        assert_eq!(config.request, "launch");
+        assert_eq!(config.source, LaunchConfigSource::LaunchJson);
+        assert_eq!(
+            config.args["args"],
+            serde_json::json!(["--flag", "\"quoted\""])
+        );
+        let mut keys: Vec<_> = config.args.keys().map(String::as_str).collect();
+        keys.sort_unstable();
+        assert_eq!(keys, ["args", "program"]);
+        if cfg!(target_os = "linux") {
+            assert_eq!(config.args["program"], "${workspaceFolder}/foo");
+        }
+    }
+
+    #[test]
+    fn debug_toml() {
+        let text = r#"
+            [[configurations]]
+            name = "Attach"
+            request = "attach"
+            pid = 1234
+
+            [configurations.env]
+            RUST_LOG = "debug"
+        "#;
+        let configs = parse_debug_toml(text).unwrap();
+        assert_eq!(configs.len(), 1);
+        assert_eq!(configs[0].adapter, None);
+        assert_eq!(configs[0].source, LaunchConfigSource::DebugToml);
+        assert_eq!(configs[0].args["pid"], 1234);
+        assert_eq!(configs[0].args["env"]["RUST_LOG"], "debug");
+    }
+}
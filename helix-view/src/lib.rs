@@ -4,6 +4,8 @@
 pub mod action;
 pub mod annotations;
 pub mod clipboard;
+pub mod debug_console;
+pub mod debug_panel;
 pub mod document;
 pub mod editor;
 pub mod events;
@@ -14,6 +16,8 @@
 pub mod info;
 pub mod input;
 pub mod keyboard;
+pub mod launch_config;
+pub mod recorded_macros;
 pub mod register;
 pub mod session;
 pub mod theme;
@@ -0,0 +1,122 @@
+//! Macros recorded with `record_macro`: persisted between sessions and editable as text in a
+//! scratch buffer opened with `:macro-edit`.
+
+use anyhow::{anyhow, Context as _};
+use helix_core::Rope;
+
+use crate::{editor::Action, input, session::SessionMacros, Document, DocumentId, Editor};
+
+/// Whether the macro in `register` is saved on exit. Special registers like the clipboard are
+/// left out, as restoring them on startup would overwrite their contents.
+fn is_persistent(register: char) -> bool {
+    register == '@' || register.is_alphanumeric()
+}
+
+/// Converts the text of a macro buffer to a macro. Line breaks only split up long macros, an
+/// Enter key has to be written as `<ret>`.
+fn macro_from_text(text: &str) -> String {
+    text.lines().collect()
+}
+
+impl Editor {
+    /// Writes `keys` to `register` and remembers it as a macro, so it's saved on exit.
+    pub fn set_macro(&mut self, register: char, keys: String) -> anyhow::Result<()> {
+        self.registers.write(register, vec![keys.clone()])?;
+        self.macros.insert(register, keys);
+        Ok(())
+    }
+
+    /// Restores the macros saved on the last exit to their registers.
+    pub fn restore_macros(&mut self) {
+        let macros = match SessionMacros::load() {
+            Ok(macros) => macros,
+            Err(err) => {
+                log::warn!("Failed to restore macros: {err:#}");
+                return;
+            }
+        };
+        for (register, keys) in macros.0 {
+            if let Err(err) = self.set_macro(register, keys) {
+                log::warn!("Failed to restore macro to register [{register}]: {err}");
+            }
+        }
+    }
+
+    /// Saves the recorded macros so they can be restored on the next start. Macros whose
+    /// register has been overwritten since, for example by a yank, are left out.
+    pub fn save_macros(&self) {
+        let macros = SessionMacros(
+            self.macros
+                .iter()
+                .filter(|(register, keys)| {
+                    is_persistent(**register)
+                        && self
+                            .registers
+                            .read(**register, self)
+                            .is_some_and(|values| values.eq([keys.as_str()]))
+                })
+                .map(|(register, keys)| (*register, keys.clone()))
+                .collect(),
+        );
+        if let Err(err) = macros.save() {
+            log::warn!("Failed to save macros: {err:#}");
+        }
+    }
+
+    /// Opens the macro in `register` in a scratch buffer. Writing the buffer stores the edited
+    /// macro back to the register.
+    pub fn edit_macro(&mut self, register: char, action: Action) {
+        let keys = self
+            .registers
+            .read(register, self)
+            .and_then(|mut values| values.next())
+            .map(|keys| keys.into_owned())
+            .unwrap_or_default();
+        let doc = Document::from(
+            Rope::from(keys),
+            None,
+            self.config.clone(),
+            self.syn_loader.clone(),
+        );
+        let doc_id = self.new_file_from_document(action, doc);
+        self.macro_buffers.insert(doc_id, register);
+        self.set_status(format!(
+            "Editing macro in register [{register}], write the buffer to save it"
+        ));
+    }
+
+    /// Stores the text of a buffer opened with `:macro-edit` as the macro of its register.
+    pub fn write_macro_buffer(&mut self, doc_id: DocumentId) -> anyhow::Result<()> {
+        let register = *self
+            .macro_buffers
+            .get(&doc_id)
+            .ok_or_else(|| anyhow!("Not a macro buffer"))?;
+        let keys = macro_from_text(&doc!(self, &doc_id).text().to_string());
+        input::parse_macro(&keys).context("Invalid macro")?;
+        self.set_macro(register, keys)?;
+        doc_mut!(self, &doc_id).reset_modified();
+        self.set_status(format!("Saved macro to register [{register}]"));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn persistent_registers() {
+        assert!(is_persistent('@'));
+        assert!(is_persistent('q'));
+        assert!(is_persistent('1'));
+        assert!(!is_persistent('+'));
+        assert!(!is_persistent('"'));
+    }
+
+    #[test]
+    fn text_to_macro() {
+        assert_eq!(macro_from_text("miw<A-d>\n"), "miw<A-d>");
+        assert_eq!(macro_from_text("xd\r\n<ret>jj\n"), "xd<ret>jj");
+        assert_eq!(macro_from_text(""), "");
+    }
+}
@@ -1,4 +1,8 @@
-use std::{borrow::Cow, collections::HashMap, iter};
+use std::{
+    borrow::Cow,
+    collections::{HashMap, VecDeque},
+    iter,
+};
 
 use anyhow::Result;
 use arc_swap::access::DynAccess;
@@ -6,6 +10,7 @@
 
 use crate::{
     clipboard::{ClipboardError, ClipboardProvider, ClipboardType},
+    session::SessionRegisters,
     Editor,
 };
 
@@ -29,14 +34,22 @@ pub struct Registers {
     inner: HashMap<char, Vec<String>>,
     clipboard_provider: Box<dyn DynAccess<ClipboardProvider>>,
     pub last_search_register: char,
+    /// The values of the last yanks and deletions, most recent first.
+    yank_history: VecDeque<Vec<String>>,
+    yank_history_size: Box<dyn DynAccess<usize>>,
 }
 
 impl Registers {
-    pub fn new(clipboard_provider: Box<dyn DynAccess<ClipboardProvider>>) -> Self {
+    pub fn new(
+        clipboard_provider: Box<dyn DynAccess<ClipboardProvider>>,
+        yank_history_size: Box<dyn DynAccess<usize>>,
+    ) -> Self {
         Self {
             inner: Default::default(),
             clipboard_provider,
             last_search_register: '/',
+            yank_history: VecDeque::new(),
+            yank_history_size,
         }
     }
 
@@ -102,6 +115,38 @@ pub fn write(&mut self, name: char, mut values: Vec<String>) -> Result<()> {
         }
     }
 
+    /// Writes the yanked or deleted `values` to the register `name` and adds them to the yank
+    /// history.
+    pub fn yank(&mut self, name: char, values: Vec<String>) -> Result<()> {
+        if name == '_' {
+            return Ok(());
+        }
+        self.write(name, values.clone())?;
+        self.push_yank_history(values);
+        Ok(())
+    }
+
+    fn push_yank_history(&mut self, values: Vec<String>) {
+        let size = *self.yank_history_size.load();
+        if size == 0 || values.iter().all(String::is_empty) {
+            return;
+        }
+        self.yank_history.retain(|entry| *entry != values);
+        self.yank_history.push_front(values);
+        self.yank_history.truncate(size);
+    }
+
+    /// The values of the last yanks and deletions, most recent first.
+    pub fn yank_history(&self) -> impl ExactSizeIterator<Item = &[String]> {
+        self.yank_history.iter().map(Vec::as_slice)
+    }
+
+    /// Replaces the yank history, e.g. with the one saved on the last exit.
+    pub fn set_yank_history(&mut self, history: Vec<Vec<String>>) {
+        self.yank_history = history.into();
+        self.yank_history.truncate(*self.yank_history_size.load());
+    }
+
     fn push_impl(&mut self, name: char, mut value: String, unique: bool) -> Result<()> {
         match name {
             '_' => Ok(()),
@@ -234,6 +279,62 @@ pub fn clipboard_provider_name(&self) -> String {
     }
 }
 
+impl Editor {
+    /// Restores the registers and the yank history saved on the last exit.
+    pub fn restore_registers(&mut self) {
+        let saved = match SessionRegisters::load() {
+            Ok(saved) => saved,
+            Err(err) => {
+                log::warn!("Failed to restore registers: {err:#}");
+                return;
+            }
+        };
+        let session = &self.config().session;
+        for (name, values) in saved.registers {
+            if !session.persist_registers.contains(&name) {
+                continue;
+            }
+            if let Err(err) = self.registers.write(name, values) {
+                log::warn!("Failed to restore register {name}: {err}");
+            }
+        }
+        if session.persist_yank_history {
+            self.registers.set_yank_history(saved.yank_history);
+        }
+    }
+
+    /// Saves the registers of `session.persist-registers` and, if enabled, the yank history so
+    /// they can be restored on the next start.
+    pub fn save_registers(&self) {
+        let session = &self.config().session;
+        let registers = session
+            .persist_registers
+            .iter()
+            .filter(|name| !matches!(name, '_' | '#' | '.' | '%' | '*' | '+'))
+            .filter_map(|&name| {
+                let values: Vec<_> = self
+                    .registers
+                    .read(name, self)?
+                    .map(|value| value.into_owned())
+                    .collect();
+                Some((name, values))
+            })
+            .collect();
+        let yank_history = if session.persist_yank_history {
+            self.registers.yank_history().map(<[_]>::to_vec).collect()
+        } else {
+            Vec::new()
+        };
+        let saved = SessionRegisters {
+            registers,
+            yank_history,
+        };
+        if let Err(err) = saved.save() {
+            log::warn!("Failed to save registers: {err:#}");
+        }
+    }
+}
+
 fn read_from_clipboard<'a>(
     provider: &ClipboardProvider,
     saved_values: Option<&'a Vec<String>>,
@@ -348,3 +449,39 @@ fn len(&self) -> usize {
 trait DoubleEndedExactSizeIterator: DoubleEndedIterator + ExactSizeIterator {}
 
 impl<I: DoubleEndedIterator + ExactSizeIterator> DoubleEndedExactSizeIterator for I {}
+
+#[cfg(test)]
+mod test {
+    use arc_swap::access::Constant;
+
+    use super::*;
+
+    fn history(registers: &Registers) -> Vec<Vec<&str>> {
+        registers
+            .yank_history()
+            .map(|values| values.iter().map(String::as_str).collect())
+            .collect()
+    }
+
+    #[test]
+    fn yank_history() {
+        let mut registers = Registers::new(
+            Box::new(Constant(ClipboardProvider::None)),
+            Box::new(Constant(3)),
+        );
+        let yank = |registers: &mut Registers, values: &[&str]| {
+            registers
+                .yank('"', values.iter().map(|value| value.to_string()).collect())
+                .unwrap()
+        };
+        yank(&mut registers, &["a"]);
+        yank(&mut registers, &["b", "c"]);
+        yank(&mut registers, &[""]);
+        yank(&mut registers, &["d"]);
+        yank(&mut registers, &["a"]);
+        registers.yank('_', vec!["e".to_string()]).unwrap();
+        assert_eq!(history(&registers), [vec!["a"], vec!["d"], vec!["b", "c"]]);
+        yank(&mut registers, &["f"]);
+        assert_eq!(history(&registers), [vec!["f"], vec!["a"], vec!["d"]]);
+    }
+}
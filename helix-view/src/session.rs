@@ -1,10 +1,17 @@
+use crate::editor::{Breakpoint, DataBreakpoint, FunctionBreakpoint};
+use crate::tree::Layout;
+use anyhow::{bail, Context};
 use helix_core::time::now_timestamp;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::path::Path;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
 
 const MAX_ENTRIES: usize = 1000;
 const FILE_NAME: &str = "sessions.json";
+const SESSIONS_DIR: &str = "sessions";
+const BREAKPOINTS_FILE_NAME: &str = "breakpoints.json";
+const MACROS_FILE_NAME: &str = "macros.json";
+const REGISTERS_FILE_NAME: &str = "registers.json";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FileState {
@@ -120,6 +127,257 @@ fn prune(&mut self) {
     }
 }
 
+/// A selection range stored as line/column coordinates so that it survives edits made to the
+/// file outside of the editor.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionRange {
+    pub anchor_row: usize,
+    pub anchor_col: usize,
+    pub head_row: usize,
+    pub head_col: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionJump {
+    pub path: PathBuf,
+    #[serde(flatten)]
+    pub range: SessionRange,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionView {
+    /// The file shown in the view, `None` for a scratch buffer.
+    pub path: Option<PathBuf>,
+    #[serde(flatten)]
+    pub range: SessionRange,
+    pub view_anchor_row: usize,
+    pub view_anchor_col: usize,
+    pub horizontal_offset: usize,
+    /// Jumplist entries from oldest to newest. Jumps into scratch buffers are not saved.
+    #[serde(default)]
+    pub jumps: Vec<SessionJump>,
+    #[serde(default)]
+    pub focused: bool,
+}
+
+/// The split layout of the view tree, mirroring the nodes of [`crate::tree::Tree`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionLayout {
+    View(SessionView),
+    Container {
+        layout: Layout,
+        children: Vec<SessionLayout>,
+    },
+}
+
+/// A named snapshot of the editor: working directory, open buffers, splits, jumplists and
+/// registers. Sessions are stored as `<cache dir>/sessions/<name>.json`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Session {
+    pub cwd: PathBuf,
+    /// All open files, in the order they were opened.
+    pub buffers: Vec<PathBuf>,
+    pub layout: SessionLayout,
+    /// Register contents, with values in the order they are read.
+    #[serde(default)]
+    pub registers: HashMap<char, Vec<String>>,
+    pub timestamp: u64,
+}
+
+impl Session {
+    pub fn dir() -> PathBuf {
+        helix_loader::cache_dir().join(SESSIONS_DIR)
+    }
+
+    fn path(name: &str) -> anyhow::Result<PathBuf> {
+        validate_name(name)?;
+        Ok(Self::dir().join(format!("{name}.json")))
+    }
+
+    pub fn load(name: &str) -> anyhow::Result<Self> {
+        let path = Self::path(name)?;
+        let contents = match std::fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                bail!("Session '{name}' does not exist")
+            }
+            Err(err) => {
+                return Err(err).with_context(|| format!("Failed to read {}", path.display()))
+            }
+        };
+        serde_json::from_str(&contents)
+            .with_context(|| format!("Failed to parse session from {}", path.display()))
+    }
+
+    pub fn save(&self, name: &str) -> anyhow::Result<()> {
+        let path = Self::path(name)?;
+        let dir = Self::dir();
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+        let contents = serde_json::to_string_pretty(self)?;
+        std::fs::write(&path, contents)
+            .with_context(|| format!("Failed to write session to {}", path.display()))
+    }
+
+    pub fn delete(name: &str) -> anyhow::Result<()> {
+        let path = Self::path(name)?;
+        match std::fs::remove_file(&path) {
+            Ok(()) => Ok(()),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                bail!("Session '{name}' does not exist")
+            }
+            Err(err) => Err(err).with_context(|| format!("Failed to delete {}", path.display())),
+        }
+    }
+
+    /// Returns the names of all saved sessions, sorted alphabetically.
+    pub fn list() -> Vec<String> {
+        let Ok(entries) = std::fs::read_dir(Self::dir()) else {
+            return Vec::new();
+        };
+        let mut names: Vec<_> = entries
+            .filter_map(|entry| {
+                let path = entry.ok()?.path();
+                if path.extension()? != "json" {
+                    return None;
+                }
+                Some(path.file_stem()?.to_str()?.to_owned())
+            })
+            .collect();
+        names.sort();
+        names
+    }
+}
+
+/// The breakpoints of a workspace, restored when the editor is started in it again. The
+/// breakpoints of all workspaces are stored in `<cache dir>/breakpoints.json`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SessionBreakpoints {
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub source: BTreeMap<PathBuf, Vec<Breakpoint>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub function: Vec<FunctionBreakpoint>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub data: Vec<DataBreakpoint>,
+}
+
+impl SessionBreakpoints {
+    fn path() -> PathBuf {
+        helix_loader::cache_dir().join(BREAKPOINTS_FILE_NAME)
+    }
+
+    fn load_all() -> anyhow::Result<BTreeMap<PathBuf, Self>> {
+        let path = Self::path();
+        match std::fs::read_to_string(&path) {
+            Ok(contents) => serde_json::from_str(&contents)
+                .with_context(|| format!("Failed to parse breakpoints from {}", path.display())),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(BTreeMap::new()),
+            Err(err) => Err(err).with_context(|| format!("Failed to read {}", path.display())),
+        }
+    }
+
+    pub fn load(workspace: &Path) -> anyhow::Result<Self> {
+        Ok(Self::load_all()?.remove(workspace).unwrap_or_default())
+    }
+
+    /// Replaces the saved breakpoints of `workspace`.
+    pub fn save(self, workspace: &Path) -> anyhow::Result<()> {
+        let mut all = Self::load_all()?;
+        let changed = if self.is_empty() {
+            all.remove(workspace).is_some()
+        } else {
+            all.insert(workspace.to_path_buf(), self);
+            true
+        };
+        if !changed {
+            return Ok(());
+        }
+        let path = Self::path();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let contents = serde_json::to_string_pretty(&all)?;
+        std::fs::write(&path, contents)
+            .with_context(|| format!("Failed to write breakpoints to {}", path.display()))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.source.values().all(Vec::is_empty) && self.function.is_empty() && self.data.is_empty()
+    }
+}
+
+/// Recorded macros by register, restored on startup. They are stored in
+/// `<cache dir>/macros.json`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct SessionMacros(pub BTreeMap<char, String>);
+
+impl SessionMacros {
+    pub fn load() -> anyhow::Result<Self> {
+        read_cache_file(MACROS_FILE_NAME, "macros")
+    }
+
+    pub fn save(&self) -> anyhow::Result<()> {
+        write_cache_file(MACROS_FILE_NAME, "macros", self)
+    }
+}
+
+/// Register contents and the yank history saved on exit and restored on startup. They are
+/// stored in `<cache dir>/registers.json`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionRegisters {
+    /// Register contents, with values in the order they are read.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub registers: BTreeMap<char, Vec<String>>,
+    /// The values of the last yanks and deletions, most recent first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub yank_history: Vec<Vec<String>>,
+}
+
+impl SessionRegisters {
+    pub fn load() -> anyhow::Result<Self> {
+        read_cache_file(REGISTERS_FILE_NAME, "registers")
+    }
+
+    pub fn save(&self) -> anyhow::Result<()> {
+        write_cache_file(REGISTERS_FILE_NAME, "registers", self)
+    }
+}
+
+/// Reads `<cache dir>/<file_name>`, returning the default value if it doesn't exist.
+fn read_cache_file<T: Default + for<'de> Deserialize<'de>>(
+    file_name: &str,
+    what: &str,
+) -> anyhow::Result<T> {
+    let path = helix_loader::cache_dir().join(file_name);
+    match std::fs::read_to_string(&path) {
+        Ok(contents) => serde_json::from_str(&contents)
+            .with_context(|| format!("Failed to parse {what} from {}", path.display())),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(T::default()),
+        Err(err) => Err(err).with_context(|| format!("Failed to read {}", path.display())),
+    }
+}
+
+fn write_cache_file<T: Serialize>(file_name: &str, what: &str, value: &T) -> anyhow::Result<()> {
+    let path = helix_loader::cache_dir().join(file_name);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    let contents = serde_json::to_string_pretty(value)?;
+    std::fs::write(&path, contents)
+        .with_context(|| format!("Failed to write {what} to {}", path.display()))
+}
+
+fn validate_name(name: &str) -> anyhow::Result<()> {
+    if name.is_empty() || name.starts_with('.') || name.contains(['/', '\\']) {
+        bail!("Invalid session name '{name}'");
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -150,6 +408,54 @@ fn test_roundtrip_serialization() {
         assert_eq!(file_state.head_col, 5);
     }
 
+    #[test]
+    fn test_breakpoints_serialization() {
+        let json = r#"{"source":{"/tmp/test.rs":[{"line":3,"hit_condition":"5"},{"line":7,"enabled":false}]},"function":[{"name":"main"}]}"#;
+        let breakpoints: SessionBreakpoints = serde_json::from_str(json).unwrap();
+        let source = &breakpoints.source[Path::new("/tmp/test.rs")];
+        assert_eq!(source[0].line, 3);
+        assert_eq!(source[0].hit_condition.as_deref(), Some("5"));
+        assert!(source[0].enabled);
+        assert!(!source[1].enabled);
+        assert_eq!(breakpoints.function[0].name, "main");
+        assert!(breakpoints.function[0].enabled);
+        assert!(breakpoints.data.is_empty());
+
+        let mut verified = breakpoints.clone();
+        verified.source.get_mut(Path::new("/tmp/test.rs")).unwrap()[0].verified = true;
+        assert_eq!(
+            serde_json::to_string(&verified).unwrap(),
+            serde_json::to_string(&breakpoints).unwrap()
+        );
+        assert!(SessionBreakpoints::default().is_empty());
+    }
+
+    #[test]
+    fn test_macros_serialization() {
+        let mut macros = SessionMacros::default();
+        macros.0.insert('q', "miw<A-d>".to_string());
+        macros.0.insert('@', "x".to_string());
+        let json = serde_json::to_string(&macros).unwrap();
+        assert_eq!(json, r#"{"@":"x","q":"miw<A-d>"}"#);
+        assert_eq!(
+            serde_json::from_str::<SessionMacros>(&json).unwrap(),
+            macros
+        );
+    }
+
+    #[test]
+    fn test_registers_serialization() {
+        assert_eq!(
+            serde_json::to_string(&SessionRegisters::default()).unwrap(),
+            "{}"
+        );
+        let json = r#"{"registers":{"a":["one","two"]},"yank_history":[["x"],["y","z"]]}"#;
+        let registers: SessionRegisters = serde_json::from_str(json).unwrap();
+        assert_eq!(registers.registers[&'a'], ["one", "two"]);
+        assert_eq!(registers.yank_history, [vec!["x"], vec!["y", "z"]]);
+        assert_eq!(serde_json::to_string(&registers).unwrap(), json);
+    }
+
     #[test]
     fn test_get_set() {
         let mut state = SessionState::default();
@@ -348,4 +654,57 @@ fn test_load_missing_file() {
         let state = SessionState::load();
         assert!(state.files.is_empty());
     }
+
+    #[test]
+    fn test_session_roundtrip_serialization() {
+        let view = |path: Option<&str>, focused| {
+            SessionLayout::View(SessionView {
+                path: path.map(PathBuf::from),
+                range: SessionRange {
+                    anchor_row: 3,
+                    anchor_col: 1,
+                    head_row: 4,
+                    head_col: 0,
+                },
+                view_anchor_row: 2,
+                view_anchor_col: 0,
+                horizontal_offset: 0,
+                jumps: vec![SessionJump {
+                    path: PathBuf::from("/tmp/a.rs"),
+                    range: SessionRange::default(),
+                }],
+                focused,
+            })
+        };
+        let session = Session {
+            cwd: PathBuf::from("/tmp"),
+            buffers: vec![PathBuf::from("/tmp/a.rs"), PathBuf::from("/tmp/b.rs")],
+            layout: SessionLayout::Container {
+                layout: Layout::Vertical,
+                children: vec![
+                    view(Some("/tmp/a.rs"), false),
+                    SessionLayout::Container {
+                        layout: Layout::Horizontal,
+                        children: vec![view(Some("/tmp/b.rs"), true), view(None, false)],
+                    },
+                ],
+            },
+            registers: HashMap::from([('"', vec!["foo".to_owned(), "bar".to_owned()])]),
+            timestamp: 1000,
+        };
+
+        let json = serde_json::to_string(&session).unwrap();
+        let deserialized: Session = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized, session);
+    }
+
+    #[test]
+    fn test_session_name_validation() {
+        assert!(validate_name("work").is_ok());
+        assert!(validate_name("my-project.v2").is_ok());
+        assert!(validate_name("").is_err());
+        assert!(validate_name("..").is_err());
+        assert!(validate_name("a/b").is_err());
+        assert!(validate_name("a\\b").is_err());
+    }
 }
@@ -1,4 +1,5 @@
 use crate::{graphics::Rect, View, ViewId};
+use serde::{Deserialize, Serialize};
 use slotmap::SlotMap;
 
 // the dimensions are recomputed on window resize/tree change.
@@ -45,7 +46,8 @@ pub fn view(view: View) -> Self {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Layout {
     Horizontal,
     Vertical,
@@ -441,6 +443,19 @@ pub fn recalculate(&mut self) {
         }
     }
 
+    /// The root container of the tree.
+    pub fn root(&self) -> ViewId {
+        self.root
+    }
+
+    /// Get the layout and children of a container, or `None` if `index` is a view.
+    pub fn container(&self, index: ViewId) -> Option<(Layout, &[ViewId])> {
+        match &self.nodes.get(index)?.content {
+            Content::Container(container) => Some((container.layout, &container.children)),
+            Content::View(_) => None,
+        }
+    }
+
     pub fn traverse(&self) -> Traverse<'_> {
         Traverse::new(self)
     }
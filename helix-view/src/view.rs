@@ -1,7 +1,12 @@
 use crate::{
     align_view,
-    annotations::diagnostics::InlineDiagnostics,
-    document::{DocumentColorSwatches, DocumentInlayHints},
+    annotations::{
+        code_lens::{code_lens_lines, CodeLenses},
+        diagnostics::InlineDiagnostics,
+        hunk_diff::HunkDiffLines,
+        inline_completion::InlineCompletionLines,
+    },
+    document::{DocumentColorSwatches, DocumentFolds, DocumentInlayHints},
     editor::{GutterConfig, GutterType},
     graphics::Rect,
     handlers::diagnostics::DiagnosticsHandler,
@@ -11,12 +16,15 @@
 use helix_core::{
     char_idx_at_visual_offset,
     doc_formatter::TextFormat,
+    line_ending::line_end_char_index,
     text_annotations::TextAnnotations,
     visual_offset_from_anchor, visual_offset_from_block, Position, RopeSlice, Selection,
     Transaction,
     VisualOffsetError::{PosAfterMaxRow, PosBeforeAnchorRow},
 };
 
+use helix_vcs::Hunk;
+
 use std::{
     collections::{HashMap, VecDeque},
     fmt,
@@ -446,6 +454,29 @@ pub fn screen_coords_at_pos(
         Some(pos)
     }
 
+    /// Returns the change under the cursor if the lines of the diff base it replaced are shown
+    /// below it, either with `show_hunk_diff` or because `inline-hunk-diff` is enabled, together
+    /// with the char index of the end of its last line, below which they are shown.
+    pub fn hunk_diff(&self, doc: &Document) -> Option<(Hunk, usize)> {
+        let handle = doc.diff_handle()?;
+        let text = doc.text().slice(..);
+        let cursor = doc.selection(self.id).primary().cursor(text);
+        let diff = handle.load();
+        let idx = diff.hunk_at(text.char_to_line(cursor) as u32, true)?;
+        if !doc.config.load().inline_hunk_diff && doc.hunk_diff != Some((self.id, idx)) {
+            return None;
+        }
+        let hunk = diff.nth_hunk(idx);
+        if hunk.before.is_empty() {
+            return None;
+        }
+        // The diff may lag behind edits that shortened the document.
+        let line = (hunk.after.end as usize)
+            .saturating_sub(1)
+            .min(text.len_lines() - 1);
+        Some((hunk, line_end_char_index(&text, line)))
+    }
+
     /// Get the text annotations to display in the current view for the given document and theme.
     pub fn text_annotations<'a>(
         &self,
@@ -501,6 +532,25 @@ pub fn text_annotations<'a>(
             }
         }
 
+        if let Some(DocumentFolds {
+            visible,
+            placeholders,
+            placeholders_padding,
+            ..
+        }) = doc.folds.get(&self.id)
+        {
+            let style = theme.and_then(|t| t.find_highlight("ui.virtual.fold"));
+            text_annotations
+                .add_inline_annotations(placeholders_padding, None)
+                .add_inline_annotations(placeholders, style)
+                .add_folds(visible);
+        }
+
+        if !doc.inline_values.is_empty() {
+            let style = theme.and_then(|t| t.find_highlight("ui.virtual.inline-value"));
+            text_annotations.add_inline_annotations(&doc.inline_values, style);
+        }
+
         let width = self.inner_width(doc);
         let enable_cursor_line = self
             .diagnostics_handler
@@ -522,6 +572,30 @@ pub fn text_annotations<'a>(
             ));
         }
 
+        if config.lsp.display_code_lenses && !doc.code_lenses.is_empty() {
+            let lines = code_lens_lines(doc, self.id);
+            text_annotations.add_line_annotation(CodeLenses::new(&lines));
+        }
+
+        if let Some(inline_completion) = doc
+            .inline_completion
+            .as_ref()
+            .filter(|inline_completion| inline_completion.view_id == self.id)
+        {
+            let style = theme.and_then(|t| t.find_highlight("ui.virtual.inline-completion"));
+            text_annotations.add_inline_annotations(&inline_completion.ghost_text, style);
+            if !inline_completion.ghost_lines.is_empty() {
+                text_annotations.add_line_annotation(InlineCompletionLines::new(
+                    inline_completion.ghost_lines_anchor,
+                    inline_completion.ghost_lines.len(),
+                ));
+            }
+        }
+
+        if let Some((hunk, anchor)) = self.hunk_diff(doc) {
+            text_annotations.add_line_annotation(HunkDiffLines::new(anchor, hunk.before.len()));
+        }
+
         text_annotations
     }
 
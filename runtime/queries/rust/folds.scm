@@ -0,0 +1,16 @@
+[
+  (function_item)
+  (impl_item)
+  (trait_item)
+  (struct_item)
+  (enum_item)
+  (union_item)
+  (mod_item)
+  (macro_definition)
+  (match_expression)
+  (block)
+  (use_declaration)
+  (call_expression)
+  (closure_expression)
+  (block_comment)
+] @fold